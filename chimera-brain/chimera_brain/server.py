@@ -83,6 +83,63 @@ class VisionServiceImpl(vision_pb2_grpc.VisionServiceServicer):
                 confidence=0.0
             )
 
+    
+    def VerifyCondition(
+        self,
+        request: vision_pb2.VerifyRequest,
+        context: grpc.ServicerContext
+    ) -> vision_pb2.VerifyResponse:
+        """
+        Yes/no check of an objective's success condition against a screenshot.
+        """
+        try:
+            satisfied, confidence = self.processor.verify_condition(
+                request.image,
+                request.condition
+            )
+            logger.info(f"Condition '{request.condition}': {satisfied} (confidence: {confidence})")
+            return vision_pb2.VerifyResponse(satisfied=satisfied, confidence=confidence)
+            
+        except Exception as e:
+            logger.error(f"Error verifying condition: {e}", exc_info=True)
+            context.set_code(grpc.StatusCode.INTERNAL)
+            context.set_details(f"Error verifying condition: {str(e)}")
+            return vision_pb2.VerifyResponse(satisfied=False, confidence=0.0)
+
+    
+    def GetCoordinatesBatch(
+        self,
+        request: vision_pb2.BatchCoordinateRequest,
+        context: grpc.ServicerContext
+    ) -> vision_pb2.BatchCoordinateResponse:
+        """
+        Locate several intents on one screenshot.
+        
+        One result per command, in order; a command that fails is reported
+        as not found without failing the rest.
+        """
+        results = []
+        for text_command in request.text_commands:
+            try:
+                x, y, confidence = self.processor.get_click_coordinates(
+                    request.image,
+                    text_command
+                )
+                results.append(vision_pb2.CoordinateResponse(
+                    found=True,
+                    x=x,
+                    y=y,
+                    width=50,
+                    height=50,
+                    confidence=confidence
+                ))
+            except Exception as e:
+                logger.warning(f"Could not locate '{text_command}': {e}")
+                results.append(vision_pb2.CoordinateResponse(found=False))
+        
+        logger.info(f"Batch located {sum(r.found for r in results)}/{len(results)} intents")
+        return vision_pb2.BatchCoordinateResponse(results=results)
+
 
 def serve(port: int = 50052, use_simple: bool = False):
     """
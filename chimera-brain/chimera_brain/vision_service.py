@@ -113,6 +113,35 @@ class VisualIntentProcessor:
             image = Image.open(io.BytesIO(image_bytes))
             return self._fallback_coordinate_detection(image, text_command)
     
+    def verify_condition(self, image_bytes: bytes, condition: str) -> Tuple[bool, float]:
+        """
+        Check whether a natural-language condition holds on a screenshot.
+        
+        Args:
+            image_bytes: PNG image bytes (screenshot)
+            condition: Yes/no statement (e.g., "cart shows 1 item")
+            
+        Returns:
+            Tuple of (satisfied, confidence)
+        """
+        if self.model is None:
+            # Without a VLM we can't judge page state; never claim success
+            logger.info(f"No vision model loaded, reporting unmet: '{condition}'")
+            return (False, 0.0)
+        
+        image = Image.open(io.BytesIO(image_bytes)).convert("RGB")
+        prompt = f"Answer yes or no: does this screenshot show that {condition}?"
+        
+        inputs = self.processor(images=image, text=prompt, return_tensors="pt")
+        inputs = {k: v.to(self.device) for k, v in inputs.items()}
+        
+        with torch.no_grad():
+            outputs = self.model.generate(**inputs, max_new_tokens=5)
+        
+        answer = self.processor.decode(outputs[0], skip_special_tokens=True).strip().lower()
+        satisfied = answer.endswith("yes") or answer.startswith("yes")
+        return (satisfied, 0.7)
+    
     def _fallback_coordinate_detection(
         self, 
         image: Image.Image, 
@@ -239,3 +268,10 @@ class SimpleCoordinateDetector:
         
         confidence = 0.5
         return (x, y, confidence)
+    
+    def verify_condition(self, image_bytes: bytes, condition: str) -> Tuple[bool, float]:
+        """
+        Heuristics can't read page state, so conditions are always unmet.
+        """
+        logger.info(f"Simple detector cannot verify, reporting unmet: '{condition}'")
+        return (False, 0.0)
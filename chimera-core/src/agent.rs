@@ -1,11 +1,21 @@
-use crate::browser::BrowserSession;
+use crate::browser::{BrowserSession, LaunchMode, ScreenshotFormat, ViewportConfig};
+use crate::circuit_breaker::{BreakerState, CircuitBreaker};
+use crate::config::ChimeraConfig;
 use crate::error::{ChimeraError, Result};
-use crate::vision_client::VisionClient;
-use std::collections::HashMap;
+use crate::identity_grafting::IdentityGrafting;
+use crate::seed::SessionSeed;
+use crate::stealth_transport::{StealthProxy, UpstreamProxy};
+use crate::vision_client::{circuit_open, VisionClient};
+use crate::world_model::{
+    detect_block_page, ActionCandidate, CurrentState, DetectionPolicy, Outcome, RiskIndicator,
+    SafetyClassifier, WorldModel,
+};
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tonic::{Request, Response, Status};
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 use std::sync::Mutex;
 
 pub mod proto {
@@ -14,128 +24,271 @@ pub mod proto {
 
 use proto::{
     chimera_agent_server::ChimeraAgent, ActionRequest, ActionResponse, ActionType,
-    CloseSessionRequest, CloseSessionResponse, GetStateRequest, GetStateResponse,
+    CloseSessionRequest, CloseSessionResponse, GetAxTreeRequest, GetAxTreeResponse,
+    GetStateRequest, GetStateResponse,
     NavigateRequest, NavigateResponse, ObjectiveRequest, ObjectiveUpdate,
-    StartSessionRequest, StartSessionResponse,
+    StartSessionRequest, StartSessionResponse, StealthMeasure,
 };
 
+/// Default cap on agent loop iterations when the client doesn't specify one
+const DEFAULT_MAX_ITERATIONS: u32 = 20;
+
+/// Vertical scroll (px) when a Scroll action carries no deltas
+const DEFAULT_SCROLL_DELTA_Y: f64 = 500.0;
+
+/// Status (ObjectiveUpdate) and message prefix (gRPC) when vision is needed but down
+const VISION_UNAVAILABLE: &str = "vision_unavailable";
+
+/// How long `run_objective` spends reading each page it hasn't seen before acting
+const FIRST_VISIT_READING: (std::time::Duration, std::time::Duration) =
+    (std::time::Duration::from_secs(2), std::time::Duration::from_secs(6));
+
+#[derive(Clone)]
 pub struct ChimeraAgentService {
     sessions: Arc<RwLock<HashMap<String, Arc<Mutex<BrowserSession>>>>>,
+    
+    /// Ids whose browser is still launching (reserved so a retry can't launch a second one)
+    starting: Arc<Mutex<HashSet<String>>>,
+    
     vision_client: Arc<RwLock<Option<VisionClient>>>,
     vision_service_addr: String,
+    
+    /// Fails vision calls fast while the service is browning out
+    vision_breaker: Arc<CircuitBreaker>,
+    
+    identity: Option<Arc<Mutex<IdentityGrafting>>>,
+    config: Arc<ChimeraConfig>,
+    
+    /// Shared across sessions so honeypot/captcha patterns learned once are avoided everywhere
+    world_model: Arc<RwLock<WorldModel>>,
 }
 
 impl ChimeraAgentService {
     pub fn new(vision_service_addr: String) -> Self {
+        Self::from_config(ChimeraConfig {
+            vision_addr: vision_service_addr,
+            ..ChimeraConfig::default()
+        })
+    }
+
+    /// Build the service from the process configuration
+    pub fn from_config(config: ChimeraConfig) -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            starting: Arc::new(Mutex::new(HashSet::new())),
             vision_client: Arc::new(RwLock::new(None)),
-            vision_service_addr,
+            vision_service_addr: config.vision_addr.clone(),
+            vision_breaker: Arc::new(CircuitBreaker::new(
+                "Vision service",
+                config.vision_failure_threshold,
+                config.vision_cooldown,
+            )),
+            identity: None,
+            world_model: Arc::new(RwLock::new(load_world_model(&config.world_model_path))),
+            config: Arc::new(config),
         }
     }
 
-    async fn get_vision_client(&self) -> Result<VisionClient> {
-        // For simplicity, create a new connection each time
-        // In production, you'd want connection pooling
-        VisionClient::connect(self.vision_service_addr.clone())
-            .await
-            .map_err(|e| ChimeraError::Vision(format!("Failed to connect: {}", e)))
+    /// Attach the identity store so detections can burn the offending profile
+    pub fn with_identity_grafting(mut self, identity: IdentityGrafting) -> Self {
+        self.identity = Some(Arc::new(Mutex::new(identity)));
+        self
     }
-}
 
-#[tonic::async_trait]
-impl ChimeraAgent for ChimeraAgentService {
-    async fn start_session(
-        &self,
-        request: Request<StartSessionRequest>,
-    ) -> Result<Response<StartSessionResponse>, Status> {
-        let req = request.into_inner();
-        info!("Starting session: {}", req.session_id);
-
-        let session = BrowserSession::new(req.session_id.clone(), req.headless)
-            .map_err(|e| Status::internal(format!("Failed to start session: {}", e)))?;
-
-        let mut sessions = self.sessions.write().await;
-        sessions.insert(req.session_id.clone(), Arc::new(Mutex::new(session)));
+    /// Clone of the shared vision client (one channel for the whole service)
+    /// 
+    /// Fails fast while the vision circuit is open. The clone reports each of
+    /// its calls (OODA's `locate`, for one) to the breaker.
+    async fn get_vision_client(&self) -> Result<VisionClient> {
+        if self.vision_breaker.state() == BreakerState::Open {
+            return Err(circuit_open());
+        }
+        let client = shared_vision_client(&self.vision_client, &self.vision_service_addr).await?;
+        Ok(client.with_breaker(Arc::clone(&self.vision_breaker)))
+    }
 
-        Ok(Response::new(StartSessionResponse {
-            success: true,
-            message: "Session started".to_string(),
-        }))
+    /// Look up a live session by id
+    async fn session(&self, session_id: &str) -> Result<Arc<Mutex<BrowserSession>>, Status> {
+        self.sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| Status::not_found(format!("Session not found: {}", session_id)))
     }
 
-    async fn navigate(
+    /// `run_action` bounded by `deadline`
+    async fn run_action_with_deadline(
         &self,
-        request: Request<NavigateRequest>,
-    ) -> Result<Response<NavigateResponse>, Status> {
-        let req = request.into_inner();
-        let sessions = self.sessions.read().await;
-        
-        let session = sessions
-            .get(&req.session_id)
-            .ok_or_else(|| Status::not_found(format!("Session not found: {}", req.session_id)))?
-            .clone();
-        
-        drop(sessions);
-        
-        let session = session.lock().unwrap();
-        session
-            .navigate(&req.url)
-            .map_err(|e| Status::internal(format!("Navigation failed: {}", e)))?;
+        session: Arc<Mutex<BrowserSession>>,
+        req: ActionRequest,
+        screenshot: Option<Vec<u8>>,
+        deadline: std::time::Duration,
+    ) -> Result<ActionResponse, Status> {
+        let session_id = req.session_id.clone();
 
-        Ok(Response::new(NavigateResponse {
-            success: true,
-            message: "Navigation successful".to_string(),
-        }))
+        // Dropping the future on timeout (or client disconnect) cancels the OODA loop
+        match tokio::time::timeout(deadline, self.run_action(session, req, screenshot)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("⏱️  Action on session {} exceeded {:?}, aborted", session_id, deadline);
+                Err(Status::deadline_exceeded(format!("Action did not complete within {:?}", deadline)))
+            }
+        }
     }
 
-    async fn perform_action(
+    /// Body of `perform_action` (Observe -> Think -> Act), run under the action deadline
+    /// 
+    /// `screenshot` is the current screen if the caller already has it (the
+    /// previous action's result in a batch); otherwise one is captured.
+    #[instrument(name = "action", skip_all, fields(intent = %req.intent))]
+    async fn run_action(
         &self,
-        request: Request<ActionRequest>,
-    ) -> Result<Response<ActionResponse>, Status> {
-        let req = request.into_inner();
+        session: Arc<Mutex<BrowserSession>>,
+        req: ActionRequest,
+        screenshot: Option<Vec<u8>>,
+    ) -> Result<ActionResponse, Status> {
         debug!("Performing action: {} for session: {}", req.intent, req.session_id);
 
-        let sessions = self.sessions.read().await;
-        let session = sessions
-            .get(&req.session_id)
-            .ok_or_else(|| Status::not_found(format!("Session not found: {}", req.session_id)))?
-            .clone();
-        
-        drop(sessions);
-
         // Capture current state
-        let screenshot = {
-            let session = session.lock().unwrap();
-            session
+        let screenshot = match screenshot {
+            Some(screenshot) => screenshot,
+            None => session
+                .lock()
+                .unwrap()
                 .capture_screenshot()
-                .map_err(|e| Status::internal(format!("Screenshot failed: {}", e)))?
+                .map_err(|e| Status::internal(format!("Screenshot failed: {}", e)))?,
         };
 
-        // Get coordinates from vision service
-        let mut vision = self.get_vision_client().await
-            .map_err(|e| Status::internal(format!("Vision service error: {}", e)))?;
-        
-        let (x, y, confidence) = vision
-            .get_coordinates(screenshot.clone(), req.intent.clone())
-            .await
-            .map_err(|e| Status::internal(format!("Vision service error: {}", e)))?;
+        // Get coordinates from vision service, falling back to the AX tree if it's down
+        let located = with_vision(&self.vision_client, &self.vision_service_addr, &self.vision_breaker, |mut vision| {
+            let (screenshot, intent) = (screenshot.clone(), req.intent.clone());
+            async move { vision.get_coordinates(screenshot, intent).await }
+        })
+        .await;
+        // Vision answers in screenshot (device) pixels; clicks are in CSS pixels
+        let viewport = session.lock().unwrap().viewport();
+        let located = located.map(|(x, y, confidence)| {
+            let (x, y) = viewport.to_css(x, y);
+            (x, y, confidence)
+        });
+        let (x, y, confidence) = match located {
+            Ok(found) => found,
+            Err(e) => {
+                let fallback = crate::ooda::resolve_semantically(&session.lock().unwrap(), &req.intent);
+                match fallback {
+                    Some(found) => {
+                        warn!("👁️  Vision service error ({}), resolved '{}' from the AX tree", e, req.intent);
+                        found
+                    }
+                    None if matches!(e, ChimeraError::VisionUnavailable(_)) => {
+                        return Err(Status::unavailable(format!(
+                            "{}: {} (no AX tree match either)",
+                            VISION_UNAVAILABLE, e
+                        )))
+                    }
+                    None => {
+                        return Err(Status::internal(format!(
+                            "Vision service error: {} (no AX tree match either)",
+                            e
+                        )))
+                    }
+                }
+            }
+        };
 
         debug!("Found element at ({}, {}) with confidence: {}", x, y, confidence);
 
+        // OODA re-queries vision while verifying; without it the loop resolves from the AX tree
+        let mut vision = self.get_vision_client().await
+            .map_err(|e| warn!("Vision service unreachable, OODA will use the AX tree: {}", e))
+            .ok();
+
+        let action_label = match req.action_type() {
+            ActionType::Click => "click",
+            ActionType::Type => "type",
+            ActionType::Scroll => "scroll",
+            ActionType::Wait => "wait",
+        };
+        crate::metrics::agent().actions(action_label).inc();
+
+        // Session-level cadence: no bursts of back-to-back actions
+        if req.action_type() != ActionType::Wait {
+            let pacer = session.lock().unwrap().pacer();
+            pacer.wait_turn().await;
+        }
+
         // Perform the action with OODA loop verification
+        let mut ooda_summary = None;
         let new_screenshot = match req.action_type() {
             ActionType::Click => {
+                // Mental simulation first: refuse clicks the World Model predicts are traps
+                let current_state = CurrentState::from_session(&session.lock().unwrap())
+                    .map_err(|e| Status::internal(format!("Failed to capture state: {}", e)))?;
+                let candidate = ActionCandidate {
+                    action_type: crate::world_model::ActionType::Click,
+                    target_coordinates: (x as f64, y as f64),
+                    target_element: None,
+                    confidence: confidence as f64,
+                };
+                // The AX tree lets the honeypot heuristics inspect what's under the cursor.
+                // Snapshots carry no geometry, so the controls get theirs from the DOM.
+                let fusion = {
+                    let session = session.lock().unwrap();
+                    crate::cortex::FusionState::from_session(&session).and_then(|mut fusion| {
+                        crate::cortex::Cortex::new(session.get_tab()?).resolve_bounds(&mut fusion.ax_tree, |n| {
+                            crate::ooda::CLICKABLE_ROLES.contains(&n.role.as_str())
+                        })?;
+                        Ok(fusion)
+                    })
+                }
+                .map_err(|e| debug!("No fusion state for honeypot checks (non-fatal): {}", e))
+                .ok();
+                let predicted = self.world_model.read().await
+                    .predict(&current_state, &candidate, fusion.as_ref())
+                    .await
+                    .map_err(|e| Status::internal(format!("World Model prediction failed: {}", e)))?;
+                let risk = SafetyClassifier.assess(&predicted);
+                if risk >= self.config.risk_threshold {
+                    let err = ChimeraError::UnsafeAction(format!(
+                        "click at ({}, {}) predicted risk {:.2} >= {:.2} ({:?})",
+                        x, y, risk, self.config.risk_threshold, predicted.risk_indicators
+                    ));
+                    warn!("🛑 {}", err);
+                    return Err(Status::failed_precondition(err.to_string()));
+                }
+                
                 // Use OODA loop for self-healing clicks
                 let session_ref = session.clone();
-                crate::ooda::execute_with_verification(
+                let verified = crate::ooda::execute_with_verification(
                     &*session_ref.lock().unwrap(),
-                    &mut vision,
+                    vision.as_mut(),
                     &req.intent,
                     3, // max retries
                 )
-                .await
-                .map_err(|e| Status::internal(format!("OODA loop failed: {}", e)))?;
+                .await;
+                
+                // Judge what the click led to, then teach the World Model
+                let (outcome, new_hash) = {
+                    let session = session_ref.lock().unwrap();
+                    let outcome = match &verified {
+                        Ok(_) => classify_click_outcome(&session),
+                        Err(e) => Outcome::Failure { reason: e.to_string() },
+                    };
+                    (outcome, session.get_visual_hash().ok())
+                };
+                debug!("Click outcome: {:?}", outcome);
+                if let Some(new_hash) = new_hash {
+                    let mut world_model = self.world_model.write().await;
+                    world_model.learn(current_state.visual_hash, candidate, new_hash, outcome);
+                    // Saved now, not at CloseSession: sessions also end by TTL, rotation or process exit
+                    if let Err(e) = world_model.save(&self.config.world_model_path) {
+                        warn!("Failed to persist world model (non-fatal): {}", e);
+                    }
+                }
+                
+                let report = verified.map_err(|e| Status::internal(format!("OODA loop failed: {}", e)))?;
+                ooda_summary = Some(report.summary());
                 
                 // Capture new state after successful action
                 session_ref.lock().unwrap()
@@ -147,9 +300,10 @@ impl ChimeraAgent for ChimeraAgentService {
                 if let Some(text) = req.text {
                     crate::ooda::type_with_verification(
                         &*session_ref.lock().unwrap(),
-                        &mut vision,
+                        vision.as_mut(),
                         &req.intent,
                         &text,
+                        req.overwrite,
                         3,
                     )
                     .await
@@ -162,13 +316,17 @@ impl ChimeraAgent for ChimeraAgentService {
                     .map_err(|e| Status::internal(format!("Screenshot failed: {}", e)))?
             }
             ActionType::Scroll => {
-                {
-                    let session = session.lock().unwrap();
-                    // Default scroll down
-                    session
-                        .scroll(x, y, 0, 500)
-                        .map_err(|e| Status::internal(format!("Scroll failed: {}", e)))?;
-                }
+                let delta_x = req.scroll_delta_x.unwrap_or(0.0);
+                let delta_y = req.scroll_delta_y.unwrap_or(DEFAULT_SCROLL_DELTA_Y);
+                let cortex = session
+                    .lock()
+                    .unwrap()
+                    .cortex()
+                    .map_err(|e| Status::internal(format!("Failed to get tab: {}", e)))?;
+                cortex
+                    .human_scroll(delta_x, delta_y, Some(x as f64), Some(y as f64))
+                    .await
+                    .map_err(|e| Status::internal(format!("Scroll failed: {}", e)))?;
                 
                 // Capture new state
                 session.lock().unwrap()
@@ -195,14 +353,236 @@ impl ChimeraAgent for ChimeraAgentService {
 
         let new_state = format!("Action completed at ({}, {})", x, y);
 
-        Ok(Response::new(ActionResponse {
+        Ok(ActionResponse {
             success: true,
-            message: format!("Action completed with confidence: {}", confidence),
+            message: match ooda_summary {
+                Some(summary) => format!("Action completed with confidence: {} (OODA {})", confidence, summary),
+                None => format!("Action completed with confidence: {}", confidence),
+            },
             new_state,
             screenshot: new_screenshot,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl ChimeraAgent for ChimeraAgentService {
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
+    async fn start_session(
+        &self,
+        request: Request<StartSessionRequest>,
+    ) -> Result<Response<StartSessionResponse>, Status> {
+        let req = request.into_inner();
+        info!("Starting session: {}", req.session_id);
+        let reuse_if_exists = req.reuse_if_exists.unwrap_or(false);
+
+        // A retried StartSession must not launch a second browser for the same
+        // id: reserve it before launching, released on every exit path
+        let _reservation = {
+            let sessions = self.sessions.read().await;
+            if sessions.contains_key(&req.session_id) {
+                return existing_session_response(&req.session_id, &sessions[&req.session_id], reuse_if_exists);
+            }
+            StartReservation::claim(&self.starting, &req.session_id).ok_or_else(|| {
+                Status::unavailable(format!("Session {} is still starting, retry shortly", req.session_id))
+            })?
+        };
+
+        let profile_id = req.options.get("profile_id").map(|id| id.as_str());
+        let viewport = match req.options.get("viewport") {
+            Some(spec) => Some(ViewportConfig::parse(spec)
+                .ok_or_else(|| Status::invalid_argument(format!("Invalid viewport (want WIDTHxHEIGHT[@SCALE]): {}", spec)))?),
+            None => None,
+        };
+        // "launch_mode" overrides the boolean, e.g. to run headed under Xvfb
+        let mode = match req.options.get("launch_mode") {
+            Some(name) => LaunchMode::from_name(name)
+                .ok_or_else(|| Status::invalid_argument(format!("Unknown launch_mode (want headless, xvfb or headful): {}", name)))?,
+            None => LaunchMode::from_headless(req.headless),
+        };
+        // "upstream_proxy" routes this session's traffic through its own egress
+        let sidecar = match req.options.get("upstream_proxy") {
+            Some(spec) => {
+                let upstream = UpstreamProxy::parse(spec)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                Some(spawn_session_sidecar(&self.config, upstream)
+                    .await
+                    .map_err(|e| Status::internal(format!("Failed to start session proxy: {}", e)))?)
+            }
+            None => None,
+        };
+        let config = sidecar.as_ref().map_or(&*self.config, |(config, _)| config);
+        let launched = launch_session(req.session_id.clone(), mode, config, self.identity.as_ref(), profile_id, viewport).await;
+        let session = match launched {
+            Ok(session) => session,
+            Err(e) => {
+                if let Some((_, task)) = &sidecar {
+                    task.abort();
+                }
+                return Err(Status::internal(format!("Failed to start session: {}", e)));
+            }
+        };
+        // The sidecar lives exactly as long as its session
+        if let Some((_, task)) = &sidecar {
+            session.track_task(task.abort_handle());
+        }
+
+        let stealth_status = stealth_measures(&session);
+        let session = Arc::new(Mutex::new(session));
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(req.session_id.clone(), Arc::clone(&session));
+        crate::metrics::agent().sessions_started.inc();
+        crate::metrics::agent().sessions_active.set(sessions.len() as i64);
+
+        // Enforce the configured session lifetime
+        if let Some(ttl) = self.config.session_ttl {
+            let sessions = Arc::clone(&self.sessions);
+            let session_id = req.session_id.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(ttl).await;
+                let mut sessions = sessions.write().await;
+                // Only reap the session we started, not a later one reusing the id
+                if sessions.get(&session_id).is_some_and(|s| Arc::ptr_eq(s, &session)) {
+                    sessions.remove(&session_id);
+                    crate::metrics::agent().sessions_closed.inc();
+                    crate::metrics::agent().sessions_active.set(sessions.len() as i64);
+                    drop(sessions);
+                    info!("⏱️  Session {} reached its TTL and was closed", session_id);
+                    shutdown_session(session).await;
+                }
+            }.in_current_span());
+        }
+
+        Ok(Response::new(StartSessionResponse {
+            success: true,
+            message: "Session started".to_string(),
+            stealth_status,
+        }))
+    }
+
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
+    async fn navigate(
+        &self,
+        request: Request<NavigateRequest>,
+    ) -> Result<Response<NavigateResponse>, Status> {
+        let req = request.into_inner();
+        let sessions = self.sessions.read().await;
+        
+        let session = sessions
+            .get(&req.session_id)
+            .ok_or_else(|| Status::not_found(format!("Session not found: {}", req.session_id)))?
+            .clone();
+        
+        drop(sessions);
+        
+        let session = session.lock().unwrap();
+        session
+            .navigate(&req.url)
+            .map_err(|e| Status::internal(format!("Navigation failed: {}", e)))?;
+
+        Ok(Response::new(NavigateResponse {
+            success: true,
+            message: "Navigation successful".to_string(),
         }))
     }
 
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
+    async fn perform_action(
+        &self,
+        request: Request<ActionRequest>,
+    ) -> Result<Response<ActionResponse>, Status> {
+        // Bounded latency: the client's grpc-timeout if tighter than our own cap
+        let deadline = action_deadline(&request, self.config.action_timeout);
+        let req = request.into_inner();
+        let session = self.session(&req.session_id).await?;
+
+        self.run_action_with_deadline(session, req, None, deadline).await.map(Response::new)
+    }
+
+    type PerformActionsStream = tokio_stream::wrappers::ReceiverStream<Result<ActionResponse, Status>>;
+
+    async fn perform_actions(
+        &self,
+        request: Request<tonic::Streaming<ActionRequest>>,
+    ) -> Result<Response<Self::PerformActionsStream>, Status> {
+        // Each action gets the full deadline; the stream as a whole is bounded by the client
+        let deadline = self.config.action_timeout;
+        let mut actions = request.into_inner();
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            // Kept warm across the batch: the session handle and the screen after
+            // the last action, with the URL it showed
+            let mut session: Option<(String, Arc<Mutex<BrowserSession>>)> = None;
+            let mut screenshot: Option<(String, Vec<u8>)> = None;
+            
+            loop {
+                let req = match actions.message().await {
+                    Ok(Some(req)) => req,
+                    Ok(None) => break,
+                    Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        break;
+                    }
+                };
+                let stop_on_failure = req.stop_on_failure.unwrap_or(false);
+                let session_id = req.session_id.clone();
+                
+                let handle = match &session {
+                    Some((id, handle)) if *id == session_id => Ok(handle.clone()),
+                    _ => {
+                        screenshot = None;
+                        service.session(&session_id).await
+                    }
+                };
+                let result = match handle {
+                    Ok(handle) => {
+                        session = Some((session_id.clone(), handle.clone()));
+                        // A late redirect or client-side route change makes the last screen stale
+                        let current_url = handle.lock().unwrap().get_url().ok();
+                        let screen = screenshot
+                            .take()
+                            .filter(|(url, _)| current_url.as_ref() == Some(url))
+                            .map(|(_, screen)| screen);
+                        service.run_action_with_deadline(handle, req, screen, deadline)
+                            .instrument(tracing::info_span!("session", session_id = %session_id))
+                            .await
+                    }
+                    Err(status) => Err(status),
+                };
+                
+                // Failures are reported in-band so the client sees which step broke
+                let response = match result {
+                    Ok(response) => {
+                        screenshot = session
+                            .as_ref()
+                            .and_then(|(_, handle)| handle.lock().unwrap().get_url().ok())
+                            .map(|url| (url, response.screenshot.clone()));
+                        response
+                    }
+                    Err(status) => ActionResponse {
+                        success: false,
+                        message: status.message().to_string(),
+                        new_state: String::new(),
+                        screenshot: vec![],
+                    },
+                };
+                let failed = !response.success;
+                if tx.send(Ok(response)).await.is_err() {
+                    break; // client went away
+                }
+                if failed && stop_on_failure {
+                    warn!("Batched action failed on session {}, skipping the rest (stop_on_failure)", session_id);
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(rx)))
+    }
+
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
     async fn get_state(
         &self,
         request: Request<GetStateRequest>,
@@ -217,9 +597,18 @@ impl ChimeraAgent for ChimeraAgentService {
         
         drop(sessions);
         
+        let format = ScreenshotFormat::from_name(
+            req.screenshot_format.as_deref().unwrap_or("png"),
+            req.screenshot_quality,
+        )
+        .ok_or_else(|| Status::invalid_argument(format!(
+            "Unsupported screenshot format: {}",
+            req.screenshot_format.as_deref().unwrap_or_default()
+        )))?;
+        
         let session = session.lock().unwrap();
         let screenshot = session
-            .capture_screenshot()
+            .capture_screenshot_as(format)
             .map_err(|e| Status::internal(format!("Screenshot failed: {}", e)))?;
         
         let url = session
@@ -234,11 +623,13 @@ impl ChimeraAgent for ChimeraAgentService {
             screenshot,
             url,
             title,
+            stealth_status: stealth_measures(&session),
         }))
     }
 
     type RunObjectiveStream = tokio_stream::wrappers::ReceiverStream<Result<ObjectiveUpdate, Status>>;
 
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
     async fn run_objective(
         &self,
         request: Request<ObjectiveRequest>,
@@ -250,23 +641,61 @@ impl ChimeraAgent for ChimeraAgentService {
 
         // Start the objective loop in a background task
         let sessions = Arc::clone(&self.sessions);
+        let starting = Arc::clone(&self.starting);
         let vision_client = Arc::clone(&self.vision_client);
+        let vision_breaker = Arc::clone(&self.vision_breaker);
         let session_id = req.session_id.clone();
         let start_url = req.start_url.clone();
         let instruction = req.instruction.clone();
+        let max_iterations = req.max_iterations
+            .filter(|m| *m > 0)
+            .unwrap_or(DEFAULT_MAX_ITERATIONS);
+        let progress = move |done: u32| done as f32 / max_iterations as f32;
+        let on_detection = req.on_detection.as_deref().unwrap_or("abort");
+        let policy = DetectionPolicy::from_name(on_detection)
+            .ok_or_else(|| Status::invalid_argument(format!("Unknown on_detection policy: {}", on_detection)))?;
+        let profile_id = req.profile_id.clone();
+        let success_condition = req.success_condition.clone().filter(|c| !c.trim().is_empty());
+        let identity = self.identity.clone();
+        let config = Arc::clone(&self.config);
 
         let vision_service_addr = self.vision_service_addr.clone();
         tokio::spawn(async move {
             // Start session if needed
-            let session_arc = if !sessions.read().await.contains_key(&session_id) {
-                let new_session = BrowserSession::new(session_id.clone(), req.headless)
-                    .expect("Failed to start session");
-                let mut sessions_write = sessions.write().await;
-                let arc = Arc::new(Mutex::new(new_session));
-                sessions_write.insert(session_id.clone(), arc.clone());
-                arc
-            } else {
-                sessions.read().await.get(&session_id).unwrap().clone()
+            let existing = sessions.read().await.get(&session_id).cloned();
+            let session_arc = match existing {
+                Some(arc) => arc,
+                None => {
+                    // Same guard as StartSession: one launch per id at a time
+                    let Some(_reservation) = StartReservation::claim(&starting, &session_id) else {
+                        let _ = tx.send(Err(Status::unavailable(format!("Session {} is still starting, retry shortly", session_id)))).await;
+                        return;
+                    };
+                    match launch_session(session_id.clone(), LaunchMode::from_headless(req.headless), &config, identity.as_ref(), profile_id.as_deref(), None).await {
+                        Ok(new_session) => {
+                            let arc = Arc::new(Mutex::new(new_session));
+                            let mut sessions = sessions.write().await;
+                            match sessions.get(&session_id).cloned() {
+                                // A StartSession finished between our check and our claim: use it, close ours
+                                Some(existing) => {
+                                    drop(sessions);
+                                    shutdown_session(arc).await;
+                                    existing
+                                }
+                                None => {
+                                    sessions.insert(session_id.clone(), arc.clone());
+                                    crate::metrics::agent().sessions_started.inc();
+                                    crate::metrics::agent().sessions_active.set(sessions.len() as i64);
+                                    arc
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            let _ = tx.send(Err(Status::internal(format!("Failed to start session: {}", e)))).await;
+                            return;
+                        }
+                    }
+                }
             };
 
             // Navigate to start URL
@@ -275,6 +704,9 @@ impl ChimeraAgent for ChimeraAgentService {
                 message: format!("Navigating to {}", start_url),
                 screenshot: vec![],
                 last_action: None,
+                iteration: 0,
+                max_iterations,
+                progress: 0.0,
             })).await;
 
             {
@@ -284,10 +716,37 @@ impl ChimeraAgent for ChimeraAgentService {
                     return;
                 }
             }
-
+            
             // Main agent loop: Observe -> Think -> Act -> Verify
-            let max_iterations = 20;
+            let mut last_screenshot = Vec::new();
+            let mut read_url = None;
             for iteration in 0..max_iterations {
+                // A first-time visitor looks each page over before clicking anything on it
+                let reader = {
+                    let session = session_arc.lock().unwrap();
+                    let url = session.get_url().ok();
+                    let navigated = url.is_some() && url != read_url;
+                    let first_visit = navigated
+                        && session
+                            .get_visual_hash()
+                            .is_ok_and(|hash| !session.recall_page(&hash, crate::ooda::OodaConfig::default().change_threshold));
+                    if navigated {
+                        read_url = url;
+                    }
+                    match session.cortex() {
+                        Ok(cortex) if first_visit => {
+                            let duration = session.pacer().rng().gen_range(FIRST_VISIT_READING.0..FIRST_VISIT_READING.1);
+                            Some((cortex, duration))
+                        }
+                        _ => None,
+                    }
+                };
+                if let Some((reader, duration)) = reader {
+                    if let Err(e) = reader.simulate_reading(duration).await {
+                        debug!("Reading simulation failed (non-fatal): {}", e);
+                    }
+                }
+
                 // Observe
                 let screenshot = {
                     let session = session_arc.lock().unwrap();
@@ -295,16 +754,19 @@ impl ChimeraAgent for ChimeraAgentService {
                         Ok(s) => s,
                         Err(e) => {
                             let _ = tx.send(Err(Status::internal(format!("Screenshot failed: {}", e)))).await;
-                            break;
+                            return;
                         }
                     }
                 };
 
                 let _ = tx.send(Ok(ObjectiveUpdate {
                     status: "observing".to_string(),
-                    message: format!("Iteration {}: Observing current state", iteration + 1),
+                    message: format!("Iteration {}/{}: Observing current state", iteration + 1, max_iterations),
                     screenshot: screenshot.clone(),
                     last_action: None,
+                    iteration: iteration + 1,
+                    max_iterations,
+                    progress: progress(iteration),
                 })).await;
 
                 // Think (get coordinates)
@@ -325,37 +787,51 @@ impl ChimeraAgent for ChimeraAgentService {
                             }
                             tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                         }
-                    })
-                };
-                
-                let mut vision = match VisionClient::connect(vision_service_addr.clone()).await {
-                    Ok(v) => v,
-                    Err(e) => {
-                        thinking_task.abort();
-                        let _ = tx.send(Ok(ObjectiveUpdate {
-                            status: "error".to_string(),
-                            message: format!("Vision service error: {}", e),
-                            screenshot: vec![],
-                            last_action: None,
-                        })).await;
-                        break;
-                    }
+                    }.in_current_span())
                 };
+                // Closing the session must not leave this running against a dead browser
+                session_arc.lock().unwrap().track_task(thinking_task.abort_handle());
                 
                 // Wait for vision response (fidgeting continues in background)
+                let coords = with_vision(&vision_client, &vision_service_addr, &vision_breaker, |mut vision| {
+                    let (screenshot, instruction) = (screenshot.clone(), instruction.clone());
+                    async move { vision.get_coordinates(screenshot, instruction).await }
+                }).await;
+                let viewport = session_arc.lock().unwrap().viewport();
+                let coords = coords.map(|(x, y, confidence)| {
+                    let (x, y) = viewport.to_css(x, y);
+                    (x, y, confidence)
+                });
+                
                 // Abort fidgeting task once we have coordinates
                 thinking_task.abort();
                 
-                let (x, y, confidence) = match vision.get_coordinates(screenshot, instruction.clone()).await {
+                // The AX tree stands in for the vision service when it's down
+                let coords = coords.or_else(|e| {
+                    let fallback = crate::ooda::resolve_semantically(&session_arc.lock().unwrap(), &instruction);
+                    if fallback.is_some() {
+                        warn!("👁️  Vision service error ({}), resolved '{}' from the AX tree", e, instruction);
+                    }
+                    fallback.ok_or(e)
+                });
+                
+                let (x, y, confidence) = match coords {
                     Ok(coords) => coords,
                     Err(e) => {
+                        let status = match e {
+                            ChimeraError::VisionUnavailable(_) => VISION_UNAVAILABLE,
+                            _ => "error",
+                        };
                         let _ = tx.send(Ok(ObjectiveUpdate {
-                            status: "error".to_string(),
+                            status: status.to_string(),
                             message: format!("Vision service error: {}", e),
                             screenshot: vec![],
                             last_action: None,
+                            iteration: iteration + 1,
+                            max_iterations,
+                            progress: progress(iteration),
                         })).await;
-                        break;
+                        return;
                     }
                 };
 
@@ -364,14 +840,19 @@ impl ChimeraAgent for ChimeraAgentService {
                     message: format!("Found target at ({}, {}) with confidence: {}", x, y, confidence),
                     screenshot: vec![],
                     last_action: None,
+                    iteration: iteration + 1,
+                    max_iterations,
+                    progress: progress(iteration),
                 })).await;
 
-                // Act
+                // Act (after the session's pacing governor releases us)
+                let pacer = session_arc.lock().unwrap().pacer();
+                pacer.wait_turn().await;
                 {
                     let session = session_arc.lock().unwrap();
                     if let Err(e) = session.click(x, y) {
                         let _ = tx.send(Err(Status::internal(format!("Click failed: {}", e)))).await;
-                        break;
+                        return;
                     }
                 }
 
@@ -392,28 +873,130 @@ impl ChimeraAgent for ChimeraAgentService {
                     message: "Action executed".to_string(),
                     screenshot: action_response.screenshot.clone(),
                     last_action: Some(action_response),
+                    iteration: iteration + 1,
+                    max_iterations,
+                    progress: progress(iteration + 1),
                 })).await;
 
-                // Verify (simple: wait and check)
-                tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+                // Verify (simple: look at the result, then check)
+                let pacer = session_arc.lock().unwrap().pacer();
+                pacer.dwell().await;
+
+                // Panic button: challenge/ban page after acting
+                let detection = {
+                    let session = session_arc.lock().unwrap();
+                    let title = session.get_title().unwrap_or_default();
+                    let url = session.get_url().unwrap_or_default();
+                    detect_block_page(&title, &url)
+                };
+                
+                // Widget captchas don't change the title; look for them in frames and the AX tree
+                let captcha = match detection {
+                    Some(_) => None,
+                    None => {
+                        let session = session_arc.lock().unwrap();
+                        session.get_tab().ok().and_then(|tab| crate::cortex::Cortex::new(tab).detect_captcha())
+                    }
+                };
+                if let Some(kind) = captcha {
+                    let _ = tx.send(Ok(ObjectiveUpdate {
+                        status: "captcha_detected".to_string(),
+                        message: format!("{} captcha detected", kind.name()),
+                        screenshot: new_screenshot.clone(),
+                        last_action: None,
+                        iteration: iteration + 1,
+                        max_iterations,
+                        progress: progress(iteration + 1),
+                    })).await;
+                }
+                let detection = detection.or(captcha.map(|_| RiskIndicator::CaptchaAppeared));
+                
+                if let Some(indicator) = detection {
+                    let (status, message) = respond_to_detection(
+                        &indicator,
+                        policy,
+                        &session_id,
+                        profile_id.as_deref(),
+                        &sessions,
+                        identity.as_ref(),
+                    ).await;
+                    let _ = tx.send(Ok(ObjectiveUpdate {
+                        status,
+                        message,
+                        screenshot: new_screenshot,
+                        last_action: None,
+                        iteration: iteration + 1,
+                        max_iterations,
+                        progress: progress(iteration + 1),
+                    })).await;
+                    return;
+                }
+
 
-                // For now, we'll assume success after one action
-                // In a real implementation, you'd verify the objective was met
-                if iteration == 0 {
+                // Verify: without a success condition the objective is a single action
+                let satisfied = match &success_condition {
+                    None => true,
+                    Some(condition) => {
+                        let _ = tx.send(Ok(ObjectiveUpdate {
+                            status: "verifying".to_string(),
+                            message: format!("Checking: {}", condition),
+                            screenshot: vec![],
+                            last_action: None,
+                            iteration: iteration + 1,
+                            max_iterations,
+                            progress: progress(iteration + 1),
+                        })).await;
+                        
+                        let verdict = with_vision(&vision_client, &vision_service_addr, &vision_breaker, |mut vision| {
+                            let (screenshot, condition) = (new_screenshot.clone(), condition.clone());
+                            async move { vision.verify_condition(screenshot, condition).await }
+                        }).await;
+                        match verdict {
+                            Ok((satisfied, confidence)) => {
+                                debug!("Success condition {:?}: {} (confidence {})", condition, satisfied, confidence);
+                                satisfied
+                            }
+                            Err(e) => {
+                                warn!("Success condition check failed (treating as unmet): {}", e);
+                                false
+                            }
+                        }
+                    }
+                };
+                
+                if satisfied {
                     let _ = tx.send(Ok(ObjectiveUpdate {
                         status: "complete".to_string(),
                         message: "Objective completed".to_string(),
                         screenshot: new_screenshot,
                         last_action: None,
+                        iteration: iteration + 1,
+                        max_iterations,
+                        progress: 1.0,
                     })).await;
-                    break;
+                    return;
                 }
+                last_screenshot = new_screenshot;
             }
-        });
-
+            
+            let _ = tx.send(Ok(ObjectiveUpdate {
+                status: "failed".to_string(),
+                message: format!(
+                    "Success condition not met after {} iterations: {}",
+                    max_iterations,
+                    success_condition.as_deref().unwrap_or_default()
+                ),
+                screenshot: last_screenshot,
+                last_action: None,
+                iteration: max_iterations,
+                max_iterations,
+                progress: 1.0,
+            })).await;
+        }.in_current_span());
         Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(rx)))
     }
 
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
     async fn close_session(
         &self,
         request: Request<CloseSessionRequest>,
@@ -422,8 +1005,431 @@ impl ChimeraAgent for ChimeraAgentService {
         info!("Closing session: {}", req.session_id);
 
         let mut sessions = self.sessions.write().await;
-        sessions.remove(&req.session_id);
+        let removed = sessions.remove(&req.session_id);
+        crate::metrics::agent().sessions_active.set(sessions.len() as i64);
+        drop(sessions);
+        
+        // End Chrome now rather than whenever the last clone of the Arc drops
+        if let Some(session) = removed {
+            crate::metrics::agent().sessions_closed.inc();
+            shutdown_session(session).await;
+        }
 
         Ok(Response::new(CloseSessionResponse { success: true }))
     }
+
+    #[instrument(name = "session", skip_all, fields(session_id = %request.get_ref().session_id))]
+    async fn get_ax_tree(
+        &self,
+        request: Request<GetAxTreeRequest>,
+    ) -> Result<Response<GetAxTreeResponse>, Status> {
+        let req = request.into_inner();
+        let sessions = self.sessions.read().await;
+        
+        let session = sessions
+            .get(&req.session_id)
+            .ok_or_else(|| Status::not_found(format!("Session not found: {}", req.session_id)))?
+            .clone();
+        
+        drop(sessions);
+
+        let tree = {
+            let session = session.lock().unwrap();
+            let tab = session
+                .get_tab()
+                .map_err(|e| Status::internal(format!("Failed to get tab: {}", e)))?;
+            crate::cortex::Cortex::new(tab)
+                .snapshot_accessibility_tree()
+                .map_err(|e| Status::internal(format!("AX tree extraction failed: {}", e)))?
+        };
+
+        if tree.nodes.is_empty() {
+            return Err(Status::internal("AX tree extraction returned no nodes"));
+        }
+
+        let role_filter = req.role_filter.map(|r| r.to_lowercase()).filter(|r| !r.is_empty());
+        let nodes = tree
+            .nodes
+            .into_iter()
+            .filter(|node| match &role_filter {
+                Some(filter) => node.role.to_lowercase().contains(filter),
+                None => true,
+            })
+            .map(|node| proto::AxNode {
+                node_id: node.node_id,
+                role: node.role,
+                name: node.name,
+                value: node.value,
+                parent_id: node.parent_id,
+                bounds: node.bounds.map(|b| proto::AxBounds {
+                    x: b.x,
+                    y: b.y,
+                    width: b.width,
+                    height: b.height,
+                }),
+                state: node.state,
+                frame_id: node.frame_id,
+            })
+            .collect();
+
+        Ok(Response::new(GetAxTreeResponse { nodes }))
+    }
+}
+
+/// The service's vision client, connecting on first use
+/// 
+/// Every caller gets a clone sharing one HTTP/2 channel, so actions don't pay
+/// a handshake each.
+async fn shared_vision_client(slot: &RwLock<Option<VisionClient>>, addr: &str) -> Result<VisionClient> {
+    if let Some(client) = slot.read().await.as_ref() {
+        return Ok(client.clone());
+    }
+    
+    let mut slot = slot.write().await;
+    if let Some(client) = slot.as_ref() {
+        return Ok(client.clone());
+    }
+    let client = VisionClient::connect(addr.to_string()).await?;
+    info!("👁️  Connected to vision service at {}", addr);
+    *slot = Some(client.clone());
+    Ok(client)
+}
+
+/// Run a vision call on the shared client, reconnecting once if the channel died
+/// 
+/// Goes through `breaker`: while it's open the call fails immediately with
+/// `VisionUnavailable` instead of reconnecting, so a brownout doesn't turn
+/// every session's every iteration into a reconnect storm. Only channel
+/// failures count against it; an application error means the service is up.
+async fn with_vision<T, F, Fut>(
+    slot: &RwLock<Option<VisionClient>>,
+    addr: &str,
+    breaker: &CircuitBreaker,
+    call: F,
+) -> Result<T>
+where
+    F: Fn(VisionClient) -> Fut,
+    Fut: std::future::Future<Output = Result<T>>,
+{
+    if !breaker.allow() {
+        return Err(circuit_open());
+    }
+    
+    let result = async {
+        match call(shared_vision_client(slot, addr).await?).await {
+            Err(ChimeraError::VisionUnavailable(e)) => {
+                warn!("Vision channel failed, reconnecting: {}", e);
+                *slot.write().await = None;
+                call(shared_vision_client(slot, addr).await?).await
+            }
+            result => result,
+        }
+    }
+    .await;
+    
+    match &result {
+        Err(ChimeraError::VisionUnavailable(_)) => breaker.record_failure(),
+        _ => breaker.record_success(),
+    }
+    result
+}
+
+/// Load persisted World Model patterns, starting fresh if there are none
+fn load_world_model(path: &std::path::Path) -> WorldModel {
+    if !path.exists() {
+        return WorldModel::new();
+    }
+    WorldModel::load(path).unwrap_or_else(|e| {
+        warn!("Ignoring unreadable world model (starting fresh): {}", e);
+        WorldModel::new()
+    })
+}
+
+/// What a verified click led to: a captcha, an error/block page, or success
+fn classify_click_outcome(session: &BrowserSession) -> Outcome {
+    let title = session.get_title().unwrap_or_default();
+    let url = session.get_url().unwrap_or_default();
+    match detect_block_page(&title, &url) {
+        Some(RiskIndicator::CaptchaAppeared) => return Outcome::Captcha,
+        Some(indicator) => return Outcome::Failure { reason: format!("{:?}", indicator) },
+        None => {}
+    }
+    
+    // Widget captchas (reCAPTCHA, hCaptcha, Turnstile) show up without changing the title
+    let captcha = session.get_tab().ok()
+        .and_then(|tab| crate::cortex::Cortex::new(tab).detect_captcha());
+    if captcha.is_some() {
+        Outcome::Captcha
+    } else {
+        Outcome::Success
+    }
+}
+
+/// Shut a session's browser down off the async runtime (waits for any in-flight action)
+async fn shutdown_session(session: Arc<Mutex<BrowserSession>>) {
+    // Keep the caller's session span on the blocking thread so shutdown logs correlate
+    let span = tracing::Span::current();
+    let result = tokio::task::spawn_blocking(move || {
+        let _span = span.enter();
+        session
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .shutdown();
+    })
+    .await;
+    if let Err(e) = result {
+        error!("Session shutdown panicked: {}", e);
+    }
+}
+
+/// An id reserved by an in-flight `StartSession`, released on drop
+struct StartReservation<'a> {
+    starting: &'a Mutex<HashSet<String>>,
+    session_id: String,
+}
+
+impl<'a> StartReservation<'a> {
+    /// Reserve `session_id`, or None if another start already holds it
+    fn claim(starting: &'a Mutex<HashSet<String>>, session_id: &str) -> Option<Self> {
+        starting.lock().unwrap().insert(session_id.to_string()).then(|| Self {
+            starting,
+            session_id: session_id.to_string(),
+        })
+    }
+}
+
+impl Drop for StartReservation<'_> {
+    fn drop(&mut self) {
+        self.starting.lock().unwrap().remove(&self.session_id);
+    }
+}
+
+/// `StartSession` for an id that's already live: reuse it or refuse
+fn existing_session_response(
+    session_id: &str,
+    existing: &Mutex<BrowserSession>,
+    reuse_if_exists: bool,
+) -> Result<Response<StartSessionResponse>, Status> {
+    if !reuse_if_exists {
+        return Err(Status::already_exists(format!("Session already exists: {}", session_id)));
+    }
+    info!("Session {} already running, reusing it", session_id);
+    Ok(Response::new(StartSessionResponse {
+        success: true,
+        message: "Session already running".to_string(),
+        stealth_status: stealth_measures(&existing.lock().unwrap()),
+    }))
+}
+
+/// A session's `StealthStatus` as reported over RPC
+fn stealth_measures(session: &BrowserSession) -> Vec<StealthMeasure> {
+    session
+        .stealth_status()
+        .measures()
+        .iter()
+        .map(|(name, state)| StealthMeasure {
+            name: name.to_string(),
+            state: state.name().to_string(),
+            reason: state.reason().to_string(),
+        })
+        .collect()
+}
+
+/// Launch a session, grafting `profile_id`'s identity when a store is attached
+/// 
+/// The profile is leased for the session's lifetime so no other swarm worker
+/// grafts it concurrently. Its cookies are loaded at launch and written back
+/// when the session closes, and its seed keeps its behaviour the same from
+/// session to session.
+async fn launch_session(
+    session_id: String,
+    mode: LaunchMode,
+    config: &ChimeraConfig,
+    identity: Option<&Arc<Mutex<IdentityGrafting>>>,
+    profile_id: Option<&str>,
+    viewport: Option<ViewportConfig>,
+) -> anyhow::Result<BrowserSession> {
+    let (Some(identity), Some(profile_id)) = (identity, profile_id) else {
+        return BrowserSession::new_async(session_id, mode, config, None, viewport, SessionSeed::random()).await;
+    };
+    
+    let lease = IdentityGrafting::acquire_profile(identity, Some(profile_id)).await?;
+    let profile = lease.profile().clone();
+    let seed = SessionSeed::for_profile(&profile.id);
+    let session = BrowserSession::new_async(session_id, mode, config, Some(profile), viewport, seed).await?;
+    Ok(session
+        .with_identity_store(Arc::clone(identity))
+        .with_lease(lease))
+}
+
+/// Start a Phantom sidecar dedicated to one session, egressing through `upstream`
+/// 
+/// Chrome can't tag its CONNECTs, so per-session egress takes a proxy per
+/// session. Returns the config to launch that session's Chrome with (pointed
+/// at the sidecar's port) and the sidecar task.
+async fn spawn_session_sidecar(
+    config: &ChimeraConfig,
+    upstream: UpstreamProxy,
+) -> Result<(ChimeraConfig, tokio::task::JoinHandle<()>)> {
+    let mut session_config = ChimeraConfig {
+        proxy_port: 0, // any free port
+        upstream_proxy: Some(upstream),
+        ..config.clone()
+    };
+    let proxy = StealthProxy::from_config(&session_config)?;
+    let listener = proxy.bind().await?;
+    session_config.proxy_port = listener.local_addr()?.port();
+    
+    let task = tokio::spawn(async move {
+        if let Err(e) = proxy.serve_on(listener).await {
+            error!("Session proxy died: {}", e);
+        }
+    }.in_current_span());
+    Ok((session_config, task))
+}
+
+/// Apply the mission's detection policy, returning the (status, message) to stream
+async fn respond_to_detection(
+    indicator: &RiskIndicator,
+    policy: DetectionPolicy,
+    session_id: &str,
+    profile_id: Option<&str>,
+    sessions: &Arc<RwLock<HashMap<String, Arc<Mutex<BrowserSession>>>>>,
+    identity: Option<&Arc<Mutex<IdentityGrafting>>>,
+) -> (String, String) {
+    warn!("🚨 Detection in session {}: {:?} (policy: {:?})", session_id, indicator, policy);
+    if matches!(indicator, RiskIndicator::CaptchaAppeared) {
+        crate::metrics::agent().captcha_detections.inc();
+    }
+
+    match policy {
+        DetectionPolicy::Abort => (
+            "aborted".to_string(),
+            format!("Detection: {:?}, mission aborted", indicator),
+        ),
+        DetectionPolicy::Solve => (
+            "solver_required".to_string(),
+            format!("Detection: {:?}, session held open for solver", indicator),
+        ),
+        DetectionPolicy::Rotate => {
+            // Blank and drop the session so nothing from the burned identity survives
+            let removed = {
+                let mut sessions = sessions.write().await;
+                let removed = sessions.remove(session_id);
+                crate::metrics::agent().sessions_active.set(sessions.len() as i64);
+                removed
+            };
+            let mut lease = None;
+            if let Some(session) = removed {
+                {
+                    let mut session = session.lock().unwrap();
+                    if let Err(e) = session.blank_fingerprint() {
+                        warn!("Failed to blank fingerprint (non-fatal): {}", e);
+                    }
+                    // The blanked jar must not be saved back into the burned profile
+                    lease = session.discard_identity();
+                }
+                shutdown_session(session).await;
+                crate::metrics::agent().sessions_closed.inc();
+            }
+
+            if let (Some(identity), Some(profile_id)) = (identity, profile_id) {
+                let (burned, redis) = {
+                    let mut identity = identity.lock().unwrap();
+                    (identity.mark_burned(profile_id), identity.redis_store())
+                };
+                match burned {
+                    // Tell the rest of the swarm to stop using the identity
+                    Ok(profile) => if let Some(redis) = redis {
+                        if let Err(e) = redis.save_profile(&profile).await {
+                            warn!("Failed to save burned profile to Redis (non-fatal): {}", e);
+                        }
+                    },
+                    Err(e) => warn!("Failed to burn profile (non-fatal): {}", e),
+                }
+            }
+            // Only free the identity once the swarm can see it is burned
+            if let Some(lease) = lease {
+                lease.release().await;
+            }
+
+            (
+                "rotate".to_string(),
+                format!("Detection: {:?}, profile burned and session closed; respawn with a fresh identity", indicator),
+            )
+        }
+    }
+}
+
+/// Effective deadline for one action: our configured cap, tightened by the
+/// client's `grpc-timeout` header when present
+fn action_deadline<T>(request: &Request<T>, cap: std::time::Duration) -> std::time::Duration {
+    request
+        .metadata()
+        .get("grpc-timeout")
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_grpc_timeout)
+        .map_or(cap, |client| client.min(cap))
+}
+
+/// Parse a gRPC `grpc-timeout` value (`<digits><H|M|S|m|u|n>`)
+fn parse_grpc_timeout(value: &str) -> Option<std::time::Duration> {
+    use std::time::Duration;
+    
+    let unit = value.chars().last()?;
+    let digits = &value[..value.len() - unit.len_utf8()];
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        'H' => Some(Duration::from_secs(amount.checked_mul(3600)?)),
+        'M' => Some(Duration::from_secs(amount.checked_mul(60)?)),
+        'S' => Some(Duration::from_secs(amount)),
+        'm' => Some(Duration::from_millis(amount)),
+        'u' => Some(Duration::from_micros(amount)),
+        'n' => Some(Duration::from_nanos(amount)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_grpc_timeout() {
+        assert_eq!(parse_grpc_timeout("2H"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_grpc_timeout("5M"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_grpc_timeout("30S"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_grpc_timeout("1500m"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_grpc_timeout("250u"), Some(Duration::from_micros(250)));
+        assert_eq!(parse_grpc_timeout("99n"), Some(Duration::from_nanos(99)));
+    }
+
+    #[test]
+    fn test_parse_grpc_timeout_rejects_garbage() {
+        assert_eq!(parse_grpc_timeout(""), None);
+        assert_eq!(parse_grpc_timeout("S"), None);
+        assert_eq!(parse_grpc_timeout("10"), None);
+        assert_eq!(parse_grpc_timeout("10x"), None);
+        assert_eq!(parse_grpc_timeout("+10S"), None);
+        assert_eq!(parse_grpc_timeout("10µ"), None);
+        // Would overflow u64 seconds
+        assert_eq!(parse_grpc_timeout(&format!("{}H", u64::MAX)), None);
+    }
+
+    #[test]
+    fn test_action_deadline_takes_the_tighter_bound() {
+        let cap = Duration::from_secs(60);
+        let mut request = Request::new(());
+        assert_eq!(action_deadline(&request, cap), cap);
+
+        request.metadata_mut().insert("grpc-timeout", "5S".parse().unwrap());
+        assert_eq!(action_deadline(&request, cap), Duration::from_secs(5));
+
+        request.metadata_mut().insert("grpc-timeout", "2H".parse().unwrap());
+        assert_eq!(action_deadline(&request, cap), cap);
+    }
 }
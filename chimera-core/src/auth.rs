@@ -0,0 +1,181 @@
+/// HTTP Authentication - "Answer the Prompt Chrome Would Show"
+///
+/// A site behind HTTP Basic/Digest auth (or an authenticated egress proxy
+/// Chrome talks to directly) makes Chrome open a login dialog, which no
+/// automation can click. With `Fetch.enable { handleAuthRequests: true }` the
+/// challenge arrives as `Fetch.authRequired` instead, and is answered with
+/// `Fetch.continueWithAuth`.
+///
+/// Credentials are registered per origin (`BrowserSession::set_credentials`).
+/// A challenge from an origin nobody registered is cancelled rather than left
+/// hanging, so the page gets its 401 and the session carries on.
+
+use anyhow::Result;
+use headless_chrome::protocol::cdp::types::Event;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use tracing::{debug, info, warn};
+
+/// How to answer one `Fetch.authRequired`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthAnswer {
+    /// `ProvideCredentials` with this username and password
+    Provide(String, String),
+
+    /// `CancelAuth`: the request fails with the server's 401/407
+    Cancel,
+}
+
+impl AuthAnswer {
+    /// `authChallengeResponse` for `Fetch.continueWithAuth`
+    pub fn to_challenge_response(&self) -> serde_json::Value {
+        match self {
+            Self::Provide(username, password) => serde_json::json!({
+                "response": "ProvideCredentials",
+                "username": username,
+                "password": password,
+            }),
+            Self::Cancel => serde_json::json!({ "response": "CancelAuth" }),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Normalized origin -> (username, password)
+    credentials: HashMap<String, (String, String)>,
+
+    /// Tabs already answering auth challenges from here
+    tabs: HashSet<String>,
+}
+
+/// A session's HTTP auth credentials, keyed by origin
+#[derive(Debug, Default)]
+pub struct CredentialStore {
+    inner: Mutex<Inner>,
+}
+
+impl CredentialStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register credentials for `origin` (`https://host[:port]`, or a bare
+    /// `host[:port]` for a proxy), replacing any previous ones
+    pub fn set(&self, origin: &str, username: &str, password: &str) -> Result<()> {
+        let origin = normalize_origin(origin)
+            .ok_or_else(|| anyhow::anyhow!("Not an origin: {:?}", origin))?;
+        debug!("Credentials set for {}", origin);
+        self.inner
+            .lock()
+            .unwrap()
+            .credentials
+            .insert(origin, (username.to_string(), password.to_string()));
+        Ok(())
+    }
+
+    /// Whether any credentials are registered
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().credentials.is_empty()
+    }
+
+    /// Answer for a challenge from `origin` (`AuthChallenge.origin`)
+    pub fn answer(&self, origin: &str) -> AuthAnswer {
+        let inner = self.inner.lock().unwrap();
+        match normalize_origin(origin).and_then(|o| inner.credentials.get(&o)) {
+            Some((username, password)) => AuthAnswer::Provide(username.clone(), password.clone()),
+            None => AuthAnswer::Cancel,
+        }
+    }
+
+    /// Have `tab` hand auth challenges to this store (once per tab)
+    ///
+    /// Enables the Fetch domain with auth handling. headless_chrome's own
+    /// interceptor continues the paused requests that come with it.
+    pub fn attach(self: &Arc<Self>, tab: &Arc<headless_chrome::Tab>) -> Result<()> {
+        if !self.inner.lock().unwrap().tabs.insert(tab.get_target_id().to_string()) {
+            return Ok(());
+        }
+
+        tab.call_method("Fetch.enable", serde_json::json!({
+            "patterns": [{ "urlPattern": "*" }],
+            "handleAuthRequests": true,
+        }))
+        .map_err(|e| anyhow::anyhow!("Failed to enable auth handling on tab {}: {}", tab.get_target_id(), e))?;
+
+        // Weak: the tab owns this listener
+        let store = Arc::clone(self);
+        let weak_tab: Weak<headless_chrome::Tab> = Arc::downgrade(tab);
+        tab.add_event_listener(Arc::new(move |event: &Event| {
+            let Event::FetchAuthRequired(e) = event else {
+                return;
+            };
+            let Some(tab) = weak_tab.upgrade() else {
+                return;
+            };
+            let challenge = &e.params.auth_challenge;
+            let answer = store.answer(&challenge.origin);
+            match &answer {
+                AuthAnswer::Provide(username, _) => {
+                    info!("🔑 Answering {} auth challenge from {} as {}", challenge.scheme, challenge.origin, username)
+                }
+                AuthAnswer::Cancel => {
+                    warn!("🔑 No credentials for {} auth challenge from {}, cancelling", challenge.scheme, challenge.origin)
+                }
+            }
+            let result = tab.call_method("Fetch.continueWithAuth", serde_json::json!({
+                "requestId": e.params.request_id,
+                "authChallengeResponse": answer.to_challenge_response(),
+            }));
+            if let Err(e) = result {
+                warn!("Failed to answer auth challenge from {}: {}", challenge.origin, e);
+            }
+        }))
+        .map_err(|e| anyhow::anyhow!("Failed to listen for auth challenges on tab {}: {}", tab.get_target_id(), e))?;
+        Ok(())
+    }
+}
+
+/// `scheme://host:port` with the default port filled in, lowercased
+///
+/// Chrome reports origins as `https://example.com` (default port omitted);
+/// proxies are given without a scheme, as `host:port`.
+pub fn normalize_origin(origin: &str) -> Option<String> {
+    let origin = origin.trim().trim_end_matches('/');
+    let with_scheme = if origin.contains("://") {
+        origin.to_string()
+    } else {
+        format!("http://{}", origin)
+    };
+    let url = url::Url::parse(&with_scheme).ok()?;
+    let host = url.host_str().filter(|h| !h.is_empty())?;
+    let port = url.port_or_known_default()?;
+    Some(format!("{}://{}:{}", url.scheme(), host.to_ascii_lowercase(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials_match_by_origin() {
+        let store = CredentialStore::new();
+        assert!(store.is_empty());
+        store.set("https://Intranet.example.com/", "alice", "s3cret").unwrap();
+        store.set("proxy.example.net:3128", "pool", "pw").unwrap();
+        assert!(store.set("not a url", "a", "b").is_err());
+
+        let alice = AuthAnswer::Provide("alice".to_string(), "s3cret".to_string());
+        assert_eq!(store.answer("https://intranet.example.com"), alice);
+        assert_eq!(store.answer("https://intranet.example.com:443"), alice);
+        assert_eq!(store.answer("http://intranet.example.com"), AuthAnswer::Cancel);
+        assert_eq!(store.answer("https://other.example.com"), AuthAnswer::Cancel);
+        assert_eq!(
+            store.answer("http://proxy.example.net:3128"),
+            AuthAnswer::Provide("pool".to_string(), "pw".to_string())
+        );
+
+        assert_eq!(AuthAnswer::Cancel.to_challenge_response()["response"], "CancelAuth");
+        assert_eq!(alice.to_challenge_response()["username"], "alice");
+    }
+}
@@ -0,0 +1,173 @@
+//! chimera-repl - drive a BrowserSession by hand
+//!
+//! Opens one session (behind its own Phantom Sidecar, configured from the
+//! same `CHIMERA_*` environment as the agent) and reads commands from stdin.
+//! Useful for exploring a target's accessibility tree and trying selectors
+//! before writing a gRPC client.
+//!
+//! Usage: `chimera-repl [headless|xvfb|headful]`
+
+use chimera_core::browser::{BrowserSession, LaunchMode};
+use chimera_core::config::ChimeraConfig;
+use chimera_core::seed::SessionSeed;
+use chimera_core::stealth_transport::StealthProxy;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing::{error, Level};
+
+const HELP: &str = "\
+commands:
+  nav <url>             navigate the active tab
+  click <role> [name]   click an AX node by role and (fuzzy) name
+  type <text>           type into the focused element
+  ax                    dump the accessibility tree as an outline
+  shot <path>           save a PNG screenshot
+  roi <role> [name]     print the region (x, y, w, h) covering matching nodes
+  help                  show this list
+  quit                  close the browser and exit";
+
+/// One line of REPL input
+#[derive(Debug, PartialEq)]
+enum Command<'a> {
+    Nav(&'a str),
+    Click(&'a str, Option<&'a str>),
+    Type(&'a str),
+    Ax,
+    Shot(&'a str),
+    Roi(&'a str, Option<&'a str>),
+    Help,
+    Quit,
+}
+
+impl<'a> Command<'a> {
+    /// Parse a line; `Ok(None)` for a blank line
+    fn parse(line: &'a str) -> Result<Option<Self>, String> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        let required = |what: &str| (!rest.is_empty()).then_some(rest).ok_or(format!("usage: {} {}", verb, what));
+        let role_and_name = || -> Result<(&'a str, Option<&'a str>), String> {
+            let rest = required("<role> [name]")?;
+            let (role, name) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            let name = name.trim();
+            Ok((role, (!name.is_empty()).then_some(name)))
+        };
+
+        let command = match verb {
+            "nav" => Self::Nav(required("<url>")?),
+            "click" => {
+                let (role, name) = role_and_name()?;
+                Self::Click(role, name)
+            }
+            "type" => Self::Type(required("<text>")?),
+            "ax" => Self::Ax,
+            "shot" => Self::Shot(required("<path>")?),
+            "roi" => {
+                let (role, name) = role_and_name()?;
+                Self::Roi(role, name)
+            }
+            "help" | "?" => Self::Help,
+            "quit" | "exit" => Self::Quit,
+            other => return Err(format!("unknown command '{}' (try 'help')", other)),
+        };
+        Ok(Some(command))
+    }
+}
+
+/// Run one command against the session; `Ok(false)` once asked to quit
+async fn run(session: &BrowserSession, command: Command<'_>) -> anyhow::Result<bool> {
+    match command {
+        Command::Nav(url) => {
+            session.navigate(url)?;
+            println!("{} - {}", session.get_url()?, session.get_title()?);
+        }
+        Command::Click(role, name) => {
+            session.click_by_role(role, name).await?;
+            println!("clicked {} {:?}", role, name.unwrap_or(""));
+        }
+        Command::Type(text) => session.human_type(text).await?,
+        Command::Ax => {
+            let tree = session.cortex()?.snapshot_accessibility_tree()?;
+            println!("{}", tree.to_markdown(usize::MAX));
+        }
+        Command::Shot(path) => {
+            std::fs::write(path, session.capture_screenshot()?)?;
+            println!("saved {}", path);
+        }
+        Command::Roi(role, name) => match session.cortex()?.find_roi(role, name)? {
+            Some((x, y, width, height)) => println!("x={:.0} y={:.0} w={:.0} h={:.0}", x, y, width, height),
+            None => println!("no rendered {} nodes", role),
+        },
+        Command::Help => println!("{}", HELP),
+        Command::Quit => return Ok(false),
+    }
+    Ok(true)
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = ChimeraConfig::from_env()?;
+    tracing_subscriber::fmt()
+        .with_max_level(Level::WARN)
+        .with_writer(std::io::stderr)
+        .init();
+
+    let mode = match std::env::args().nth(1) {
+        Some(name) => LaunchMode::from_name(&name).ok_or(format!("unknown launch mode '{}'", name))?,
+        None => LaunchMode::Headless,
+    };
+
+    // Chrome is pointed at the sidecar, so it has to be listening first
+    let proxy = StealthProxy::from_config(&config)?;
+    let listener = proxy.bind().await?;
+    tokio::spawn(async move {
+        if let Err(e) = proxy.serve_on(listener).await {
+            error!("Phantom Sidecar died: {}", e);
+        }
+    });
+
+    let mut session =
+        BrowserSession::new_async("repl".to_string(), mode, &config, None, None, SessionSeed::random()).await?;
+    println!("🛡️  {}", session.stealth_status());
+    println!("{}", HELP);
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        eprint!("chimera> ");
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        match Command::parse(&line) {
+            Ok(None) => {}
+            Ok(Some(command)) => match run(&session, command).await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => println!("error: {:#}", e),
+            },
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    session.shutdown();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse("  "), Ok(None));
+        assert_eq!(Command::parse("nav https://example.com"), Ok(Some(Command::Nav("https://example.com"))));
+        assert_eq!(Command::parse("click button Sign in"), Ok(Some(Command::Click("button", Some("Sign in")))));
+        assert_eq!(Command::parse("click link"), Ok(Some(Command::Click("link", None))));
+        assert_eq!(Command::parse("type hello world"), Ok(Some(Command::Type("hello world"))));
+        assert_eq!(Command::parse("roi heading"), Ok(Some(Command::Roi("heading", None))));
+        assert_eq!(Command::parse("ax"), Ok(Some(Command::Ax)));
+        assert!(Command::parse("shot").is_err());
+        assert!(Command::parse("dance").is_err());
+    }
+}
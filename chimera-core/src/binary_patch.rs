@@ -6,9 +6,12 @@
 /// This module implements hardcoded binary patching to remove automation
 /// markers from the Chromium binary itself.
 
+use crate::config::ChimeraConfig;
+use crate::stealth_status::MeasureState;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
+use std::sync::OnceLock;
 use tracing::{debug, info, warn};
 
 /// Binary patching configuration
@@ -38,6 +41,13 @@ impl Default for BinaryPatchConfig {
 /// These are the known byte sequences that Chromium uses internally
 /// to mark automation-controlled browsers. We replace them with
 /// innocuous strings that don't trigger detection.
+/// 
+/// Replacements may be shorter than the original: the tail is filled with
+/// `pad_with`, so no byte after the match moves and every offset in the
+/// binary stays valid. With the default NUL padding a C-string literal simply
+/// reads as the shorter string, so such patterns only touch standalone C
+/// strings (NUL on both sides); an occurrence inside a longer string is left
+/// alone. Longer replacements can't be made relocation-safe and are skipped.
 pub struct PatchPattern {
     /// Original byte sequence (automation marker)
     pub original: Vec<u8>,
@@ -47,51 +57,106 @@ pub struct PatchPattern {
     
     /// Description of what this patch does
     pub description: &'static str,
+    
+    /// Fills the gap when `replacement` is shorter than `original` (default NUL)
+    pub pad_with: u8,
 }
 
 impl PatchPattern {
+    /// Pattern padded with NUL bytes when the replacement is shorter
+    pub fn new(original: &[u8], replacement: &[u8], description: &'static str) -> Self {
+        Self {
+            original: original.to_vec(),
+            replacement: replacement.to_vec(),
+            description,
+            pad_with: 0x00,
+        }
+    }
+    
+    /// Pad shorter replacements with `byte` instead of NUL (e.g. spaces inside non-C strings)
+    pub fn with_pad(mut self, byte: u8) -> Self {
+        self.pad_with = byte;
+        self
+    }
+    
+    /// Offsets in `data` this pattern rewrites
+    /// 
+    /// A NUL-padded replacement cuts the string it lands in short, so it only
+    /// applies where the match is a whole C string. Elsewhere it would also
+    /// rewrite e.g. "--disable-blink-features=AutomationControlled", and the
+    /// switch would stop matching the feature it is meant to turn off.
+    fn matches(&self, data: &[u8]) -> Vec<usize> {
+        let truncates = self.replacement.len() < self.original.len() && self.pad_with == 0x00;
+        find_matches(data, &self.original)
+            .into_iter()
+            .filter(|&offset| {
+                !truncates
+                    || ((offset == 0 || data[offset - 1] == 0x00)
+                        && data.get(offset + self.original.len()) == Some(&0x00))
+            })
+            .collect()
+    }
+    
     /// Get all known patch patterns for Chromium
     pub fn all_patterns() -> Vec<Self> {
         vec![
             // Pattern 1: navigator.webdriver internal string
             // Chromium stores "webdriver" as an internal property name
             // We replace it with a unique, innocuous identifier
-            PatchPattern {
-                original: b"webdriver".to_vec(),
-                replacement: b"__chimera_internal__".to_vec(),
-                description: "Replace 'webdriver' internal string with innocuous identifier",
-            },
+            PatchPattern::new(
+                b"webdriver",
+                b"__chimera_internal__",
+                "Replace 'webdriver' internal string with innocuous identifier",
+            ),
             
             // Pattern 2: "Headless" in error stack traces
             // V8 isolate metadata contains "Headless" in stack traces
             // We replace it with a generic identifier
-            PatchPattern {
-                original: b"Headless".to_vec(),
-                replacement: b"Standard".to_vec(),
-                description: "Replace 'Headless' in V8 isolate metadata",
-            },
+            PatchPattern::new(
+                b"Headless",
+                b"Standard",
+                "Replace 'Headless' in V8 isolate metadata",
+            ),
             
             // Pattern 3: "CDP" (Chrome DevTools Protocol) references
             // Internal CDP markers can leak automation
             // We replace with generic protocol identifier
-            PatchPattern {
-                original: b"CDP".to_vec(),
-                replacement: b"PRO".to_vec(), // "PRO" = Protocol
-                description: "Replace 'CDP' references in internal metadata",
-            },
+            PatchPattern::new(
+                b"CDP",
+                b"PRO", // "PRO" = Protocol
+                "Replace 'CDP' references in internal metadata",
+            ),
             
             // Pattern 4: Automation-controlled flag
             // Binary flag that marks browser as automation-controlled
             // We flip the bit or replace the flag
-            PatchPattern {
-                original: b"AutomationControlled".to_vec(),
-                replacement: b"UserControlled".to_vec(),
-                description: "Replace 'AutomationControlled' flag with 'UserControlled'",
-            },
+            // Shorter replacement: NUL-padded, so C-string readers see "UserControlled";
+            // only standalone literals, never the blink feature name inside a switch
+            PatchPattern::new(
+                b"AutomationControlled",
+                b"UserControlled",
+                "Replace 'AutomationControlled' flag with 'UserControlled'",
+            ),
         ]
     }
 }
 
+/// Bytes of surrounding context reported on each side of a `PatchHit`
+const HIT_CONTEXT_BYTES: usize = 16;
+
+/// One place `BinaryPatcher::patch` would rewrite
+#[derive(Debug, Clone)]
+pub struct PatchHit {
+    /// Byte offset of the match in the binary
+    pub offset: usize,
+    
+    /// `PatchPattern::description` of the pattern that matched
+    pub pattern_desc: &'static str,
+    
+    /// The matched bytes plus up to `HIT_CONTEXT_BYTES` on either side
+    pub context: Vec<u8>,
+}
+
 /// Binary patcher
 pub struct BinaryPatcher {
     config: BinaryPatchConfig,
@@ -107,6 +172,40 @@ impl BinaryPatcher {
         }
     }
     
+    /// Dry run: every offset `patch` would change, without writing anything
+    /// 
+    /// Use this to validate the patterns against a new Chromium build before
+    /// letting `patch` loose on it.
+    pub fn plan(&self) -> Result<Vec<PatchHit>> {
+        let chromium_path = Path::new(&self.config.chromium_path);
+        if !chromium_path.exists() {
+            return Ok(Vec::new());
+        }
+        
+        let binary_data = fs::read(chromium_path)
+            .context("Failed to read Chromium binary for planning")?;
+        Ok(self.plan_data(&binary_data))
+    }
+    
+    /// `plan` over in-memory binary data
+    pub fn plan_data(&self, data: &[u8]) -> Vec<PatchHit> {
+        self.patterns
+            .iter()
+            .filter(|pattern| pattern.replacement.len() <= pattern.original.len())
+            .flat_map(|pattern| {
+                pattern.matches(data).into_iter().map(move |offset| {
+                    let start = offset.saturating_sub(HIT_CONTEXT_BYTES);
+                    let end = (offset + pattern.original.len() + HIT_CONTEXT_BYTES).min(data.len());
+                    PatchHit {
+                        offset,
+                        pattern_desc: pattern.description,
+                        context: data[start..end].to_vec(),
+                    }
+                })
+            })
+            .collect()
+    }
+    
     /// Patch the Chromium binary
     pub fn patch(&self) -> Result<()> {
         if !self.config.enabled {
@@ -164,31 +263,39 @@ impl BinaryPatcher {
     }
     
     /// Apply a single patch pattern to binary data
-    fn apply_pattern(&self, data: &mut Vec<u8>, pattern: &PatchPattern) -> Result<usize> {
-        let mut replacements = 0;
+    /// 
+    /// Every replacement occupies exactly the original's bytes (shorter ones
+    /// are padded), so the binary never changes size.
+    fn apply_pattern(&self, data: &mut [u8], pattern: &PatchPattern) -> Result<usize> {
         let pattern_len = pattern.original.len();
         let replacement_len = pattern.replacement.len();
         
-        // Find all occurrences of the pattern
-        let mut i = 0;
-        while i <= data.len().saturating_sub(pattern_len) {
-            if data[i..i + pattern_len] == pattern.original[..] {
-                // Found a match - replace it
-                if replacement_len == pattern_len {
-                    // Same length - direct replacement
-                    data[i..i + pattern_len].copy_from_slice(&pattern.replacement);
-                    replacements += 1;
-                    i += pattern_len;
-                } else {
-                    // Different length - need to resize
-                    // For now, we only support same-length replacements
-                    // (Different-length would require more complex binary manipulation)
-                    warn!("Pattern '{}' has different length replacement, skipping", pattern.description);
-                    i += 1;
-                }
-            } else {
-                i += 1;
-            }
+        if pattern_len == 0 {
+            return Ok(0);
+        }
+        if replacement_len > pattern_len {
+            // Growing would shift every following byte and break offsets
+            warn!(
+                "Pattern '{}': replacement is {} bytes longer than the original, skipping",
+                pattern.description, replacement_len - pattern_len
+            );
+            return Ok(0);
+        }
+        
+        let mut patched = pattern.replacement.clone();
+        patched.resize(pattern_len, pattern.pad_with);
+        
+        let offsets = pattern.matches(data);
+        for &offset in &offsets {
+            data[offset..offset + pattern_len].copy_from_slice(&patched);
+        }
+        let replacements = offsets.len();
+        
+        if replacements > 0 && replacement_len < pattern_len {
+            warn!(
+                "⚠️  Pattern '{}': replacement is {} bytes shorter, padded {} matches with 0x{:02x} - only safe for C-string literals",
+                pattern.description, pattern_len - replacement_len, replacements, pattern.pad_with
+            );
         }
         
         Ok(replacements)
@@ -207,7 +314,7 @@ impl BinaryPatcher {
         
         // Check if any original patterns still exist
         for pattern in &self.patterns {
-            if binary_data.windows(pattern.original.len()).any(|window| window == pattern.original.as_slice()) {
+            if !pattern.matches(&binary_data).is_empty() {
                 warn!("Pattern '{}' still found in binary - patch may have failed", pattern.description);
                 return Ok(false);
             }
@@ -218,22 +325,76 @@ impl BinaryPatcher {
     }
 }
 
+/// Non-overlapping offsets of `needle` in `data`, in order
+fn find_matches(data: &[u8], needle: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    if needle.is_empty() {
+        return offsets;
+    }
+    
+    let mut i = 0;
+    while i + needle.len() <= data.len() {
+        if data[i..i + needle.len()] == *needle {
+            offsets.push(i);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    offsets
+}
+
+/// Outcome of `initialize_binary_patching` in this process (reported per session)
+static PATCH_OUTCOME: OnceLock<MeasureState> = OnceLock::new();
+
 /// Initialize binary patching (called at build/runtime)
-pub fn initialize_binary_patching() -> Result<()> {
+pub fn initialize_binary_patching(chimera: &ChimeraConfig) -> Result<()> {
     let config = BinaryPatchConfig {
-        chromium_path: std::env::var("CHROME_BIN")
-            .unwrap_or_else(|_| "/usr/bin/chromium".to_string()),
-        enabled: std::env::var("CHIMERA_BINARY_PATCH")
-            .unwrap_or_else(|_| "true".to_string())
-            .parse::<bool>()
-            .unwrap_or(true),
+        chromium_path: chimera.chrome_bin.clone(),
+        enabled: chimera.binary_patch,
         backup: true,
     };
     
+    let outcome = if !config.enabled {
+        MeasureState::Skipped("CHIMERA_BINARY_PATCH=false".to_string())
+    } else if !Path::new(&config.chromium_path).exists() {
+        MeasureState::Skipped(format!("binary not found at {}", config.chromium_path))
+    } else {
+        MeasureState::Applied
+    };
+    
     let patcher = BinaryPatcher::new(config);
-    patcher.patch()?;
     
-    Ok(())
+    // Log what's about to change before touching the binary
+    if outcome.is_applied() {
+        match patcher.plan() {
+            Ok(hits) => {
+                info!("📋 Binary patch plan: {} sites", hits.len());
+                for hit in &hits {
+                    debug!(
+                        "  0x{:08x} '{}': {}",
+                        hit.offset, hit.pattern_desc, String::from_utf8_lossy(&hit.context).escape_debug()
+                    );
+                }
+            }
+            Err(e) => warn!("Failed to plan binary patches: {:#}", e),
+        }
+    }
+    
+    let result = patcher.patch();
+    let _ = PATCH_OUTCOME.set(match &result {
+        Ok(_) => outcome,
+        Err(e) => MeasureState::Failed(format!("{:#}", e)),
+    });
+    result
+}
+
+/// Binary patching state for this process
+pub fn patch_status() -> MeasureState {
+    PATCH_OUTCOME
+        .get()
+        .cloned()
+        .unwrap_or_else(|| MeasureState::Skipped("not run in this process (expected at image build)".to_string()))
 }
 
 #[cfg(test)]
@@ -243,16 +404,70 @@ mod tests {
     #[test]
     fn test_pattern_matching() {
         let mut data = b"webdriver test".to_vec();
-        let pattern = PatchPattern {
-            original: b"webdriver".to_vec(),
-            replacement: b"__chimera__".to_vec(),
-            description: "Test pattern",
-        };
+        let pattern = PatchPattern::new(b"webdriver", b"__chimera", "Test pattern");
         
         let patcher = BinaryPatcher::new(BinaryPatchConfig::default());
         let replacements = patcher.apply_pattern(&mut data, &pattern).unwrap();
         
         assert_eq!(replacements, 1);
-        assert_eq!(data, b"__chimera__ test");
+        assert_eq!(data, b"__chimera test");
+    }
+    
+    #[test]
+    fn test_shorter_replacement_is_nul_padded() {
+        let mut data = b"\0webdriver\0\x02".to_vec();
+        let pattern = PatchPattern::new(b"webdriver", b"nav", "Test pattern");
+        
+        let patcher = BinaryPatcher::new(BinaryPatchConfig::default());
+        let replacements = patcher.apply_pattern(&mut data, &pattern).unwrap();
+        
+        assert_eq!(replacements, 1);
+        assert_eq!(data, b"\0nav\0\0\0\0\0\0\0\x02");
+        
+        // A C reader stops at the first NUL
+        let c_str = std::ffi::CStr::from_bytes_until_nul(&data[1..]).unwrap();
+        assert_eq!(c_str.to_str().unwrap(), "nav");
+    }
+    
+    #[test]
+    fn test_nul_padding_skips_strings_it_would_truncate() {
+        let mut data = b"\0--disable-blink-features=AutomationControlled\0AutomationControlledBrowser\0".to_vec();
+        let original = data.clone();
+        let pattern = PatchPattern::new(b"AutomationControlled", b"UserControlled", "Test pattern");
+        
+        let patcher = BinaryPatcher::new(BinaryPatchConfig::default());
+        assert_eq!(patcher.apply_pattern(&mut data, &pattern).unwrap(), 0);
+        assert_eq!(data, original);
+        
+        // A standalone literal is still patched
+        let mut data = b"\0AutomationControlled\0".to_vec();
+        assert_eq!(patcher.apply_pattern(&mut data, &pattern).unwrap(), 1);
+        assert_eq!(data, b"\0UserControlled\0\0\0\0\0\0\0");
+    }
+    
+    #[test]
+    fn test_plan_reports_without_writing() {
+        let data = b"navigator.webdriver; Headless".to_vec();
+        let patcher = BinaryPatcher::new(BinaryPatchConfig::default());
+        let hits = patcher.plan_data(&data);
+        
+        // "webdriver" has a longer replacement and is never patched
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].offset, 21);
+        assert_eq!(hits[0].pattern_desc, "Replace 'Headless' in V8 isolate metadata");
+        assert_eq!(hits[0].context, b"navigator.webdriver; Headless"[5..].to_vec());
+        assert_eq!(data, b"navigator.webdriver; Headless");
+    }
+    
+    #[test]
+    fn test_longer_replacement_is_skipped() {
+        let mut data = b"webdriver test".to_vec();
+        let pattern = PatchPattern::new(b"webdriver", b"__chimera_internal__", "Test pattern");
+        
+        let patcher = BinaryPatcher::new(BinaryPatchConfig::default());
+        let replacements = patcher.apply_pattern(&mut data, &pattern).unwrap();
+        
+        assert_eq!(replacements, 0);
+        assert_eq!(data, b"webdriver test");
     }
 }
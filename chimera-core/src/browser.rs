@@ -1,33 +1,473 @@
+use crate::auth::CredentialStore;
+use crate::config::{ChimeraConfig, ImpersonationTarget};
+use crate::dbi::WebRtcPolicy;
+use crate::download::DownloadTracker;
+use crate::error::ChimeraError;
+use crate::ghost_mouse::MotorProfile;
+use crate::identity_grafting::{plugins_for_browser, BrowserFingerprint, CookieRecord, IdentityGrafting, ProfileLease, SyntheticProfile};
+use crate::pacing::PacingGovernor;
+use crate::seed::{SeedStream, SessionSeed};
+use crate::stealth_status::{MeasureState, StealthStatus};
 use anyhow::Context;
 use headless_chrome::{Browser, LaunchOptions};
-use std::sync::Arc;
-use tracing::{debug, error, info};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, error, info, warn};
 use sha2::{Sha256, Digest};
 use hex;
 
+/// Chance that a micro-fidget drifts toward the nearest element instead of randomly
+const FIDGET_ATTRACT_PROBABILITY: f64 = 0.2;
+
+/// How far (px) a fidget looks for an element to drift toward
+const FIDGET_ATTRACT_RADIUS: f64 = 150.0;
+
+/// AX roles a resting hand drifts toward; only these get their boxes looked up
+const FIDGET_ATTRACT_ROLES: &[&str] = &["link", "button", "heading", "textbox", "checkbox", "image"];
+
+/// Pages (visual hashes) a session remembers for `recall_page`
+const RECENT_PAGE_MEMORY: usize = 32;
+
+/// How often the session looks for tabs the page opened (they wait, paused, until prepared)
+const NEW_TAB_POLL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Screenshot encoding
+/// 
+/// PNG is lossless (needed for AX-overlay work and visual hashing), but large on
+/// photographic pages. JPEG/WebP shrink payloads sent to clients and the vision service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    /// JPEG with quality 0-100
+    Jpeg(u32),
+    /// WebP with quality 0-100
+    Webp(u32),
+}
+
+impl ScreenshotFormat {
+    /// Parse a format name ("png", "jpeg"/"jpg", "webp") with an optional quality.
+    /// Quality defaults to 80 for lossy formats and is clamped to 0-100.
+    pub fn from_name(name: &str, quality: Option<u32>) -> Option<Self> {
+        let quality = quality.unwrap_or(80).min(100);
+        match name.to_ascii_lowercase().as_str() {
+            "" | "png" => Some(Self::Png),
+            "jpeg" | "jpg" => Some(Self::Jpeg(quality)),
+            "webp" => Some(Self::Webp(quality)),
+            _ => None,
+        }
+    }
+}
+
+/// Modifier key held during `BrowserSession::press_key`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Alt,
+    Ctrl,
+    Meta,
+    Shift,
+}
+
+impl Modifier {
+    /// Bit in CDP's `Input.dispatchKeyEvent` `modifiers` mask
+    pub fn bit(self) -> u32 {
+        match self {
+            Self::Alt => 1,
+            Self::Ctrl => 2,
+            Self::Meta => 4,
+            Self::Shift => 8,
+        }
+    }
+    
+    /// Combined `modifiers` mask for a key combo
+    pub fn mask(modifiers: &[Modifier]) -> u32 {
+        modifiers.iter().fold(0, |mask, m| mask | m.bit())
+    }
+    
+    /// Modifier for editing shortcuts (select-all, copy) on `os`: Cmd on macOS, Ctrl elsewhere
+    pub fn shortcut_for_os(os: &str) -> Self {
+        if os.to_ascii_lowercase().contains("mac") {
+            Self::Meta
+        } else {
+            Self::Ctrl
+        }
+    }
+}
+
+/// CDP description of a key: DOM `key`, DOM `code`, Windows virtual key code, typed text
+struct KeyDefinition {
+    key: String,
+    code: String,
+    key_code: u32,
+    text: Option<String>,
+}
+
+impl KeyDefinition {
+    /// Resolve a named key ("Enter", "ArrowDown", "F5") or a single character ("a", "7")
+    fn resolve(key: &str) -> Option<Self> {
+        let named = |code: &str, key_code: u32, text: Option<&str>| Self {
+            key: key.to_string(),
+            code: code.to_string(),
+            key_code,
+            text: text.map(str::to_string),
+        };
+        
+        let definition = match key {
+            "Enter" => named("Enter", 13, Some("\r")),
+            "Tab" => named("Tab", 9, None),
+            "Escape" => named("Escape", 27, None),
+            "Backspace" => named("Backspace", 8, None),
+            "Delete" => named("Delete", 46, None),
+            "Space" | " " => Self {
+                key: " ".to_string(),
+                code: "Space".to_string(),
+                key_code: 32,
+                text: Some(" ".to_string()),
+            },
+            "ArrowLeft" => named("ArrowLeft", 37, None),
+            "ArrowUp" => named("ArrowUp", 38, None),
+            "ArrowRight" => named("ArrowRight", 39, None),
+            "ArrowDown" => named("ArrowDown", 40, None),
+            "Home" => named("Home", 36, None),
+            "End" => named("End", 35, None),
+            "PageUp" => named("PageUp", 33, None),
+            "PageDown" => named("PageDown", 34, None),
+            _ => {
+                if let Some(n) = key.strip_prefix('F').and_then(|n| n.parse::<u32>().ok()).filter(|n| (1..=12).contains(n)) {
+                    return Some(named(key, 111 + n, None));
+                }
+                
+                let mut chars = key.chars();
+                let (Some(c), None) = (chars.next(), chars.next()) else {
+                    return None;
+                };
+                let code = if c.is_ascii_alphabetic() {
+                    format!("Key{}", c.to_ascii_uppercase())
+                } else if c.is_ascii_digit() {
+                    format!("Digit{}", c)
+                } else {
+                    String::new()
+                };
+                Self {
+                    key: key.to_string(),
+                    code,
+                    key_code: if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() as u32 } else { 0 },
+                    text: Some(key.to_string()),
+                }
+            }
+        };
+        Some(definition)
+    }
+}
+
+/// How Chrome is launched for a session
+/// 
+/// Detection scripts tell true headless Chrome apart from a headed browser
+/// (missing `chrome.runtime`, permission quirks, `HeadlessChrome` leaks), so
+/// a headed browser on a virtual display is often the stealthier choice.
+/// 
+/// Stealth measures per mode:
+/// - `Headless`: everything (Biological BIOS, DBI canvas/WebGL noise).
+/// - `HeadfulXvfb`: everything. Under Xvfb the process still runs on server
+///   hardware with software rendering, so the hardware mask and canvas noise
+///   are still needed.
+/// - `Headful`: the Biological BIOS is skipped (the hardware and screen are a
+///   real desktop's, and overriding them only risks inconsistencies), and
+///   `--disable-gpu` is dropped. DBI noise stays so sessions can't be linked
+///   by canvas hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LaunchMode {
+    #[default]
+    Headless,
+    /// Headed Chrome on an existing virtual display (`DISPLAY` must be set, e.g. under `xvfb-run`)
+    HeadfulXvfb,
+    /// Headed Chrome on a real desktop
+    Headful,
+}
+
+impl LaunchMode {
+    /// Parse a mode name ("headless", "xvfb"/"headful-xvfb", "headful")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "headless" => Some(Self::Headless),
+            "xvfb" | "headful-xvfb" | "headful_xvfb" => Some(Self::HeadfulXvfb),
+            "headful" => Some(Self::Headful),
+            _ => None,
+        }
+    }
+    
+    /// The legacy `headless` flag: true is `Headless`, false is `HeadfulXvfb`
+    /// 
+    /// Callers asking for a headed browser run on servers, under a virtual
+    /// display, where the full stealth set still applies; a real desktop
+    /// (`Headful`) has to be asked for by name.
+    pub fn from_headless(headless: bool) -> Self {
+        if headless { Self::Headless } else { Self::HeadfulXvfb }
+    }
+    
+    pub fn is_headless(self) -> bool {
+        self == Self::Headless
+    }
+}
+
+/// One open page in a session, as reported by `BrowserSession::list_tabs`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabInfo {
+    pub target_id: String,
+    pub url: String,
+    pub title: String,
+    pub active: bool,
+}
+
+/// Emulated screen: viewport size, pixel density and mobile mode
+/// 
+/// The same values drive Chrome's device metrics and the `screen` /
+/// `devicePixelRatio` reported by the Biological BIOS, so a profile claiming a
+/// 2560x1600 Mac doesn't render into a 1920x1080 window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportConfig {
+    pub width: u32,
+    pub height: u32,
+    pub device_scale_factor: f64,
+    pub mobile: bool,
+}
+
+impl Default for ViewportConfig {
+    fn default() -> Self {
+        Self {
+            width: 1920,
+            height: 1080,
+            device_scale_factor: 1.0,
+            mobile: false,
+        }
+    }
+}
+
+impl ViewportConfig {
+    /// Match a grafted profile's claimed screen (Macs report Retina density)
+    /// 
+    /// The profile's resolution is in device pixels, so a 2560x1600 Retina
+    /// Mac lays out a 1280x800 CSS viewport.
+    pub fn from_profile(profile: &SyntheticProfile) -> Self {
+        let os = profile.metadata.os.to_ascii_lowercase();
+        let (width, height) = profile.metadata.viewport;
+        let device_scale_factor = if os.contains("mac") || os.contains("ios") { 2.0 } else { 1.0 };
+        Self {
+            width: (width as f64 / device_scale_factor).round() as u32,
+            height: (height as f64 / device_scale_factor).round() as u32,
+            device_scale_factor,
+            mobile: os.contains("ios") || os.contains("android"),
+        }
+    }
+    
+    /// Map a point in a screenshot (device pixels) to the CSS pixels input events use
+    pub fn to_css(&self, x: i32, y: i32) -> (i32, i32) {
+        let scale = self.device_scale_factor;
+        ((x as f64 / scale).round() as i32, (y as f64 / scale).round() as i32)
+    }
+    
+    /// Parse "WIDTHxHEIGHT" with an optional "@SCALE" suffix (e.g. "2560x1600@2")
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (size, scale) = match spec.trim().split_once('@') {
+            Some((size, scale)) => (size, scale.trim().parse::<f64>().ok().filter(|s| *s > 0.0)?),
+            None => (spec.trim(), 1.0),
+        };
+        let (width, height) = size.split_once(['x', 'X'])?;
+        let width: u32 = width.trim().parse().ok().filter(|w| *w > 0)?;
+        let height: u32 = height.trim().parse().ok().filter(|h| *h > 0)?;
+        Some(Self {
+            width,
+            height,
+            device_scale_factor: scale,
+            mobile: false,
+        })
+    }
+}
+
 pub struct BrowserSession {
     browser: Browser,
     session_id: String,
+    
+    /// Session-wide action cadence (shared so it can be awaited without holding the session lock)
+    pacer: Arc<PacingGovernor>,
+    
+    /// Master seed every source of randomness in the session derives from
+    seed: SessionSeed,
+    
+    /// Mouse stream: trajectories, fidgets, scrolls
+    mouse_rng: Mutex<StdRng>,
+    
+    /// Typing stream: keystroke timing and typos
+    typing_rng: Mutex<StdRng>,
+    
+    /// Which stealth measures actually applied at launch
+    stealth_status: StealthStatus,
+    
+    /// Grafted identity whose cookie jar is written back on close
+    profile: Option<SyntheticProfile>,
+    
+    /// Store the grafted profile's cookies are persisted to
+    identity: Option<Arc<Mutex<IdentityGrafting>>>,
+    
+    /// Swarm lock on the grafted profile, released once its cookies are saved
+    lease: Option<ProfileLease>,
+    
+    /// Where the cursor was last moved to (trajectories start here); shared
+    /// with the Cortexes handed out by `cortex`
+    current_mouse: Arc<Mutex<(f64, f64)>>,
+    
+    /// Motor personality: the grafted identity's, or one picked for this session
+    motor_profile: MotorProfile,
+    
+    /// Emulated screen the session was launched with
+    viewport: ViewportConfig,
+    
+    /// User-Agent pages see; new tabs are held to it
+    user_agent: String,
+    
+    /// `navigator.plugins` names of the claimed browser
+    plugins: &'static [&'static str],
+    
+    /// WebRTC leak handling in the injected scripts
+    webrtc_policy: WebRtcPolicy,
+    
+    /// Target id of the tab actions operate on
+    active_tab: Mutex<String>,
+    
+    /// Tabs that already carry the viewport override and stealth scripts
+    prepared_tabs: Arc<Mutex<HashSet<String>>>,
+    
+    /// Background tasks (e.g. micro-fidgeting) aborted on shutdown
+    background_tasks: Mutex<Vec<tokio::task::AbortHandle>>,
+    
+    /// Visual hashes of recently observed pages, oldest first
+    recent_pages: Mutex<VecDeque<String>>,
+    
+    /// Download capture, once `enable_downloads` has run
+    downloads: Mutex<Option<Arc<DownloadTracker>>>,
+    
+    /// HTTP auth credentials answered on `Fetch.authRequired`
+    credentials: Arc<CredentialStore>,
+    
+    /// Set once `shutdown` has run
+    closed: bool,
+}
+
+/// What every tab of a session is held to: its screen, UA and stealth scripts
+#[derive(Clone)]
+struct TabStealth {
+    viewport: ViewportConfig,
+    user_agent: String,
+    profile: Option<SyntheticProfile>,
+    plugins: &'static [&'static str],
+    seed: SessionSeed,
+    webrtc_policy: WebRtcPolicy,
+}
+
+impl TabStealth {
+    /// Bring `tab` in line with the session, then let it run
+    /// 
+    /// Only CDP overrides and new-document scripts, nothing evaluated in the
+    /// page: a tab paused on start can't answer until it's resumed.
+    fn prepare(&self, tab: &Arc<headless_chrome::Tab>) {
+        let target_id = tab.get_target_id().to_string();
+        if let Err(e) = self.apply(tab) {
+            warn!("Stealth setup incomplete for tab {} (non-fatal): {}", target_id, e);
+        }
+        // A no-op unless the tab is waiting for us (see `watch_new_tabs`)
+        if let Err(e) = tab.call_method("Runtime.runIfWaitingForDebugger", serde_json::json!({})) {
+            warn!("Failed to resume tab {}: {}", target_id, e);
+        }
+    }
+    
+    fn apply(&self, tab: &Arc<headless_chrome::Tab>) -> anyhow::Result<()> {
+        BrowserSession::emulate_viewport(tab, &self.viewport)?;
+        match &self.profile {
+            Some(profile) => BrowserSession::apply_locale(tab, profile, &self.user_agent)?,
+            None => {
+                tab.call_method("Network.setUserAgentOverride", serde_json::json!({ "userAgent": self.user_agent }))
+                    .context("Failed to override User-Agent")?;
+            }
+        }
+        let fingerprint = self.profile.as_ref().map(|p| &p.fingerprint);
+        BrowserSession::inject_bio_bios(tab, &self.viewport, fingerprint, &self.user_agent)?;
+        BrowserSession::spoof_plugins(tab, self.plugins)?;
+        BrowserSession::dbi(self.seed, self.webrtc_policy).inject_hooks(tab)?;
+        if let Some(profile) = &self.profile {
+            BrowserSession::normalize_fonts(tab, profile)?;
+        }
+        Ok(())
+    }
 }
 
 impl BrowserSession {
-    pub fn new(session_id: String, headless: bool) -> anyhow::Result<Self> {
-        info!("Starting browser session: {}", session_id);
+    pub fn new(
+        session_id: String,
+        mode: LaunchMode,
+        config: &ChimeraConfig,
+        profile: Option<SyntheticProfile>,
+        viewport: Option<ViewportConfig>,
+    ) -> anyhow::Result<Self> {
+        Self::new_with_seed(session_id, mode, config, profile, viewport, SessionSeed::random())
+    }
+
+    /// Launch a session whose randomness all derives from `seed`
+    /// 
+    /// Canvas noise, mouse motion, typing and pacing each get a sub-seed (see
+    /// `SessionSeed::derive`), so the same seed gives the same behaviour -
+    /// for tests and replays. A grafted profile's seed
+    /// (`SessionSeed::for_profile`) pins only the canvas stream.
+    pub fn new_with_seed(
+        session_id: String,
+        mode: LaunchMode,
+        config: &ChimeraConfig,
+        profile: Option<SyntheticProfile>,
+        viewport: Option<ViewportConfig>,
+        seed: SessionSeed,
+    ) -> anyhow::Result<Self> {
+        info!("Starting browser session: {} ({:?})", session_id, mode);
+        debug!("Session {} master seed: {:016x} nonce: {:016x}", session_id, seed.master(), seed.nonce());
         
-        // Get proxy port from environment (defaults to 8080)
-        let proxy_port = std::env::var("CHIMERA_PROXY_PORT")
-            .unwrap_or_else(|_| "8080".to_string());
+        if mode == LaunchMode::HeadfulXvfb && std::env::var_os("DISPLAY").is_none() {
+            anyhow::bail!("HeadfulXvfb launch needs a virtual display, but DISPLAY is not set (run under xvfb-run)");
+        }
+        
+        // Explicit viewport wins; otherwise the grafted profile's claimed screen
+        let viewport = viewport
+            .or_else(|| profile.as_ref().map(ViewportConfig::from_profile))
+            .unwrap_or_default();
+        
+        let mut args = vec![
+            format!("--window-size={},{}", viewport.width, viewport.height),
+            "--disable-blink-features=AutomationControlled".to_string(),
+            "--disable-dev-shm-usage".to_string(),
+            "--no-sandbox".to_string(),
+            // CRITICAL: Configure proxy to use Phantom Sidecar
+            format!("--proxy-server={}", config.proxy_url()),
+        ];
+        
+        // A real desktop has a GPU worth exposing; headless and Xvfb don't
+        if mode != LaunchMode::Headful {
+            args.push("--disable-gpu".to_string());
+        }
+        
+        // Hosts that must not be impersonated go direct (CRL/OCSP, telemetry)
+        if !config.no_proxy.is_empty() {
+            args.push(format!("--proxy-bypass-list={}", config.no_proxy.to_chrome_arg()));
+        }
+        
+        // MITM mode: trust the Phantom root CA so re-signed leaves validate
+        if let Some(dir) = &config.mitm_ca_dir {
+            let ca = crate::mitm::CertAuthority::load_or_generate(dir)
+                .context("Failed to load MITM root CA")?;
+            args.push(ca.chrome_trust_arg());
+        }
         
         let launch_options = LaunchOptions {
-            headless,
-            args: vec![
-                "--disable-blink-features=AutomationControlled".to_string(),
-                "--disable-dev-shm-usage".to_string(),
-                "--no-sandbox".to_string(),
-                "--disable-gpu".to_string(),
-                // CRITICAL: Configure proxy to use Phantom Sidecar
-                format!("--proxy-server=http://127.0.0.1:{}", proxy_port),
-            ],
+            headless: mode.is_headless(),
+            args,
             ..Default::default()
         };
 
@@ -38,22 +478,521 @@ impl BrowserSession {
             .wait_for_initial_tab()
             .context("Failed to get initial tab")?;
 
-        // Set viewport size
-        tab.set_viewport_size(1920, 1080)
-            .context("Failed to set viewport")?;
+        // Set viewport size, pixel density and screen dimensions
+        Self::emulate_viewport(&tab, &viewport)?;
+
+        // A missed binary patch still announces "HeadlessChrome" in the default UA
+        let replacement_ua = profile.as_ref()
+            .map(|p| p.fingerprint.user_agent.as_str())
+            .unwrap_or_else(|| config.impersonation_target.user_agent());
+        let (user_agent, ua_leaked) = Self::sanitize_user_agent(&tab, replacement_ua)?;
+
+        // navigator.plugins must list what the claimed browser would
+        let plugins = match &profile {
+            Some(profile) => profile.metadata.plugins(),
+            None => plugins_for_browser(config.impersonation_target.name()),
+        };
 
         // CRITICAL: Inject Biological BIOS (hardware fingerprint masking)
         // This prevents "server-grade" leaks (96 CPUs, 64GB RAM on a "laptop")
-        Self::inject_bio_bios(&tab)?;
+        let bio_bios = match mode {
+            LaunchMode::Headful => MeasureState::Skipped("headful: real hardware".to_string()),
+            _ => MeasureState::from_result(
+                &Self::inject_bio_bios(&tab, &viewport, profile.as_ref().map(|p| &p.fingerprint), &user_agent)
+                    .and_then(|_| Self::spoof_plugins(&tab, plugins)),
+            ),
+        };
 
-        // CRITICAL: Inject DBI hooks for Canvas/WebGL entropy
+        // CRITICAL: Inject DBI hooks for Canvas/WebGL entropy and WebRTC leaks
         // This adds session-unique noise to prevent canvas fingerprinting
-        crate::dbi::initialize_dbi(None).inject_hooks(&tab)?;
+        let dbi_hooks = MeasureState::from_result(
+            &Self::dbi(seed, config.webrtc_policy)
+                .inject_hooks(&tab)
+                .and_then(|_| Self::check_webrtc(&tab, config)),
+        );
+
+        // Lived-in identity: load the profile's cookie jar before any navigation
+        let profile_grafting = match &profile {
+            Some(profile) => MeasureState::from_result(
+                &Self::restore_cookies(&tab, &profile.cookies)
+                    .and_then(|_| Self::normalize_fonts(&tab, profile))
+                    .and_then(|_| Self::check_profile_target(profile, config.impersonation_target)),
+            ),
+            None => MeasureState::Skipped("no profile grafted".to_string()),
+        };
 
-        Ok(Self {
+        // Intl/Date timezone, locale and Accept-Language must agree with the profile
+        let locale_emulation = match &profile {
+            Some(profile) => MeasureState::from_result(&Self::emulate_locale(&tab, profile, &user_agent).and_then(|_| {
+                match &config.header_echo_url {
+                    Some(echo_url) => Self::check_profile_headers(&tab, profile, echo_url),
+                    None => Ok(()),
+                }
+            })),
+            None => MeasureState::Skipped("no profile grafted".to_string()),
+        };
+
+        let binary_patch = if ua_leaked {
+            MeasureState::Failed("default User-Agent said HeadlessChrome (overridden per session)".to_string())
+        } else {
+            crate::binary_patch::patch_status()
+        };
+        let stealth_status = StealthStatus {
+            binary_patch,
+            dbi_hooks,
+            bio_bios,
+            profile_grafting,
+            proxy_routing: Self::check_proxy_routing(config),
+            locale_emulation,
+        };
+        
+        if stealth_status.failures().is_empty() {
+            info!("🛡️  Stealth status for {}: {}", session_id, stealth_status);
+        } else {
+            warn!("🛡️  Stealth status for {} (degraded: {:?}): {}", session_id, stealth_status.failures(), stealth_status);
+        }
+
+        let motor_profile = profile
+            .as_ref()
+            .map_or_else(|| MotorProfile::sample(&mut seed.rng(SeedStream::Motor)), |p| p.motor_profile());
+
+        let session = Self {
             browser,
             session_id,
-        })
+            pacer: Arc::new(PacingGovernor::new(config.pacing.clone()).with_seed(Some(seed.derive(SeedStream::Pacing)))),
+            seed,
+            mouse_rng: Mutex::new(seed.rng(SeedStream::Mouse)),
+            typing_rng: Mutex::new(seed.rng(SeedStream::Typing)),
+            stealth_status,
+            profile,
+            identity: None,
+            lease: None,
+            // Unknown until the first move; centre of the viewport is the likeliest resting spot
+            current_mouse: Arc::new(Mutex::new((viewport.width as f64 / 2.0, viewport.height as f64 / 2.0))),
+            motor_profile,
+            viewport,
+            user_agent,
+            plugins,
+            webrtc_policy: config.webrtc_policy,
+            active_tab: Mutex::new(tab.get_target_id().to_string()),
+            prepared_tabs: Arc::new(Mutex::new(HashSet::from([tab.get_target_id().to_string()]))),
+            background_tasks: Mutex::new(Vec::new()),
+            recent_pages: Mutex::new(VecDeque::with_capacity(RECENT_PAGE_MEMORY)),
+            downloads: Mutex::new(None),
+            credentials: Arc::new(CredentialStore::new()),
+            closed: false,
+        };
+        if let Err(e) = session.watch_new_tabs() {
+            warn!("Tabs opened by pages in session {} run unmasked until switched to: {}", session.session_id, e);
+        }
+        Ok(session)
+    }
+
+    /// DBI hooks with the session's canvas/WebGL noise seed (same for every tab)
+    fn dbi(seed: SessionSeed, webrtc_policy: WebRtcPolicy) -> crate::dbi::DbiManager {
+        crate::dbi::initialize_dbi(Some(crate::dbi::DbiConfig {
+            session_seed: seed.derive(SeedStream::Dbi),
+            webrtc_policy,
+            ..Default::default()
+        }))
+    }
+
+    /// Prove the WebRTC hooks hold before the session is handed out
+    /// 
+    /// Hooks only run in new documents, so this loads a blank one first. The
+    /// upstream proxy's address, when it's an IP literal, is what sites see anyway.
+    fn check_webrtc(tab: &Arc<headless_chrome::Tab>, config: &ChimeraConfig) -> anyhow::Result<()> {
+        if config.webrtc_policy == WebRtcPolicy::Allow {
+            return Ok(());
+        }
+        tab.navigate_to("about:blank")
+            .context("Failed to open a blank page for the WebRTC check")?
+            .wait_until_navigated()
+            .context("Blank page for the WebRTC check never loaded")?;
+        let proxy_ip = config.upstream_proxy.as_ref().and_then(|proxy| proxy.host().parse().ok());
+        crate::dbi::verify_webrtc_leak_free(tab, proxy_ip)
+    }
+
+    /// Apply `viewport` as the tab's device metrics
+    fn emulate_viewport(tab: &Arc<headless_chrome::Tab>, viewport: &ViewportConfig) -> anyhow::Result<()> {
+        tab.call_method("Emulation.setDeviceMetricsOverride", serde_json::json!({
+            "width": viewport.width,
+            "height": viewport.height,
+            "deviceScaleFactor": viewport.device_scale_factor,
+            "mobile": viewport.mobile,
+            "screenWidth": viewport.width,
+            "screenHeight": viewport.height,
+        }))
+        .context("Failed to set viewport")?;
+        Ok(())
+    }
+
+    /// Replace the tab's User-Agent with `replacement` if it gives headless mode away
+    /// 
+    /// Returns the UA pages now see and whether the default one leaked. A leak
+    /// means binary patching missed the "HeadlessChrome" token, so it's logged
+    /// loudly; `Network.setUserAgentOverride` only fixes this tab.
+    fn sanitize_user_agent(tab: &Arc<headless_chrome::Tab>, replacement: &str) -> anyhow::Result<(String, bool)> {
+        let reported = Self::read_user_agent(tab)?;
+        if !reported.contains("Headless") {
+            return Ok((reported, false));
+        }
+        
+        warn!("⚠️  User-Agent leaks headless mode ({}) - binary patching was incomplete, overriding", reported);
+        tab.call_method("Network.setUserAgentOverride", serde_json::json!({ "userAgent": replacement }))
+            .context("Failed to override headless User-Agent")?;
+        let effective = Self::read_user_agent(tab)?;
+        if effective.contains("Headless") {
+            anyhow::bail!("User-Agent still reports headless mode after override: {}", effective);
+        }
+        Ok((effective, true))
+    }
+
+    fn read_user_agent(tab: &Arc<headless_chrome::Tab>) -> anyhow::Result<String> {
+        tab.evaluate("navigator.userAgent", false)
+            .context("Failed to read navigator.userAgent")?
+            .value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .ok_or_else(|| anyhow::anyhow!("navigator.userAgent is not a string"))
+    }
+
+    /// The User-Agent the active tab reports to pages (`navigator.userAgent`)
+    pub fn verify_user_agent(&self) -> anyhow::Result<String> {
+        let tab = self.get_tab()?;
+        Self::read_user_agent(&tab)
+    }
+
+    /// The binary's own default User-Agent (`Browser.getVersion`), untouched
+    /// by per-tab overrides - what tells whether the binary patch took
+    pub fn engine_user_agent(&self) -> anyhow::Result<String> {
+        let version = self.browser.get_version().context("Failed to query Browser.getVersion")?;
+        Ok(version.user_agent)
+    }
+
+    /// Emulated screen this session was launched with
+    pub fn viewport(&self) -> ViewportConfig {
+        self.viewport
+    }
+
+    /// How this session moves the mouse (pass to `NeuromotorMouse::new`)
+    pub fn motor_profile(&self) -> &MotorProfile {
+        &self.motor_profile
+    }
+
+    /// Modifier this session's claimed OS uses for editing shortcuts (see `Modifier::shortcut_for_os`)
+    pub fn shortcut_modifier(&self) -> Modifier {
+        self.profile
+            .as_ref()
+            .map_or(Modifier::Ctrl, |profile| Modifier::shortcut_for_os(&profile.metadata.os))
+    }
+
+    /// Last known cursor position
+    pub fn mouse_position(&self) -> (f64, f64) {
+        *self.current_mouse.lock().unwrap()
+    }
+
+    fn set_mouse_position(&self, x: f64, y: f64) {
+        *self.current_mouse.lock().unwrap() = (x, y);
+    }
+
+    /// Persist the grafted profile's cookies to `identity` when the session closes
+    pub fn with_identity_store(mut self, identity: Arc<Mutex<IdentityGrafting>>) -> Self {
+        self.identity = Some(identity);
+        self
+    }
+
+    /// Hold the grafted profile's swarm lock for the lifetime of the session
+    pub fn with_lease(mut self, lease: ProfileLease) -> Self {
+        self.lease = Some(lease);
+        self
+    }
+
+    /// Apply the profile's timezone, locale and request headers, then read the timezone back
+    /// 
+    /// Without this a profile claiming America/New_York reports UTC from
+    /// `Intl.DateTimeFormat().resolvedOptions().timeZone` - a cheap tell.
+    /// Accept-Language and the UA Client Hints metadata
+    /// (`ProfileMetadata::user_agent_metadata`) ride on the UA override, so
+    /// Chrome itself sends the `Sec-CH-UA*` headers it would for the claimed
+    /// browser and OS - only where it would, and matching
+    /// `navigator.userAgentData`.
+    fn emulate_locale(tab: &Arc<headless_chrome::Tab>, profile: &SyntheticProfile, user_agent: &str) -> anyhow::Result<()> {
+        Self::apply_locale(tab, profile, user_agent)?;
+        let metadata = &profile.metadata;
+        
+        // Self-check: the page must see the profile's timezone
+        let resolved = tab.evaluate("Intl.DateTimeFormat().resolvedOptions().timeZone", false)
+            .context("Failed to read back timezone")?
+            .value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        if resolved != metadata.timezone {
+            anyhow::bail!("timezone override did not apply: page reports {:?}, profile says {}", resolved, metadata.timezone);
+        }
+        
+        debug!("Locale emulation: {} / {} / {}", metadata.timezone, metadata.language, metadata.accept_language());
+        Ok(())
+    }
+    
+    /// The overrides behind `emulate_locale`, without reading anything back from the page
+    fn apply_locale(tab: &Arc<headless_chrome::Tab>, profile: &SyntheticProfile, user_agent: &str) -> anyhow::Result<()> {
+        let metadata = &profile.metadata;
+        tab.call_method("Emulation.setTimezoneOverride", serde_json::json!({ "timezoneId": metadata.timezone }))
+            .with_context(|| format!("Failed to override timezone to {}", metadata.timezone))?;
+        tab.call_method("Emulation.setLocaleOverride", serde_json::json!({ "locale": metadata.language }))
+            .with_context(|| format!("Failed to override locale to {}", metadata.language))?;
+        
+        // Keep the session's (sanitized) UA
+        let mut ua_override = serde_json::json!({
+            "userAgent": user_agent,
+            "acceptLanguage": metadata.accept_language(),
+        });
+        if let Some(ua_metadata) = metadata.user_agent_metadata() {
+            ua_override["userAgentMetadata"] = ua_metadata;
+        }
+        tab.call_method("Network.setUserAgentOverride", ua_override)
+            .context("Failed to override Accept-Language and Client Hints")?;
+        Ok(())
+    }
+
+    /// Load `echo_url` and confirm the profile's headers arrived exactly as set
+    /// 
+    /// The endpoint must echo request headers as JSON, either as an object
+    /// (`{"headers": {"Sec-Ch-Ua": "..."}}`, httpbin style) or as a list of
+    /// `"name: value"` lines (tls.peet.ws style). Leaves the tab on the echo page.
+    fn check_profile_headers(tab: &Arc<headless_chrome::Tab>, profile: &SyntheticProfile, echo_url: &str) -> anyhow::Result<()> {
+        tab.navigate_to(echo_url)
+            .with_context(|| format!("Failed to load header echo {}", echo_url))?
+            .wait_until_navigated()
+            .context("Header echo did not load")?;
+        let body = tab.evaluate("document.body.innerText", false)
+            .context("Failed to read header echo")?
+            .value
+            .and_then(|v| v.as_str().map(str::to_string))
+            .unwrap_or_default();
+        let echoed: serde_json::Value = serde_json::from_str(&body)
+            .with_context(|| format!("Header echo {} did not return JSON", echo_url))?;
+        
+        let mismatches: Vec<String> = profile.metadata.client_headers()
+            .into_iter()
+            .filter_map(|(name, expected)| match find_echoed_header(&echoed, name) {
+                Some(actual) if actual == expected => None,
+                Some(actual) => Some(format!("{}: sent {:?}, expected {:?}", name, actual, expected)),
+                None => Some(format!("{}: missing", name)),
+            })
+            .collect();
+        if !mismatches.is_empty() {
+            anyhow::bail!("profile headers not as set: {}", mismatches.join("; "));
+        }
+        Ok(())
+    }
+
+    /// Confirm the grafted profile's Accept-Language and Client Hints reach `echo_url` as set
+    /// 
+    /// Navigates the active tab. Sessions without a profile have nothing to check.
+    pub fn verify_profile_headers(&self, echo_url: &str) -> anyhow::Result<()> {
+        match &self.profile {
+            Some(profile) => Self::check_profile_headers(&self.get_tab()?, profile, echo_url),
+            None => Ok(()),
+        }
+    }
+
+    /// Make `navigator.plugins`/`mimeTypes` list `plugins` (see `plugins_script`)
+    fn spoof_plugins(tab: &Arc<headless_chrome::Tab>, plugins: &[&str]) -> anyhow::Result<()> {
+        tab.call_method("Page.addScriptToEvaluateOnNewDocument", serde_json::json!({ "source": plugins_script(plugins) }))
+            .context("Failed to inject plugin spoofing")?;
+        debug!("navigator.plugins set to {} PDF viewer plugins", plugins.len());
+        Ok(())
+    }
+
+    /// Hide fonts the profile's OS wouldn't have from font enumeration
+    /// 
+    /// Enumeration works by measuring text in a candidate family and comparing
+    /// against the fallback. Canvas `measureText` and `document.fonts.check`
+    /// drop families outside the profile's set (web fonts the page loaded
+    /// itself stay visible), so a Linux container's DejaVu/Liberation fonts
+    /// measure as missing. Profile fonts absent from the host still measure as
+    /// the fallback - ship them in the image for a full match.
+    fn normalize_fonts(tab: &Arc<headless_chrome::Tab>, profile: &SyntheticProfile) -> anyhow::Result<()> {
+        let script = font_normalization_script(profile.metadata.fonts())?;
+        
+        tab.call_method("Page.addScriptToEvaluateOnNewDocument", serde_json::json!({ "source": script }))
+            .context("Failed to inject font normalization")?;
+        debug!("Font set normalized to {} ({} families)", profile.metadata.os, profile.metadata.fonts().len());
+        Ok(())
+    }
+
+    /// Push a profile's cookie jar into Chrome
+    fn restore_cookies(tab: &Arc<headless_chrome::Tab>, cookies: &[CookieRecord]) -> anyhow::Result<()> {
+        if cookies.is_empty() {
+            return Ok(());
+        }
+        
+        let params: Vec<serde_json::Value> = cookies.iter().map(CookieRecord::to_cdp).collect();
+        tab.call_method("Network.setCookies", serde_json::json!({ "cookies": params }))
+            .context("Failed to restore profile cookies")?;
+        
+        debug!("Restored {} cookies into browser", cookies.len());
+        Ok(())
+    }
+
+    /// Ensure the profile's claimed browser matches the proxy's TLS fingerprint
+    /// 
+    /// A "Firefox 120" identity arriving with a Chrome JA4 is a stronger bot
+    /// signal than no identity at all. Cookies are still restored; the mismatch
+    /// is surfaced through `StealthStatus`.
+    fn check_profile_target(profile: &SyntheticProfile, target: ImpersonationTarget) -> anyhow::Result<()> {
+        match ImpersonationTarget::from_browser_name(&profile.metadata.browser) {
+            Some(profile_target) if profile_target == target => Ok(()),
+            Some(profile_target) => anyhow::bail!(
+                "profile {} claims {} ({}) but the proxy impersonates {}",
+                profile.id, profile.metadata.browser, profile_target.name(), target.name()
+            ),
+            None => anyhow::bail!(
+                "profile {} claims {}, which has no network fingerprint (proxy impersonates {})",
+                profile.id, profile.metadata.browser, target.name()
+            ),
+        }
+    }
+
+    /// Read every cookie the browser currently holds
+    pub fn export_cookies(&self) -> anyhow::Result<Vec<CookieRecord>> {
+        let tab = self.get_tab()?;
+        let result = tab.call_method("Network.getAllCookies", serde_json::json!({}))
+            .context("Failed to read cookies")?;
+        
+        Ok(result["cookies"]
+            .as_array()
+            .map(|cookies| cookies.iter().filter_map(CookieRecord::from_cdp).collect())
+            .unwrap_or_default())
+    }
+
+    /// Write the browser's cookie jar back to the grafted profile
+    fn persist_profile_cookies(&mut self) {
+        let (Some(profile), Some(identity)) = (self.profile.take(), self.identity.take()) else {
+            return;
+        };
+        
+        let cookies = match self.export_cookies() {
+            Ok(cookies) => cookies,
+            Err(e) => {
+                warn!("Failed to export cookies for profile {} (non-fatal): {}", profile.id, e);
+                return;
+            }
+        };
+        
+        let (updated, redis) = {
+            let mut identity = identity.lock().unwrap();
+            (identity.update_cookies(&profile.id, cookies), identity.redis_store())
+        };
+        let updated = match updated {
+            Ok(updated) => updated,
+            Err(e) => {
+                warn!("Failed to persist cookies for profile {} (non-fatal): {}", profile.id, e);
+                return;
+            }
+        };
+        
+        // Share the jar with the swarm; Redis is async-only, so this needs a runtime
+        let Some(redis) = redis else {
+            return;
+        };
+        let lease = self.lease.take();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = redis.save_profile(&updated).await {
+                        warn!("Failed to save profile cookies to Redis (non-fatal): {}", e);
+                    }
+                    // Only hand the identity to another worker once its jar is shared
+                    if let Some(lease) = lease {
+                        lease.release().await;
+                    }
+                });
+            }
+            Err(_) => debug!("No runtime at session close, profile {} saved to filesystem only", updated.id),
+        }
+    }
+
+    /// Detach the grafted profile so `shutdown` doesn't save cookies back into it
+    /// 
+    /// For burned identities: the jar was blanked (or is tainted) and must
+    /// not overwrite the stored one. Returns the swarm lease, to be released
+    /// once the profile is marked burned.
+    pub fn discard_identity(&mut self) -> Option<ProfileLease> {
+        self.profile = None;
+        self.identity = None;
+        self.lease.take()
+    }
+
+    /// Abort `task` when the session shuts down
+    pub fn track_task(&self, task: tokio::task::AbortHandle) {
+        let mut tasks = self.background_tasks.lock().unwrap();
+        tasks.retain(|t| !t.is_finished());
+        tasks.push(task);
+    }
+
+    /// Close the session now: stop background tasks, save the profile's cookies, end Chrome
+    /// 
+    /// Other tasks can still hold clones of the session's `Arc`, so dropping
+    /// it from the session map doesn't reliably end the process; this does.
+    /// Idempotent - `Drop` calls it too.
+    pub fn shutdown(&mut self) {
+        if self.closed {
+            return;
+        }
+        self.closed = true;
+        info!("Closing browser session: {}", self.session_id);
+        
+        for task in self.background_tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+        
+        // Needs the live browser, so before Chrome goes away
+        self.persist_profile_cookies();
+        
+        // Browser.close ends Chrome cleanly; kill the process if that fails
+        let closed = self.get_tab()
+            .and_then(|tab| tab.call_method("Browser.close", serde_json::json!({})).context("Browser.close failed"));
+        if let Err(e) = closed {
+            warn!("Graceful close of session {} failed, killing Chrome: {}", self.session_id, e);
+            // headless_chrome only kills its Process once every Browser clone is gone,
+            // and the tab watcher holds one
+            #[cfg(unix)]
+            if let Some(pid) = self.browser.get_process_id() {
+                // SAFETY: kill(2) on the pid of the Chrome we launched; no memory involved
+                if unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) } != 0 {
+                    error!("Failed to kill Chrome (pid {}): {}", pid, std::io::Error::last_os_error());
+                }
+            }
+        }
+    }
+
+    /// Confirm the Phantom Proxy is actually listening where Chrome was pointed
+    fn check_proxy_routing(config: &ChimeraConfig) -> MeasureState {
+        let addr = config.proxy_addr();
+        match std::net::TcpStream::connect_timeout(&addr, std::time::Duration::from_millis(250)) {
+            Ok(_) => MeasureState::Applied,
+            Err(e) => MeasureState::Failed(format!("proxy not reachable on {}: {}", addr, e)),
+        }
+    }
+
+    /// Launch a session without blocking the async runtime
+    /// 
+    /// Chrome launch + script injection takes seconds of synchronous CDP work;
+    /// running it on a tokio worker stalls every other RPC on that thread.
+    pub async fn new_async(
+        session_id: String,
+        mode: LaunchMode,
+        config: &ChimeraConfig,
+        profile: Option<SyntheticProfile>,
+        viewport: Option<ViewportConfig>,
+        seed: SessionSeed,
+    ) -> anyhow::Result<Self> {
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || Self::new_with_seed(session_id, mode, &config, profile, viewport, seed))
+            .await
+            .context("Browser launch task panicked")?
     }
 
     /// Inject Biological BIOS - Masks hardware fingerprinting
@@ -63,83 +1002,146 @@ impl BrowserSession {
     /// 
     /// The Fix: Force Chrome to "lie" about hardware stats before any website code loads.
     /// This makes a server look like a consumer PC.
-    fn inject_bio_bios(tab: &Arc<headless_chrome::Tab>) -> anyhow::Result<()> {
+    /// Cores, RAM and GPU are the grafted profile's `fingerprint` - see
+    /// `hardware_identity` and `gpu_identity` - so sessions don't all report
+    /// the same machine. `navigator.platform` follows the profile's OS, or
+    /// `user_agent` without a profile.
+    fn inject_bio_bios(
+        tab: &Arc<headless_chrome::Tab>,
+        viewport: &ViewportConfig,
+        fingerprint: Option<&BrowserFingerprint>,
+        user_agent: &str,
+    ) -> anyhow::Result<()> {
         use tracing::debug;
         debug!("Injecting Biological BIOS (hardware fingerprint masking)");
+        let gpu = gpu_identity(fingerprint);
+        let (cores, memory) = hardware_identity(fingerprint);
+        let platform = navigator_platform(fingerprint.map_or(user_agent, |f| f.platform.as_str()));
+        
+        // Screen geometry must agree with the emulated viewport (and the profile behind it)
+        let screen_script = format!(
+            r#"
+            Object.defineProperties(screen, {{
+                width: {{ get: () => {width}, configurable: true }},
+                height: {{ get: () => {height}, configurable: true }},
+                availWidth: {{ get: () => {width}, configurable: true }},
+                availHeight: {{ get: () => {avail_height}, configurable: true }},
+                colorDepth: {{ get: () => 24, configurable: true }},
+                pixelDepth: {{ get: () => 24, configurable: true }}
+            }});
+            Object.defineProperty(window, 'devicePixelRatio', {{
+                get: () => {dpr},
+                configurable: true
+            }});
+            "#,
+            width = viewport.width,
+            height = viewport.height,
+            // Desktop taskbar/dock eats into the available height
+            avail_height = if viewport.mobile { viewport.height } else { viewport.height.saturating_sub(40) },
+            dpr = viewport.device_scale_factor,
+        );
+        
+        // Safari has no navigator.deviceMemory at all; `in` checks must fail too
+        let device_memory = match memory {
+            Some(gb) => format!(
+                r#"
+                deviceMemory: {{ 
+                    get: () => {},  // Chrome rounds down to a power of two and caps at 8
+                    configurable: true
+                }},"#,
+                chrome_device_memory(gb)
+            ),
+            None => String::new(),
+        };
+        let hide_device_memory = if memory.is_none() {
+            "delete Navigator.prototype.deviceMemory;"
+        } else {
+            ""
+        };
         
         // We override the getters for hardware properties to match a standard "Consumer PC"
-        let script = r#"
+        let script = format!(
+            r#"
             // Override hardware properties to match consumer PC (not server)
-            Object.defineProperties(navigator, {
-                hardwareConcurrency: { 
-                    get: () => 8,  // Standard 8-core laptop (not 96-core server)
+            {hide_device_memory}
+            Object.defineProperties(navigator, {{
+                hardwareConcurrency: {{ 
+                    get: () => {cores},  // Consumer core count (not 96-core server)
                     configurable: true
-                },
-                deviceMemory: { 
-                    get: () => 8,  // Standard 8GB RAM (not 64GB server)
+                }},{device_memory}
+                platform: {{ 
+                    get: () => "{platform}",  // Must match User-Agent
                     configurable: true
-                },
-                platform: { 
-                    get: () => "Win32",  // Must match User-Agent
-                    configurable: true
-                },
-                webdriver: { 
+                }},
+                webdriver: {{ 
                     get: () => undefined,  // The classic "I am a robot" flag
                     configurable: true
-                }
-            });
+                }}
+            }});
             
-            // Deep GPU Parameter Masking - Phase 4: Network-Layer Authenticity
-            // Standard masking only touches Vendor/Renderer names. High-level probes
-            // now inspect WebGL technical limits to see if they match server-grade hardware.
-            // 
-            // We must hardcode WebGL parameters to match consumer hardware:
-            // - MAX_TEXTURE_SIZE: 16384 (consumer GPU limit, not server-grade 32768+)
-            // - MAX_RENDERBUFFER_SIZE: 16384 (consumer GPU limit)
-            // - UNMASKED_VENDOR_WEBGL: "Intel Inc."
-            // - UNMASKED_RENDERER_WEBGL: "Intel(R) Iris(R) Xe Graphics"
-            //
-            // This prevents high-level probes from detecting server-grade hardware
-            // by inspecting WebGL technical limits.
-            const originalGetParameter = WebGLRenderingContext.prototype.getParameter;
-            WebGLRenderingContext.prototype.getParameter = function(parameter) {
-                // UNMASKED_VENDOR_WEBGL (0x9245 = 37445)
-                if (parameter === 37445) {
-                    return "Intel Inc.";
-                }
-                // UNMASKED_RENDERER_WEBGL (0x9246 = 37446)
-                if (parameter === 37446) {
-                    return "Intel(R) Iris(R) Xe Graphics";
-                }
-                // MAX_TEXTURE_SIZE (0x0D33 = 3379)
-                if (parameter === 3379) {
-                    return 16384; // Consumer GPU limit (not server-grade 32768+)
-                }
-                // MAX_RENDERBUFFER_SIZE (0x84E8 = 34024)
-                if (parameter === 34024) {
-                    return 16384; // Consumer GPU limit
-                }
-                return originalGetParameter.call(this, parameter);
-            };
+        "#
+        );
+        
+        // Deep GPU Parameter Masking - Phase 4: Network-Layer Authenticity
+        // Standard masking only touches Vendor/Renderer names. High-level probes
+        // now inspect WebGL technical limits to see if they match server-grade hardware.
+        // 
+        // We must hardcode WebGL parameters to match consumer hardware:
+        // - MAX_TEXTURE_SIZE: 16384 (consumer GPU limit, not server-grade 32768+)
+        // - MAX_RENDERBUFFER_SIZE: 16384 (consumer GPU limit)
+        // - UNMASKED_VENDOR_WEBGL / UNMASKED_RENDERER_WEBGL: the profile's GPU
+        //
+        // This prevents high-level probes from detecting server-grade hardware
+        // by inspecting WebGL technical limits.
+        let gpu_script = format!(
+            r#"
+            (function() {{
+                const GPU_VENDOR = {vendor};
+                const GPU_RENDERER = {renderer};
             
-            // Also override WebGL2 (if available) with same parameters
-            if (typeof WebGL2RenderingContext !== 'undefined') {
-                const originalGetParameter2 = WebGL2RenderingContext.prototype.getParameter;
-                WebGL2RenderingContext.prototype.getParameter = function(parameter) {
-                    if (parameter === 37445) return "Intel Inc.";
-                    if (parameter === 37446) return "Intel(R) Iris(R) Xe Graphics";
-                    if (parameter === 3379) return 16384; // MAX_TEXTURE_SIZE
-                    if (parameter === 34024) return 16384; // MAX_RENDERBUFFER_SIZE
-                    return originalGetParameter2.call(this, parameter);
-                };
-            }
-        "#;
+                const originalGetParameter = WebGLRenderingContext.prototype.getParameter;
+                WebGLRenderingContext.prototype.getParameter = function(parameter) {{
+                    // UNMASKED_VENDOR_WEBGL (0x9245 = 37445)
+                    if (parameter === 37445) {{
+                        return GPU_VENDOR;
+                    }}
+                    // UNMASKED_RENDERER_WEBGL (0x9246 = 37446)
+                    if (parameter === 37446) {{
+                        return GPU_RENDERER;
+                    }}
+                    // MAX_TEXTURE_SIZE (0x0D33 = 3379)
+                    if (parameter === 3379) {{
+                        return 16384; // Consumer GPU limit (not server-grade 32768+)
+                    }}
+                    // MAX_RENDERBUFFER_SIZE (0x84E8 = 34024)
+                    if (parameter === 34024) {{
+                        return 16384; // Consumer GPU limit
+                    }}
+                    return originalGetParameter.call(this, parameter);
+                }};
+            
+                // Also override WebGL2 (if available) with same parameters
+                if (typeof WebGL2RenderingContext !== 'undefined') {{
+                    const originalGetParameter2 = WebGL2RenderingContext.prototype.getParameter;
+                    WebGL2RenderingContext.prototype.getParameter = function(parameter) {{
+                        if (parameter === 37445) return GPU_VENDOR;
+                        if (parameter === 37446) return GPU_RENDERER;
+                        if (parameter === 3379) return 16384; // MAX_TEXTURE_SIZE
+                        if (parameter === 34024) return 16384; // MAX_RENDERBUFFER_SIZE
+                        return originalGetParameter2.call(this, parameter);
+                    }};
+                }}
+            }})();
+            "#,
+            vendor = serde_json::Value::from(gpu.0),
+            renderer = serde_json::Value::from(gpu.1),
+        );
         
         // "evaluate_on_new_document" ensures this runs BEFORE the website can check
         // This is critical - must run before any page JavaScript executes
         tab.call_method(
             "Page.addScriptToEvaluateOnNewDocument",
-            serde_json::json!({ "source": script }),
+            serde_json::json!({ "source": format!("{}{}{}", screen_script, script, gpu_script) }),
         )
         .context("Failed to inject Biological BIOS script")?;
         
@@ -147,10 +1149,154 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// The tab actions operate on (see `switch_tab`)
     pub fn get_tab(&self) -> anyhow::Result<Arc<headless_chrome::Tab>> {
-        self.browser
+        self.active_tab()
+    }
+
+    /// Every page open in this session, including popups and `target="_blank"` tabs
+    /// 
+    /// headless_chrome tracks pages through CDP target discovery
+    /// (`Target.targetCreated` / `Target.targetDestroyed`), so this reflects
+    /// tabs the site opened on its own.
+    pub fn list_tabs(&self) -> anyhow::Result<Vec<TabInfo>> {
+        let active = self.active_tab.lock().unwrap().clone();
+        let tabs = self.browser.get_tabs().lock().unwrap().clone();
+        
+        Ok(tabs
+            .iter()
+            .map(|tab| TabInfo {
+                target_id: tab.get_target_id().to_string(),
+                url: tab.get_url(),
+                title: tab.get_title().unwrap_or_default(),
+                active: *tab.get_target_id() == active,
+            })
+            .collect())
+    }
+
+    /// The tab actions currently operate on
+    /// 
+    /// If the active tab was closed (e.g. an OAuth popup that finished), falls
+    /// back to the session's first tab.
+    pub fn active_tab(&self) -> anyhow::Result<Arc<headless_chrome::Tab>> {
+        let mut active = self.active_tab.lock().unwrap();
+        if let Some(tab) = self.find_tab(&active) {
+            return Ok(tab);
+        }
+        
+        let tab = self.browser
             .wait_for_initial_tab()
-            .context("Failed to get tab")
+            .context("Failed to get tab")?;
+        debug!("Active tab {} closed, falling back to {}", active, tab.get_target_id());
+        *active = tab.get_target_id().to_string();
+        Ok(tab)
+    }
+
+    /// Make `target_id` the tab that navigation, screenshots and input go to
+    /// 
+    /// Tabs opened by the page are normally prepared by `watch_new_tabs`
+    /// before their first script runs; one it hasn't reached yet is prepared
+    /// here.
+    pub fn switch_tab(&self, target_id: &str) -> anyhow::Result<()> {
+        let tab = self.find_tab(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Tab not found: {}", target_id))?;
+        
+        if self.prepared_tabs.lock().unwrap().insert(target_id.to_string()) {
+            self.tab_stealth().prepare(&tab);
+        }
+        
+        if let Some(downloads) = self.downloads.lock().unwrap().as_ref() {
+            if let Err(e) = downloads.attach(&tab) {
+                warn!("Downloads from tab {} won't be captured: {}", target_id, e);
+            }
+        }
+        
+        if !self.credentials.is_empty() {
+            if let Err(e) = self.credentials.attach(&tab) {
+                warn!("Auth challenges in tab {} won't be answered: {}", target_id, e);
+            }
+        }
+        
+        tab.activate().context("Failed to activate tab")?;
+        *self.active_tab.lock().unwrap() = target_id.to_string();
+        info!("🗂️  Switched to tab {} ({})", target_id, tab.get_url());
+        Ok(())
+    }
+
+    /// Snapshot of what new tabs are held to
+    fn tab_stealth(&self) -> TabStealth {
+        TabStealth {
+            viewport: self.viewport,
+            user_agent: self.user_agent.clone(),
+            profile: self.profile.clone(),
+            plugins: self.plugins,
+            seed: self.seed,
+            webrtc_policy: self.webrtc_policy,
+        }
+    }
+
+    /// Mask tabs the page opens before any of their scripts run
+    /// 
+    /// Browser-wide auto-attach with `waitForDebuggerOnStart` makes Chrome
+    /// pause every new target on start; a background task gives each one the
+    /// session's viewport, UA and stealth scripts and only then resumes it,
+    /// so a popup's first document is covered too. Needs a tokio runtime
+    /// (`new_async`); without one, new tabs are prepared on `switch_tab`.
+    fn watch_new_tabs(&self) -> anyhow::Result<()> {
+        use headless_chrome::protocol::cdp::Target;
+        
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            debug!("No runtime for session {}, new tabs are prepared on switch", self.session_id);
+            return Ok(());
+        };
+        self.browser
+            .call_method(Target::SetAutoAttach {
+                auto_attach: true,
+                wait_for_debugger_on_start: true,
+                flatten: Some(true),
+                filter: None,
+            })
+            .context("Failed to auto-attach to new tabs")?;
+        
+        let browser = self.browser.clone();
+        let prepared = Arc::clone(&self.prepared_tabs);
+        let stealth = self.tab_stealth();
+        let task = runtime.spawn(async move {
+            loop {
+                let fresh: Vec<_> = browser
+                    .get_tabs()
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|tab| prepared.lock().unwrap().insert(tab.get_target_id().to_string()))
+                    .cloned()
+                    .collect();
+                if !fresh.is_empty() {
+                    let stealth = stealth.clone();
+                    let prepared = tokio::task::spawn_blocking(move || {
+                        for tab in &fresh {
+                            stealth.prepare(tab);
+                        }
+                    });
+                    if let Err(e) = prepared.await {
+                        error!("New tab preparation panicked: {}", e);
+                    }
+                }
+                tokio::time::sleep(NEW_TAB_POLL).await;
+            }
+        });
+        self.track_task(task.abort_handle());
+        Ok(())
+    }
+
+    fn find_tab(&self, target_id: &str) -> Option<Arc<headless_chrome::Tab>> {
+        self.browser
+            .get_tabs()
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|tab| *tab.get_target_id() == target_id)
+            .cloned()
     }
 
     pub fn navigate(&self, url: &str) -> anyhow::Result<()> {
@@ -165,20 +1311,101 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// Navigate to `target` with `referrer` as the Referer (no visit to the referrer)
+    /// 
+    /// Landing on a deep URL with an empty referrer looks like a bot; a search
+    /// results page as origin looks like a person who searched for it.
+    pub fn navigate_via_referrer(&self, target: &str, referrer: &str) -> anyhow::Result<()> {
+        info!("Navigating to: {} (referrer: {})", target, referrer);
+        let tab = self.get_tab()?;
+        tab.call_method(
+            "Page.navigate",
+            serde_json::json!({ "url": target, "referrer": referrer }),
+        )
+        .context("Failed to navigate with referrer")?;
+        
+        tab.wait_until_navigated()
+            .context("Failed to wait for navigation")?;
+        
+        Ok(())
+    }
+
+    /// Actually visit `referrer`, linger, then click through to `target`
+    /// 
+    /// Stronger than `navigate_via_referrer`: the referrer appears in history and
+    /// `document.referrer` is set by a real link activation. Prefers an existing
+    /// link to the target on the referrer page; otherwise activates a synthetic one.
+    pub async fn click_through(&self, target: &str, referrer: &str) -> anyhow::Result<()> {
+        self.navigate(referrer)?;
+        
+        // Skim the results like a person would before choosing one
+        let dwell_ms = self.pacer.rng().gen_range(1500..4000);
+        tokio::time::sleep(tokio::time::Duration::from_millis(dwell_ms)).await;
+        
+        let tab = self.get_tab()?;
+        let target_json = serde_json::to_string(target)?;
+        let script = format!(
+            r#"(() => {{
+                const target = {target};
+                let link = Array.from(document.querySelectorAll('a[href]')).find(a => a.href === target);
+                if (!link) {{
+                    link = document.createElement('a');
+                    link.href = target;
+                    document.body.appendChild(link);
+                }}
+                link.removeAttribute('target');
+                link.click();
+            }})()"#,
+            target = target_json
+        );
+        tab.evaluate(&script, false)
+            .context("Failed to click through to target")?;
+        
+        tab.wait_until_navigated()
+            .context("Failed to wait for navigation")?;
+        
+        Ok(())
+    }
+
     pub fn capture_screenshot(&self) -> anyhow::Result<Vec<u8>> {
-        debug!("Capturing screenshot for session: {}", self.session_id);
+        self.capture_screenshot_as(ScreenshotFormat::Png)
+    }
+
+    /// Capture a screenshot in the requested encoding (CDP `Page.captureScreenshot`)
+    pub fn capture_screenshot_as(&self, format: ScreenshotFormat) -> anyhow::Result<Vec<u8>> {
+        use headless_chrome::protocol::cdp::Page::CaptureScreenshotFormatOption;
+        
+        debug!("Capturing {:?} screenshot for session: {}", format, self.session_id);
         let tab = self.get_tab()?;
+        
+        let (cdp_format, quality) = match format {
+            ScreenshotFormat::Png => (CaptureScreenshotFormatOption::Png, None),
+            ScreenshotFormat::Jpeg(q) => (CaptureScreenshotFormatOption::Jpeg, Some(q.min(100))),
+            ScreenshotFormat::Webp(q) => (CaptureScreenshotFormatOption::Webp, Some(q.min(100))),
+        };
+        
         let screenshot = tab
-            .capture_screenshot(
-                headless_chrome::protocol::cdp::Page::CaptureScreenshotFormat::Png,
-                None,
-                true,
-            )
+            .capture_screenshot(cdp_format, quality, None, true)
             .context("Failed to capture screenshot")?;
         
         Ok(screenshot)
     }
 
+    /// Perceptual hash of the current viewport (64-bit dHash, hex-encoded)
+    /// 
+    /// Unlike a cryptographic hash, near-identical frames (caret blink, a
+    /// spinner tick) produce hashes a few bits apart; compare them with
+    /// `visual_hash_distance` rather than `==`.
+    pub fn get_visual_hash(&self) -> anyhow::Result<String> {
+        Ok(format!("{:016x}", self.get_perceptual_hash()?))
+    }
+
+    /// 64-bit dHash of the current screen (compare with `hamming_distance`)
+    pub fn get_perceptual_hash(&self) -> anyhow::Result<u64> {
+        let screenshot = self.capture_screenshot()?;
+        perceptual_hash_u64(&screenshot)
+    }
+
     pub fn click(&self, x: i32, y: i32) -> anyhow::Result<()> {
         debug!("Clicking at ({}, {})", x, y);
         let tab = self.get_tab()?;
@@ -187,6 +1414,7 @@ impl BrowserSession {
         // For now, keep synchronous version but we'll add async version
         tab.move_mouse(x as f64, y as f64)
             .context("Failed to move mouse")?;
+        self.set_mouse_position(x as f64, y as f64);
         
         // Small delay to simulate human behavior
         std::thread::sleep(std::time::Duration::from_millis(50));
@@ -205,9 +1433,19 @@ impl BrowserSession {
         debug!("Human-like click at ({}, {})", x, y);
         let tab = self.get_tab()?;
         
-        let (current_x, current_y) = current_pos.unwrap_or((960.0, 540.0));
+        let (current_x, current_y) = current_pos.unwrap_or_else(|| self.mouse_position());
         
-        crate::mouse::human_click(&tab, x as f64, y as f64, Some(current_x), Some(current_y)).await?;
+        crate::mouse::human_click_with_rng(
+            &tab,
+            x as f64,
+            y as f64,
+            Some(current_x),
+            Some(current_y),
+            &self.motor_profile,
+            &mut self.mouse_rng(),
+        )
+        .await?;
+        self.set_mouse_position(x as f64, y as f64);
         
         // Wait for any animations/updates
         tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
@@ -215,6 +1453,189 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// Drift the cursor a few pixels from where it rests (anti "dead mouse")
+    /// 
+    /// Now and then the drift leans toward the nearest AX element, like a hand
+    /// resting near the text being read.
+    pub async fn perform_micro_fidget(&self) -> anyhow::Result<()> {
+        let tab = self.get_tab()?;
+        let (x, y) = self.mouse_position();
+        let mut rng = self.mouse_rng();
+        
+        let attractor = if rng.gen_bool(FIDGET_ATTRACT_PROBABILITY) {
+            let cortex = crate::cortex::Cortex::new(tab.clone());
+            cortex.snapshot_accessibility_tree().ok().and_then(|mut tree| {
+                cortex.resolve_bounds(&mut tree, |n| FIDGET_ATTRACT_ROLES.contains(&n.role.as_str())).ok()?;
+                tree.nearest(x, y, FIDGET_ATTRACT_RADIUS)
+            })
+        } else {
+            None
+        };
+        
+        let bounds = (self.viewport.width as f64, self.viewport.height as f64);
+        let (new_x, new_y) = crate::mouse::perform_micro_fidget(&tab, x, y, bounds, attractor, &mut rng).await?;
+        self.set_mouse_position(new_x, new_y);
+        Ok(())
+    }
+
+    /// Wait for an AX node of `role` (and `name`) to appear and be rendered, returning its center
+    /// 
+    /// Polls the accessibility tree, starting at 100ms and backing off to 1s
+    /// between snapshots, so a fast page is caught quickly without hammering
+    /// CDP on a slow one. Names match exactly or fuzzily (see `AxTree::locate`);
+    /// the match's box comes from the DOM (`Cortex::node_bounds`), and a node
+    /// without one (still hidden) keeps the wait going.
+    pub async fn wait_for_ax_node(
+        &self,
+        role: &str,
+        name: Option<&str>,
+        timeout: std::time::Duration,
+    ) -> crate::error::Result<(f64, f64)> {
+        let started = std::time::Instant::now();
+        let mut interval = std::time::Duration::from_millis(100);
+        
+        loop {
+            let found = self.get_tab().ok().map(crate::cortex::Cortex::new).and_then(|cortex| {
+                let tree = cortex.snapshot_accessibility_tree().ok()?;
+                let bounds = cortex.node_bounds(tree.locate(role, name)?).ok()??;
+                Some((bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0))
+            });
+            if let Some(point) = found {
+                debug!("AX node role '{}' name {:?} appeared after {:?}", role, name, started.elapsed());
+                return Ok(point);
+            }
+            
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                return Err(ChimeraError::ActionFailed(format!(
+                    "Timed out after {:?} waiting for AX node role '{}' name {:?}",
+                    timeout, role, name
+                )));
+            }
+            tokio::time::sleep(interval.min(remaining)).await;
+            interval = interval.mul_f64(1.5).min(std::time::Duration::from_secs(1));
+        }
+    }
+
+    /// Let the session download files, saving them into `dir`
+    /// 
+    /// Headless Chrome denies downloads unless told otherwise. Files land
+    /// under their download guid until complete (`Browser.setDownloadBehavior`
+    /// with `allowAndName`) and are then renamed to the suggested filename;
+    /// Chrome builds without that fall back to `Page.setDownloadBehavior`.
+    /// Collect them with `wait_for_download`.
+    pub fn enable_downloads(&self, dir: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create download directory {}", dir.display()))?;
+        let dir = dir.canonicalize()
+            .with_context(|| format!("Failed to resolve download directory {}", dir.display()))?;
+        let download_path = dir.to_string_lossy().to_string();
+        
+        let tab = self.get_tab()?;
+        let by_guid = tab.call_method("Browser.setDownloadBehavior", serde_json::json!({
+            "behavior": "allowAndName",
+            "downloadPath": download_path,
+            "eventsEnabled": true,
+        }));
+        if let Err(e) = by_guid {
+            debug!("Browser.setDownloadBehavior unavailable ({}), using Page.setDownloadBehavior", e);
+            tab.call_method("Page.setDownloadBehavior", serde_json::json!({
+                "behavior": "allow",
+                "downloadPath": download_path,
+            }))
+            .context("Failed to allow downloads")?;
+        }
+        
+        let tracker = Arc::new(DownloadTracker::new(dir));
+        for tab in self.browser.get_tabs().lock().unwrap().iter() {
+            tracker.attach(tab)?;
+        }
+        info!("📥 Downloads for session {} go to {}", self.session_id, tracker.dir().display());
+        *self.downloads.lock().unwrap() = Some(tracker);
+        Ok(())
+    }
+
+    /// Wait for the next download to finish, returning the saved file
+    /// 
+    /// Downloads that finished since the last call count, so clicking
+    /// "Export" and then waiting doesn't race. Tabs the page opened meanwhile
+    /// (a popup serving the file) are picked up on each poll.
+    pub async fn wait_for_download(&self, timeout: std::time::Duration) -> crate::error::Result<std::path::PathBuf> {
+        let tracker = self.downloads.lock().unwrap().clone().ok_or_else(|| {
+            ChimeraError::ActionFailed("Downloads not enabled for this session (call enable_downloads)".to_string())
+        })?;
+        let started = std::time::Instant::now();
+        
+        loop {
+            for tab in self.browser.get_tabs().lock().unwrap().iter() {
+                if let Err(e) = tracker.attach(tab) {
+                    debug!("{}", e);
+                }
+            }
+            if let Some(finished) = tracker.take_finished() {
+                return finished.map_err(ChimeraError::ActionFailed);
+            }
+            
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                return Err(ChimeraError::ActionFailed(format!(
+                    "Timed out after {:?} waiting for a download",
+                    timeout
+                )));
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100).min(remaining)).await;
+        }
+    }
+
+    /// Answer HTTP auth challenges from `origin` with `username`/`password`
+    /// 
+    /// `origin` is `https://host[:port]` for a site, or `host:port` for a proxy
+    /// Chrome authenticates to. Instead of the login dialog Chrome would show,
+    /// challenges arrive as `Fetch.authRequired` and are answered with
+    /// `Fetch.continueWithAuth`; those from origins without credentials are
+    /// cancelled. The first call turns on auth handling for every open tab.
+    pub fn set_credentials(&self, origin: &str, username: &str, password: &str) -> anyhow::Result<()> {
+        self.credentials.set(origin, username, password)?;
+        for tab in self.browser.get_tabs().lock().unwrap().iter() {
+            self.credentials.attach(tab)?;
+        }
+        info!("🔑 Credentials for {} set on session {}", origin, self.session_id);
+        Ok(())
+    }
+
+    /// Click an element by accessibility role and name - no vision round trip
+    /// 
+    /// Finds the node in the AX tree (`AxTree::locate`: exact name, then
+    /// fuzzy), scrolls it into view if needed and reads its box from the DOM,
+    /// then moves there with the same human-like path as vision-driven clicks.
+    pub async fn click_by_role(&self, role: &str, name: Option<&str>) -> crate::error::Result<()> {
+        let failed = |what: &str, e: anyhow::Error| ChimeraError::ActionFailed(format!("{}: {}", what, e));
+        let cortex = crate::cortex::Cortex::new(self.get_tab().map_err(|e| failed("Failed to get tab", e))?);
+        let tree = cortex
+            .snapshot_accessibility_tree()
+            .map_err(|e| failed("Failed to snapshot the AX tree", e))?;
+        let not_clickable = |why: &str| {
+            ChimeraError::ActionFailed(format!("AX node role '{}' name {:?} {}", role, name, why))
+        };
+        
+        let node = tree.locate(role, name).ok_or_else(|| not_clickable("not found"))?;
+        let backend_node_id = node.backend_node_id.ok_or_else(|| not_clickable("has no DOM node"))?;
+        cortex
+            .scroll_node_into_view(backend_node_id)
+            .map_err(|e| failed("Failed to scroll the target into view", e))?;
+        let bounds = cortex
+            .backend_node_bounds(backend_node_id)
+            .map_err(|e| failed("Failed to read the target's box", e))?
+            .ok_or_else(|| not_clickable("has no box (hidden?)"))?;
+        let (x, y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        
+        debug!("AX target role '{}' name {:?} at ({:.0}, {:.0})", role, name, x, y);
+        self.click_human_like(x.round() as i32, y.round() as i32, None)
+            .await
+            .map_err(|e| ChimeraError::ActionFailed(format!("Click failed: {}", e)))
+    }
+
     pub fn type_text(&self, text: &str) -> anyhow::Result<()> {
         debug!("Typing text: {}", text);
         let tab = self.get_tab()?;
@@ -224,6 +1645,48 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// Press a key or key combo ("Enter", "Tab", "ArrowDown", or "a" with `[Modifier::Ctrl]`)
+    /// 
+    /// Dispatches keyDown + keyUp via `Input.dispatchKeyEvent` with the key's
+    /// Windows virtual key code and the modifier bitmask. Text is only inserted
+    /// when no Ctrl/Alt/Meta is held, so Ctrl+A selects rather than types "a".
+    pub fn press_key(&self, key: &str, modifiers: &[Modifier]) -> anyhow::Result<()> {
+        debug!("Pressing key: {} {:?}", key, modifiers);
+        let definition = KeyDefinition::resolve(key)
+            .ok_or_else(|| anyhow::anyhow!("Unknown key: {}", key))?;
+        let mask = Modifier::mask(modifiers);
+        let tab = self.get_tab()?;
+        
+        let chord = modifiers.iter().any(|m| *m != Modifier::Shift);
+        let text = definition.text.as_deref().filter(|_| !chord);
+        let mut key_down = serde_json::json!({
+            "type": if text.is_some() { "keyDown" } else { "rawKeyDown" },
+            "key": definition.key,
+            "code": definition.code,
+            "windowsVirtualKeyCode": definition.key_code,
+            "nativeVirtualKeyCode": definition.key_code,
+            "modifiers": mask,
+        });
+        if let Some(text) = text {
+            key_down["text"] = serde_json::json!(text);
+            key_down["unmodifiedText"] = serde_json::json!(text);
+        }
+        tab.call_method("Input.dispatchKeyEvent", key_down)
+            .with_context(|| format!("Failed to press {}", key))?;
+        
+        tab.call_method("Input.dispatchKeyEvent", serde_json::json!({
+            "type": "keyUp",
+            "key": definition.key,
+            "code": definition.code,
+            "windowsVirtualKeyCode": definition.key_code,
+            "nativeVirtualKeyCode": definition.key_code,
+            "modifiers": mask,
+        }))
+        .with_context(|| format!("Failed to release {}", key))?;
+        
+        Ok(())
+    }
+
     pub fn scroll(&self, x: i32, y: i32, delta_x: i32, delta_y: i32) -> anyhow::Result<()> {
         debug!("Scrolling at ({}, {}) by ({}, {})", x, y, delta_x, delta_y);
         let tab = self.get_tab()?;
@@ -233,6 +1696,22 @@ impl BrowserSession {
         Ok(())
     }
 
+    /// Blank the session's identity state (cookies + cache)
+    /// 
+    /// Used by the detection "panic button" so a burned identity's state can't
+    /// leak into anything that outlives this session.
+    pub fn blank_fingerprint(&self) -> anyhow::Result<()> {
+        warn!("Blanking fingerprint state for session: {}", self.session_id);
+        let tab = self.get_tab()?;
+        
+        tab.call_method("Network.clearBrowserCookies", serde_json::json!({}))
+            .context("Failed to clear cookies")?;
+        tab.call_method("Network.clearBrowserCache", serde_json::json!({}))
+            .context("Failed to clear cache")?;
+        
+        Ok(())
+    }
+
     pub fn get_url(&self) -> anyhow::Result<String> {
         let tab = self.get_tab()?;
         let url = tab.get_url();
@@ -245,14 +1724,456 @@ impl BrowserSession {
         Ok(title)
     }
 
+    /// Run `script` in the active tab and deserialize its result into `T`
+    /// 
+    /// Uses CDP `Runtime.evaluate` with `returnByValue` (promises are awaited),
+    /// so the script's value must be JSON-serializable; `undefined` arrives as
+    /// `null`. A thrown exception becomes `ChimeraError::ActionFailed` carrying
+    /// the JS error.
+    pub fn execute_js<T: serde::de::DeserializeOwned>(&self, script: &str) -> crate::error::Result<T> {
+        let tab = self.get_tab()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get tab: {}", e)))?;
+        let response = tab.call_method("Runtime.evaluate", serde_json::json!({
+            "expression": script,
+            "returnByValue": true,
+            "awaitPromise": true,
+        }))
+        .map_err(|e| ChimeraError::ActionFailed(format!("Runtime.evaluate failed: {}", e)))?;
+        
+        if let Some(exception) = response.get("exceptionDetails") {
+            let message = exception["exception"]["description"].as_str()
+                .or_else(|| exception["text"].as_str())
+                .unwrap_or("unknown exception");
+            return Err(ChimeraError::ActionFailed(format!("Script threw: {}", message)));
+        }
+        
+        let value = response["result"].get("value").cloned().unwrap_or(serde_json::Value::Null);
+        Ok(serde_json::from_value(value)?)
+    }
+
     pub fn session_id(&self) -> &str {
         &self.session_id
     }
+
+    /// Master seed this session's randomness derives from
+    pub fn seed(&self) -> SessionSeed {
+        self.seed
+    }
+
+    /// Per-call RNG from the session's mouse stream
+    /// 
+    /// Motion sleeps between steps, so callers own the RNG instead of holding
+    /// the lock across awaits; successive calls continue the same stream.
+    pub fn mouse_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.mouse_rng.lock().unwrap().gen())
+    }
+
+    /// Per-call RNG from the session's typing stream
+    pub fn typing_rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.typing_rng.lock().unwrap().gen())
+    }
+
+    /// A Cortex on the active tab that moves like this session: its cursor
+    /// position, motor profile and mouse stream
+    pub fn cortex(&self) -> anyhow::Result<crate::cortex::Cortex> {
+        Ok(crate::cortex::Cortex::new(self.get_tab()?)
+            .with_shared_mouse(self.current_mouse.clone())
+            .with_motor_profile(self.motor_profile.clone())
+            .with_seed(Some(self.mouse_rng().gen())))
+    }
+
+    /// Type `text` into the focused element with human timing from the session's typing stream
+    pub async fn human_type(&self, text: &str) -> anyhow::Result<()> {
+        let tab = self.get_tab()?;
+        crate::mouse::human_type_with_rng(
+            &tab,
+            text,
+            &crate::mouse::TypeOptions::default(),
+            &crate::mouse::TypingProfile::default(),
+            &mut self.typing_rng(),
+        )
+        .await
+    }
+
+    /// Which stealth measures applied to this session
+    pub fn stealth_status(&self) -> &StealthStatus {
+        &self.stealth_status
+    }
+
+    /// Pacing governor every action on this session must wait on
+    pub fn pacer(&self) -> Arc<PacingGovernor> {
+        Arc::clone(&self.pacer)
+    }
+    
+    /// Note that the page with visual hash `hash` is on screen
+    /// 
+    /// True if one within `max_distance` bits was among the last
+    /// `RECENT_PAGE_MEMORY` pages this session saw, i.e. it's familiar.
+    pub fn recall_page(&self, hash: &str, max_distance: u32) -> bool {
+        let mut recent = self.recent_pages.lock().unwrap();
+        let familiar = recent.iter().any(|seen| match visual_hash_distance(seen, hash) {
+            Some(distance) => distance < max_distance,
+            None => seen == hash,
+        });
+        if recent.len() == RECENT_PAGE_MEMORY {
+            recent.pop_front();
+        }
+        recent.push_back(hash.to_string());
+        familiar
+    }
+}
+
+/// Value of header `name` in a header-echo response, matched case-insensitively
+/// 
+/// Accepts `{"Name": "value"}` objects and `"name: value"` strings anywhere in the document.
+fn find_echoed_header(echoed: &serde_json::Value, name: &str) -> Option<String> {
+    match echoed {
+        serde_json::Value::Object(map) => map.iter().find_map(|(key, value)| match value {
+            serde_json::Value::String(s) if key.eq_ignore_ascii_case(name) => Some(s.clone()),
+            other => find_echoed_header(other, name),
+        }),
+        serde_json::Value::Array(items) => items.iter().find_map(|item| find_echoed_header(item, name)),
+        serde_json::Value::String(line) => line
+            .split_once(':')
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// Script presenting `plugins` as Chrome presents its built-in PDF viewer
+/// 
+/// Builds real-shaped objects: `navigator.plugins instanceof PluginArray`,
+/// each entry `instanceof Plugin`, indexed and named access, `item`/`namedItem`
+/// and iteration all behave natively. Every plugin carries the shared
+/// `application/pdf` and `text/pdf` MimeTypes whose `enabledPlugin` is the
+/// first plugin, and `navigator.pdfViewerEnabled` agrees with the list. The
+/// prototype accessors are wrapped (reporting themselves as native code) and
+/// answer from the fake data only for these objects.
+/// 
+/// An empty list (Firefox identities) yields empty arrays.
+pub fn plugins_script(plugins: &[&str]) -> String {
+    format!(
+        r#"
+        (function() {{
+            'use strict';
+            if (typeof PluginArray === 'undefined' || typeof MimeTypeArray === 'undefined') return;
+            
+            const PLUGIN_NAMES = {names};
+            const PDF = 'Portable Document Format';
+            const data = new WeakMap();
+            
+            const asNative = (fn, name) => {{
+                Object.defineProperty(fn, 'toString', {{ value: () => 'function ' + name + '() {{ [native code] }}' }});
+                return fn;
+            }};
+            
+            // Answer from `data` for our objects, defer to the native accessor otherwise
+            const hook = (Iface, key, fake) => {{
+                if (typeof Iface === 'undefined') return;
+                const proto = Iface.prototype;
+                const desc = Object.getOwnPropertyDescriptor(proto, key);
+                if (!desc) return;
+                if (desc.get) {{
+                    const original = desc.get;
+                    Object.defineProperty(proto, key, Object.assign({{}}, desc, {{
+                        get: asNative(function() {{
+                            return data.has(this) ? fake(data.get(this)) : original.call(this);
+                        }}, 'get ' + key),
+                    }}));
+                }} else if (typeof desc.value === 'function') {{
+                    const original = desc.value;
+                    Object.defineProperty(proto, key, Object.assign({{}}, desc, {{
+                        value: asNative(function(...args) {{
+                            return data.has(this) ? fake(data.get(this), ...args) : original.apply(this, args);
+                        }}, key),
+                    }}));
+                }}
+            }};
+            
+            // Array-likes expose entries by index and (non-enumerable) by name
+            const arrayLike = (Iface, record, keyOf) => {{
+                const obj = Object.create(Iface.prototype);
+                record.items.forEach((item, i) => Object.defineProperty(obj, i, {{ value: item, enumerable: true, configurable: true }}));
+                record.items.forEach((item) => {{
+                    const key = keyOf(item);
+                    if (!(key in obj)) Object.defineProperty(obj, key, {{ value: item, configurable: true }});
+                }});
+                record.keyOf = keyOf;
+                data.set(obj, record);
+                return obj;
+            }};
+            
+            const mimeTypes = [['application/pdf', 'pdf'], ['text/pdf', 'pdf']].map(([type, suffixes]) => {{
+                const mime = Object.create(MimeType.prototype);
+                data.set(mime, {{ type, suffixes, description: PDF, enabledPlugin: null }});
+                return mime;
+            }});
+            const typeOf = (mime) => data.get(mime).type;
+            
+            const plugins = PLUGIN_NAMES.map((name) => arrayLike(Plugin, {{
+                items: mimeTypes,
+                name,
+                filename: 'internal-pdf-viewer',
+                description: PDF,
+            }}, typeOf));
+            const mimeTypeList = plugins.length ? mimeTypes : [];
+            mimeTypes.forEach((mime) => {{ data.get(mime).enabledPlugin = plugins[0] || null; }});
+            
+            const pluginArray = arrayLike(PluginArray, {{ items: plugins }}, (plugin) => data.get(plugin).name);
+            const mimeTypeArray = arrayLike(MimeTypeArray, {{ items: mimeTypeList }}, typeOf);
+            
+            const length = (r) => r.items.length;
+            const item = (r, index) => r.items[index >>> 0] || null;
+            const namedItem = (r, name) => r.items.find((x) => r.keyOf(x) === String(name)) || null;
+            for (const Iface of [PluginArray, MimeTypeArray, Plugin]) {{
+                hook(Iface, 'length', length);
+                hook(Iface, 'item', item);
+                hook(Iface, 'namedItem', namedItem);
+            }}
+            hook(PluginArray, 'refresh', () => undefined);
+            for (const key of ['name', 'filename', 'description']) hook(Plugin, key, (r) => r[key]);
+            for (const key of ['type', 'suffixes', 'description', 'enabledPlugin']) hook(MimeType, key, (r) => r[key]);
+            
+            const getter = (key, value) => {{
+                const desc = Object.getOwnPropertyDescriptor(Navigator.prototype, key);
+                Object.defineProperty(Navigator.prototype, key, {{
+                    get: asNative(function() {{ return value; }}, 'get ' + key),
+                    enumerable: desc ? desc.enumerable : true,
+                    configurable: true,
+                }});
+            }};
+            getter('plugins', pluginArray);
+            getter('mimeTypes', mimeTypeArray);
+            getter('pdfViewerEnabled', plugins.length > 0);
+        }})();
+        "#,
+        names = serde_json::Value::from(plugins.to_vec()),
+    )
+}
+
+/// WebGL (vendor, renderer) a session reports: the profile's GPU, else a common laptop iGPU
+fn gpu_identity(fingerprint: Option<&BrowserFingerprint>) -> (&str, &str) {
+    match fingerprint {
+        Some(fingerprint) => (&fingerprint.gpu_vendor, &fingerprint.gpu_renderer),
+        None => ("Intel Inc.", "Intel(R) Iris(R) Xe Graphics"),
+    }
+}
+
+/// Script for `normalize_fonts`: font enumeration only sees `fonts`, generic
+/// families and the page's own web fonts
+fn font_normalization_script(fonts: &[&str]) -> anyhow::Result<String> {
+    let fonts = serde_json::to_string(fonts)?;
+    Ok(format!(
+        r#"
+        (function() {{
+            'use strict';
+            
+            const ALLOWED = new Set({fonts}.map(f => f.toLowerCase()));
+            const GENERIC = new Set(['serif', 'sans-serif', 'monospace', 'cursive', 'fantasy', 'system-ui',
+                'ui-serif', 'ui-sans-serif', 'ui-monospace', 'ui-rounded', 'emoji', 'math', 'fangsong']);
+            
+            const unquote = (family) => family.trim().replace(/^['"]|['"]$/g, '');
+            const isGeneric = (family) => GENERIC.has(family.toLowerCase());
+            const isWebFont = (family) => {{
+                for (const face of document.fonts) {{
+                    if (unquote(face.family).toLowerCase() === family.toLowerCase()) return true;
+                }}
+                return false;
+            }};
+            const visible = (family) => isGeneric(family) || ALLOWED.has(family.toLowerCase()) || isWebFont(family);
+            
+            // "italic bold 12px/1.5 Foo, 'Bar Baz', serif" -> ["italic bold 12px/1.5 ", ["Foo", "Bar Baz", "serif"]]
+            const splitFont = (font) => {{
+                const m = /^(.*?[\d.]+(?:px|pt|pc|em|rem|ex|ch|%|vw|vh|in|cm|mm|q)(?:\s*\/\s*\S+)?\s+)(.+)$/i.exec(font);
+                return m ? [m[1], m[2].split(',').map(unquote)] : null;
+            }};
+            const filterFont = (font) => {{
+                const parts = splitFont(font);
+                if (!parts) return font;
+                const families = parts[1].filter(visible);
+                if (families.length === parts[1].length) return font;
+                const list = families.map(f => isGeneric(f) ? f : '"' + f + '"').join(', ');
+                return parts[0] + (list || 'sans-serif');
+            }};
+            
+            for (const name of ['CanvasRenderingContext2D', 'OffscreenCanvasRenderingContext2D']) {{
+                const proto = window[name] && window[name].prototype;
+                if (!proto) continue;
+                const originalMeasureText = proto.measureText;
+                proto.measureText = function(text) {{
+                    const font = this.font;
+                    const filtered = filterFont(font);
+                    if (filtered === font) return originalMeasureText.call(this, text);
+                    this.font = filtered;
+                    try {{
+                        return originalMeasureText.call(this, text);
+                    }} finally {{
+                        this.font = font;
+                    }}
+                }};
+            }}
+            
+            if (typeof FontFaceSet !== 'undefined') {{
+                const originalCheck = FontFaceSet.prototype.check;
+                FontFaceSet.prototype.check = function(font, text) {{
+                    const parts = splitFont(font);
+                    if (parts && !parts[1].every(visible)) return false;
+                    return originalCheck.call(this, font, text);
+                }};
+            }}
+        }})();
+        "#,
+        fonts = fonts,
+    ))
+}
+
+/// `navigator.platform` of a machine running `os` (a profile OS or a User-Agent)
+fn navigator_platform(os: &str) -> &'static str {
+    let os = os.to_ascii_lowercase();
+    if os.contains("windows") {
+        "Win32"
+    } else if os.contains("iphone") || os.contains("ios") {
+        "iPhone"
+    } else if os.contains("ipad") {
+        "iPad"
+    } else if os.contains("mac") {
+        "MacIntel"
+    } else if os.contains("android") {
+        "Linux armv81"
+    } else if os.contains("linux") || os.contains("x11") {
+        "Linux x86_64"
+    } else {
+        "Win32"
+    }
+}
+
+/// (cores, GB of RAM) `navigator` reports: the profile's, or an 8-core/8 GB laptop
+fn hardware_identity(fingerprint: Option<&BrowserFingerprint>) -> (u32, Option<u32>) {
+    match fingerprint {
+        Some(fingerprint) => (fingerprint.hardware_concurrency, fingerprint.device_memory),
+        None => (8, Some(8)),
+    }
+}
+
+/// `navigator.deviceMemory` Chrome reports for `gb` of RAM
+/// 
+/// The spec buckets to a power of two and Chrome caps it at 8, so a 16 GB
+/// machine saying 16 would stand out.
+fn chrome_device_memory(gb: u32) -> u32 {
+    match gb {
+        0 => 1,
+        gb => (1u32 << (31 - gb.leading_zeros())).min(8),
+    }
+}
+
+/// 64-bit difference hash (dHash) of an encoded image, as 16 hex chars
+/// 
+/// Downscale to 9x8 grayscale and record whether each pixel is brighter than
+/// its right neighbour. Layout changes flip many bits; rendering noise flips few.
+pub fn perceptual_hash(image_bytes: &[u8]) -> anyhow::Result<String> {
+    Ok(format!("{:016x}", perceptual_hash_u64(image_bytes)?))
+}
+
+/// 64-bit difference hash (dHash) of an encoded image
+pub fn perceptual_hash_u64(image_bytes: &[u8]) -> anyhow::Result<u64> {
+    let image = image::load_from_memory(image_bytes)
+        .context("Failed to decode screenshot for hashing")?;
+    let small = image
+        .resize_exact(9, 8, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    
+    Ok(hash)
+}
+
+/// Number of differing bits between two perceptual hashes (0-64)
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Hamming distance between two `perceptual_hash` values (None if either isn't a dHash)
+pub fn visual_hash_distance(a: &str, b: &str) -> Option<u32> {
+    let a = u64::from_str_radix(a, 16).ok()?;
+    let b = u64::from_str_radix(b, 16).ok()?;
+    Some(hamming_distance(a, b))
 }
 
 impl Drop for BrowserSession {
     fn drop(&mut self) {
-        info!("Closing browser session: {}", self.session_id);
-        // Browser will be closed automatically when dropped
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identity_grafting::fonts_for_os;
+
+    #[test]
+    fn test_retina_screenshot_points_map_to_css_pixels() {
+        let retina = ViewportConfig { width: 1280, height: 800, device_scale_factor: 2.0, mobile: false };
+        assert_eq!(retina.to_css(1001, 640), (501, 320));
+        assert_eq!(ViewportConfig::default().to_css(1001, 640), (1001, 640));
+    }
+
+    #[test]
+    fn test_headed_flag_means_virtual_display() {
+        assert_eq!(LaunchMode::from_headless(true), LaunchMode::Headless);
+        assert_eq!(LaunchMode::from_headless(false), LaunchMode::HeadfulXvfb);
+        assert_eq!(LaunchMode::from_name("headful"), Some(LaunchMode::Headful));
+    }
+
+    #[test]
+    fn test_font_script_follows_profile_os() {
+        let windows = font_normalization_script(fonts_for_os("Windows 11")).unwrap();
+        let mac = font_normalization_script(fonts_for_os("macOS 14")).unwrap();
+        
+        assert!(windows.contains(r#""Segoe UI""#));
+        assert!(windows.contains(r#""Calibri""#));
+        assert!(!windows.contains("Helvetica Neue"));
+        assert!(!windows.contains("DejaVu Sans"));
+        
+        assert!(mac.contains(r#""Helvetica Neue""#));
+        assert!(!mac.contains("Segoe UI"));
+        assert!(!mac.contains("Liberation Sans"));
+    }
+
+    #[test]
+    fn test_navigator_platform_follows_os() {
+        assert_eq!(navigator_platform("Windows 11"), "Win32");
+        assert_eq!(navigator_platform("macOS Sonoma"), "MacIntel");
+        assert_eq!(navigator_platform("Linux"), "Linux x86_64");
+        assert_eq!(
+            navigator_platform("Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36"),
+            "MacIntel"
+        );
+        assert_eq!(
+            navigator_platform("Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15"),
+            "iPhone"
+        );
+        assert_eq!(navigator_platform("Mozilla/5.0 (Linux; Android 14; Pixel 8)"), "Linux armv81");
+    }
+
+    #[test]
+    fn test_find_echoed_header_reads_both_echo_styles() {
+        let httpbin = serde_json::json!({ "headers": { "Sec-Ch-Ua-Mobile": "?0", "Accept-Language": "en-US,en;q=0.9" } });
+        assert_eq!(find_echoed_header(&httpbin, "sec-ch-ua-mobile").as_deref(), Some("?0"));
+        assert_eq!(find_echoed_header(&httpbin, "Accept-Language").as_deref(), Some("en-US,en;q=0.9"));
+
+        let peet = serde_json::json!({ "http2": { "sent_frames": [{ "headers": [
+            ":method: GET",
+            "sec-ch-ua-platform: \"Windows\"",
+        ] }] } });
+        assert_eq!(find_echoed_header(&peet, "Sec-CH-UA-Platform").as_deref(), Some("\"Windows\""));
+        assert_eq!(find_echoed_header(&peet, "Sec-CH-UA"), None);
     }
 }
@@ -0,0 +1,159 @@
+/// Circuit Breaker - Stop Hammering a Dependency That's Down
+///
+/// When a backing service (Redis, the vision service) dies mid-mission, every
+/// call that touches it would otherwise block on a timeout and log the same
+/// warning. After `failure_threshold` consecutive failures the breaker *opens*:
+/// callers skip the dependency for `cooldown`, and the outage is logged once.
+/// After the cooldown one trial call is let through (*half-open*); success
+/// closes the breaker, failure re-opens it for another cooldown. Callers
+/// racing the trial are turned away until it reports back (or, if it never
+/// does, for another cooldown).
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls flow normally
+    Closed,
+
+    /// Dependency considered down; calls are skipped
+    Open,
+
+    /// Cooldown elapsed; a single trial call is allowed through
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    
+    /// When the half-open trial permit was handed out
+    trial_started: Option<Instant>,
+}
+
+/// Consecutive-failure circuit breaker
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    name: String,
+    failure_threshold: u32,
+    cooldown: Duration,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name: name.into(),
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            inner: Mutex::new(Inner {
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_started: None,
+            }),
+        }
+    }
+
+    pub fn state(&self) -> BreakerState {
+        let inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => BreakerState::Closed,
+            Some(opened) if opened.elapsed() >= self.cooldown => BreakerState::HalfOpen,
+            Some(_) => BreakerState::Open,
+        }
+    }
+
+    /// Whether a call to the dependency should be attempted now
+    /// 
+    /// Half-open, only the first caller gets a permit; it must report back
+    /// with `record_success` or `record_failure`.
+    pub fn allow(&self) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.opened_at {
+            None => true,
+            Some(opened) if opened.elapsed() >= self.cooldown => {
+                let trial_pending = inner.trial_started.is_some_and(|started| started.elapsed() < self.cooldown);
+                if !trial_pending {
+                    inner.trial_started = Some(Instant::now());
+                }
+                !trial_pending
+            }
+            Some(_) => false,
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.trial_started = None;
+        if inner.opened_at.take().is_some() {
+            info!("✅ {} recovered, circuit closed", self.name);
+        }
+        inner.consecutive_failures = 0;
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.consecutive_failures += 1;
+        inner.trial_started = None;
+
+        let half_open = inner.opened_at.is_some_and(|opened| opened.elapsed() >= self.cooldown);
+        if half_open {
+            // Trial call failed - back to a full cooldown, quietly
+            inner.opened_at = Some(Instant::now());
+        } else if inner.opened_at.is_none() && inner.consecutive_failures >= self.failure_threshold {
+            inner.opened_at = Some(Instant::now());
+            warn!(
+                "🔌 {} failed {} times in a row, skipping it for {:?}",
+                self.name, inner.consecutive_failures, self.cooldown
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_recovers() {
+        let breaker = CircuitBreaker::new("test", 2, Duration::from_millis(20));
+        assert!(breaker.allow());
+
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+        assert!(!breaker.allow());
+
+        std::thread::sleep(Duration::from_millis(25));
+        assert_eq!(breaker.state(), BreakerState::HalfOpen);
+        breaker.record_success();
+        assert_eq!(breaker.state(), BreakerState::Closed);
+    }
+
+    #[test]
+    fn test_failed_trial_reopens() {
+        let breaker = CircuitBreaker::new("test", 1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(breaker.allow());
+        breaker.record_failure();
+        assert_eq!(breaker.state(), BreakerState::Open);
+    }
+
+    #[test]
+    fn test_half_open_admits_one_trial() {
+        let breaker = CircuitBreaker::new("test", 1, Duration::from_millis(20));
+        breaker.record_failure();
+        std::thread::sleep(Duration::from_millis(25));
+
+        assert!(breaker.allow());
+        assert!(!breaker.allow(), "a second caller raced the trial through");
+        breaker.record_success();
+        assert!(breaker.allow());
+        assert!(breaker.allow());
+    }
+}
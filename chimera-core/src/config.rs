@@ -0,0 +1,515 @@
+/// Configuration - One Typed Source of Truth
+///
+/// Every knob the Body reads from the environment is parsed here, once, at
+/// startup. Constructors take the resulting `ChimeraConfig` (or the relevant
+/// field) instead of calling `std::env::var` themselves, so defaults can't drift
+/// between modules (the proxy port used to be parsed in two places).
+///
+/// Invalid values are a startup error rather than a silent fallback: a typo in
+/// `CHIMERA_PROXY_PORT` should stop the container, not launch Chrome pointed at
+/// a proxy that doesn't exist.
+
+use crate::dbi::WebRtcPolicy;
+use crate::pacing::PacingConfig;
+use crate::stealth_transport::{BypassList, UpstreamProxy};
+use anyhow::{bail, Context, Result};
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// TLS impersonation target for the Phantom Proxy's outbound client
+/// 
+/// Drives the proxy client, the User-Agent PhantomBrowser and sessions claim,
+/// and what the JA4 self-test expects to see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImpersonationTarget {
+    /// Chrome 124 - the newest profile shipped by the pinned reqwest-impersonate
+    #[default]
+    Chrome124,
+    
+    /// Firefox 120 - for targets that trust Firefox traffic patterns
+    /// 
+    /// The pinned reqwest-impersonate has no Firefox TLS profile: the UA and
+    /// HTTP/2 SETTINGS follow Firefox, the ClientHello doesn't (the self-test
+    /// reports it).
+    Firefox120,
+}
+
+impl ImpersonationTarget {
+    /// Parse a version name ("chrome124", "124", "firefox120", "firefox")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "chrome124" | "chrome_124" | "124" => Some(Self::Chrome124),
+            "firefox120" | "firefox_120" | "firefox" => Some(Self::Firefox120),
+            _ => None,
+        }
+    }
+    
+    /// Map a `SyntheticProfile` browser label ("Chrome 124", "Firefox 120");
+    /// `None` for browsers the proxy can't impersonate
+    pub fn from_browser_name(browser: &str) -> Option<Self> {
+        let browser = browser.to_ascii_lowercase();
+        if browser.starts_with("chrome") {
+            Some(Self::Chrome124)
+        } else if browser.starts_with("firefox") {
+            Some(Self::Firefox120)
+        } else {
+            None
+        }
+    }
+    
+    /// Canonical name, as accepted by `CHIMERA_IMPERSONATE`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Chrome124 => "chrome124",
+            Self::Firefox120 => "firefox120",
+        }
+    }
+    
+    /// User-Agent matching the TLS fingerprint
+    pub fn user_agent(&self) -> &'static str {
+        match self {
+            Self::Chrome124 => "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+            Self::Firefox120 => "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:120.0) Gecko/20100101 Firefox/120.0",
+        }
+    }
+}
+
+/// What to do when the startup sanitization check fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SanitizationMode {
+    /// Refuse to start (production default)
+    #[default]
+    Enforce,
+
+    /// Log loudly but keep serving (local development)
+    Warn,
+
+    /// Skip binary patching and the verification session entirely
+    Off,
+}
+
+impl SanitizationMode {
+    /// Parse a mode name ("enforce", "warn", "off")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "enforce" | "strict" => Some(Self::Enforce),
+            "warn" => Some(Self::Warn),
+            "off" | "disabled" => Some(Self::Off),
+            _ => None,
+        }
+    }
+}
+
+/// How log lines are rendered on stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable lines (local development)
+    #[default]
+    Text,
+
+    /// One JSON object per line, span fields included (log shippers)
+    Json,
+}
+
+impl LogFormat {
+    /// Parse a format name ("text", "json")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" | "pretty" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// Process-wide configuration, loaded once from the environment
+#[derive(Debug, Clone)]
+pub struct ChimeraConfig {
+    /// Phantom Proxy listen port; Chrome is pointed here (`CHIMERA_PROXY_PORT`)
+    pub proxy_port: u16,
+
+    /// Phantom Proxy bind address (`CHIMERA_PROXY_BIND`)
+    pub proxy_bind: IpAddr,
+
+    /// gRPC agent listen address (`CHIMERA_AGENT_ADDR`)
+    pub agent_addr: String,
+
+    /// Vision service address (`CHIMERA_VISION_ADDR`)
+    pub vision_addr: String,
+
+    /// Prometheus `/metrics` port (`CHIMERA_METRICS_PORT`)
+    pub metrics_port: u16,
+
+    /// JA4 echo endpoint for the proxy self-test (`CHIMERA_JA4_ECHO_URL`)
+    pub ja4_echo_url: Option<String>,
+
+    /// Header echo endpoint for the launch-time profile header check (`CHIMERA_HEADER_ECHO_URL`)
+    pub header_echo_url: Option<String>,
+
+    /// `Accept-Language` the MITM proxy adds to requests without one (`CHIMERA_ACCEPT_LANGUAGE`);
+    /// the session profile's language, sent by Chrome, takes precedence
+    pub accept_language: Option<String>,
+
+    /// Redis for Identity Grafting (`REDIS_URL`, falling back to `CHIMERA_REDIS_URL`)
+    pub redis_url: Option<String>,
+
+    /// TLS impersonation target (`CHIMERA_IMPERSONATE`)
+    pub impersonation_target: ImpersonationTarget,
+
+    /// Startup sanitization policy (`CHIMERA_SANITIZATION`)
+    pub sanitization: SanitizationMode,
+
+    /// Log line rendering (`CHIMERA_LOG_FORMAT`)
+    pub log_format: LogFormat,
+
+    /// Chromium binary to patch (`CHROME_BIN`)
+    pub chrome_bin: String,
+
+    /// Whether to run the binary patcher (`CHIMERA_BINARY_PATCH`)
+    pub binary_patch: bool,
+
+    /// Maximum session lifetime (`CHIMERA_SESSION_TTL_SECS`, unset = unlimited)
+    pub session_ttl: Option<Duration>,
+
+    /// Hosts Chrome reaches directly, skipping the Phantom Proxy (`CHIMERA_NO_PROXY`)
+    pub no_proxy: BypassList,
+
+    /// Upstream HTTP/SOCKS5 proxy the sidecar egresses through (`CHIMERA_UPSTREAM_PROXY`)
+    pub upstream_proxy: Option<UpstreamProxy>,
+
+    /// Upper bound on a single `PerformAction` RPC (`CHIMERA_ACTION_TIMEOUT_SECS`)
+    pub action_timeout: Duration,
+    
+    /// Root CA directory; when set the proxy terminates TLS (`CHIMERA_MITM_CA_DIR`)
+    pub mitm_ca_dir: Option<PathBuf>,
+    
+    /// Predicted risk (0.0-1.0) at which a click is refused (`CHIMERA_RISK_THRESHOLD`)
+    pub risk_threshold: f64,
+    
+    /// Where learned World Model patterns persist across restarts (`CHIMERA_WORLD_MODEL_PATH`)
+    pub world_model_path: PathBuf,
+    
+    /// Session action rhythm (`CHIMERA_ACTIONS_PER_MINUTE`, `CHIMERA_PAUSE_PROBABILITY`)
+    pub pacing: PacingConfig,
+    
+    /// Consecutive vision-service failures that open its circuit (`CHIMERA_VISION_FAILURE_THRESHOLD`)
+    pub vision_failure_threshold: u32,
+    
+    /// How long an open vision circuit fails fast before a trial call (`CHIMERA_VISION_COOLDOWN_SECS`)
+    pub vision_cooldown: Duration,
+    
+    /// JSONL file every executed mouse trajectory is appended to (`CHIMERA_TRAJECTORY_DEBUG`)
+    pub trajectory_debug: Option<PathBuf>,
+    
+    /// What injected scripts do about WebRTC IP leaks (`CHIMERA_WEBRTC_POLICY`)
+    pub webrtc_policy: WebRtcPolicy,
+}
+
+impl Default for ChimeraConfig {
+    fn default() -> Self {
+        Self {
+            proxy_port: 8080,
+            proxy_bind: IpAddr::from([127, 0, 0, 1]),
+            agent_addr: "0.0.0.0:50051".to_string(),
+            vision_addr: "http://brainscraper.railway.internal:50052".to_string(),
+            metrics_port: 9090,
+            ja4_echo_url: None,
+            header_echo_url: None,
+            accept_language: None,
+            redis_url: None,
+            impersonation_target: ImpersonationTarget::default(),
+            sanitization: SanitizationMode::default(),
+            log_format: LogFormat::default(),
+            chrome_bin: "/usr/bin/chromium".to_string(),
+            binary_patch: true,
+            session_ttl: None,
+            no_proxy: BypassList::default(),
+            upstream_proxy: None,
+            action_timeout: Duration::from_secs(60),
+            mitm_ca_dir: None,
+            risk_threshold: 0.7,
+            world_model_path: PathBuf::from("/tmp/chimera-world-model.json"),
+            pacing: PacingConfig::default(),
+            vision_failure_threshold: 5,
+            vision_cooldown: Duration::from_secs(30),
+            trajectory_debug: None,
+            webrtc_policy: WebRtcPolicy::default(),
+        }
+    }
+}
+
+impl ChimeraConfig {
+    /// Load and validate configuration from the process environment
+    pub fn from_env() -> Result<Self> {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    /// Load configuration from an arbitrary key lookup (used by `from_env` and tests)
+    pub fn from_lookup<F>(lookup: F) -> Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let defaults = Self::default();
+        let get = |key: &str| lookup(key).filter(|v| !v.trim().is_empty());
+
+        let proxy_port = match get("CHIMERA_PROXY_PORT") {
+            Some(v) => parse_port("CHIMERA_PROXY_PORT", &v)?,
+            None => defaults.proxy_port,
+        };
+
+        let proxy_bind = match get("CHIMERA_PROXY_BIND") {
+            Some(v) => v.trim().parse()
+                .with_context(|| format!("CHIMERA_PROXY_BIND is not an IP address: {}", v))?,
+            None => defaults.proxy_bind,
+        };
+
+        let metrics_port = match get("CHIMERA_METRICS_PORT") {
+            Some(v) => parse_port("CHIMERA_METRICS_PORT", &v)?,
+            None => defaults.metrics_port,
+        };
+
+        let impersonation_target = match get("CHIMERA_IMPERSONATE") {
+            Some(v) => match ImpersonationTarget::from_name(v.trim()) {
+                Some(version) => version,
+                None => bail!("CHIMERA_IMPERSONATE has unsupported value: {}", v),
+            },
+            None => defaults.impersonation_target,
+        };
+
+        let sanitization = match get("CHIMERA_SANITIZATION") {
+            Some(v) => match SanitizationMode::from_name(v.trim()) {
+                Some(mode) => mode,
+                None => bail!("CHIMERA_SANITIZATION must be enforce, warn or off, got: {}", v),
+            },
+            None => defaults.sanitization,
+        };
+
+        let log_format = match get("CHIMERA_LOG_FORMAT") {
+            Some(v) => match LogFormat::from_name(v.trim()) {
+                Some(format) => format,
+                None => bail!("CHIMERA_LOG_FORMAT must be text or json, got: {}", v),
+            },
+            None => defaults.log_format,
+        };
+
+        let binary_patch = match get("CHIMERA_BINARY_PATCH") {
+            Some(v) => v.trim().parse::<bool>()
+                .with_context(|| format!("CHIMERA_BINARY_PATCH must be true or false, got: {}", v))?,
+            None => defaults.binary_patch,
+        };
+
+        let session_ttl = match get("CHIMERA_SESSION_TTL_SECS") {
+            Some(v) => {
+                let secs: u64 = v.trim().parse()
+                    .with_context(|| format!("CHIMERA_SESSION_TTL_SECS must be a number of seconds, got: {}", v))?;
+                (secs > 0).then(|| Duration::from_secs(secs))
+            }
+            None => defaults.session_ttl,
+        };
+
+        let action_timeout = match get("CHIMERA_ACTION_TIMEOUT_SECS") {
+            Some(v) => match v.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => Duration::from_secs(secs),
+                _ => bail!("CHIMERA_ACTION_TIMEOUT_SECS must be a positive number of seconds, got: {}", v),
+            },
+            None => defaults.action_timeout,
+        };
+
+        let risk_threshold = match get("CHIMERA_RISK_THRESHOLD") {
+            Some(v) => match v.trim().parse::<f64>() {
+                Ok(t) if (0.0..=1.0).contains(&t) => t,
+                _ => bail!("CHIMERA_RISK_THRESHOLD must be between 0.0 and 1.0, got: {}", v),
+            },
+            None => defaults.risk_threshold,
+        };
+
+        let vision_failure_threshold = match get("CHIMERA_VISION_FAILURE_THRESHOLD") {
+            Some(v) => match v.trim().parse::<u32>() {
+                Ok(n) if n > 0 => n,
+                _ => bail!("CHIMERA_VISION_FAILURE_THRESHOLD must be a positive number, got: {}", v),
+            },
+            None => defaults.vision_failure_threshold,
+        };
+
+        let vision_cooldown = match get("CHIMERA_VISION_COOLDOWN_SECS") {
+            Some(v) => match v.trim().parse::<u64>() {
+                Ok(secs) if secs > 0 => Duration::from_secs(secs),
+                _ => bail!("CHIMERA_VISION_COOLDOWN_SECS must be a positive number of seconds, got: {}", v),
+            },
+            None => defaults.vision_cooldown,
+        };
+
+        let webrtc_policy = match get("CHIMERA_WEBRTC_POLICY") {
+            Some(v) => match WebRtcPolicy::from_name(v.trim()) {
+                Some(policy) => policy,
+                None => bail!("CHIMERA_WEBRTC_POLICY must be allow, strip or disable, got: {}", v),
+            },
+            None => defaults.webrtc_policy,
+        };
+
+        let mut pacing = defaults.pacing;
+        if let Some(v) = get("CHIMERA_ACTIONS_PER_MINUTE") {
+            pacing.actions_per_minute = match v.trim().parse::<f64>() {
+                Ok(apm) if apm > 0.0 && apm.is_finite() => apm,
+                _ => bail!("CHIMERA_ACTIONS_PER_MINUTE must be a positive number, got: {}", v),
+            };
+        }
+        if let Some(v) = get("CHIMERA_PAUSE_PROBABILITY") {
+            pacing.long_pause_probability = match v.trim().parse::<f64>() {
+                Ok(p) if (0.0..=1.0).contains(&p) => p,
+                _ => bail!("CHIMERA_PAUSE_PROBABILITY must be between 0.0 and 1.0, got: {}", v),
+            };
+        }
+
+        let no_proxy = match get("CHIMERA_NO_PROXY") {
+            Some(v) => {
+                let patterns: Vec<&str> = v.split(|c| c == ',' || c == ';').collect();
+                BypassList::parse(&patterns).context("CHIMERA_NO_PROXY is invalid")?
+            }
+            None => defaults.no_proxy,
+        };
+
+        let upstream_proxy = match get("CHIMERA_UPSTREAM_PROXY") {
+            Some(v) => Some(UpstreamProxy::parse(&v).context("CHIMERA_UPSTREAM_PROXY is invalid")?),
+            None => None,
+        };
+
+        Ok(Self {
+            proxy_port,
+            proxy_bind,
+            agent_addr: get("CHIMERA_AGENT_ADDR").unwrap_or(defaults.agent_addr),
+            vision_addr: get("CHIMERA_VISION_ADDR").unwrap_or(defaults.vision_addr),
+            metrics_port,
+            ja4_echo_url: get("CHIMERA_JA4_ECHO_URL"),
+            header_echo_url: get("CHIMERA_HEADER_ECHO_URL"),
+            accept_language: get("CHIMERA_ACCEPT_LANGUAGE"),
+            redis_url: get("REDIS_URL").or_else(|| get("CHIMERA_REDIS_URL")),
+            impersonation_target,
+            sanitization,
+            log_format,
+            chrome_bin: get("CHROME_BIN").unwrap_or(defaults.chrome_bin),
+            binary_patch,
+            session_ttl,
+            no_proxy,
+            upstream_proxy,
+            action_timeout,
+            mitm_ca_dir: get("CHIMERA_MITM_CA_DIR").map(PathBuf::from),
+            risk_threshold,
+            world_model_path: get("CHIMERA_WORLD_MODEL_PATH").map(PathBuf::from).unwrap_or(defaults.world_model_path),
+            pacing,
+            vision_failure_threshold,
+            vision_cooldown,
+            trajectory_debug: get("CHIMERA_TRAJECTORY_DEBUG").map(PathBuf::from),
+            webrtc_policy,
+        })
+    }
+
+    /// Where local clients reach the Phantom Proxy: the bind address, or
+    /// loopback when it binds every interface
+    pub fn proxy_addr(&self) -> SocketAddr {
+        let host = match self.proxy_bind {
+            IpAddr::V4(ip) if ip.is_unspecified() => IpAddr::from([127, 0, 0, 1]),
+            IpAddr::V6(ip) if ip.is_unspecified() => IpAddr::V6(std::net::Ipv6Addr::LOCALHOST),
+            ip => ip,
+        };
+        SocketAddr::new(host, self.proxy_port)
+    }
+
+    /// Proxy URL handed to Chrome's `--proxy-server`
+    pub fn proxy_url(&self) -> String {
+        format!("http://{}", self.proxy_addr())
+    }
+}
+
+fn parse_port(key: &str, value: &str) -> Result<u16> {
+    match value.trim().parse::<u16>() {
+        Ok(0) | Err(_) => bail!("{} must be a port number between 1 and 65535, got: {}", key, value),
+        Ok(port) => Ok(port),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<ChimeraConfig> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        ChimeraConfig::from_lookup(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn test_defaults_when_unset() {
+        let config = config_from(&[]).unwrap();
+        assert_eq!(config.proxy_port, 8080);
+        assert_eq!(config.metrics_port, 9090);
+        assert_eq!(config.sanitization, SanitizationMode::Enforce);
+        assert_eq!(config.log_format, LogFormat::Text);
+        assert!(config.redis_url.is_none());
+        assert!(config.session_ttl.is_none());
+    }
+
+    #[test]
+    fn test_parses_overrides() {
+        let config = config_from(&[
+            ("CHIMERA_PROXY_PORT", "9000"),
+            ("CHIMERA_SANITIZATION", "warn"),
+            ("CHIMERA_REDIS_URL", "redis://cache:6379"),
+            ("CHIMERA_SESSION_TTL_SECS", "600"),
+            ("CHIMERA_IMPERSONATE", "firefox120"),
+            ("CHIMERA_LOG_FORMAT", "JSON"),
+            ("CHIMERA_TRAJECTORY_DEBUG", "/tmp/trajectories.jsonl"),
+            ("CHIMERA_VISION_COOLDOWN_SECS", "90"),
+            ("CHIMERA_WEBRTC_POLICY", "disable"),
+        ]).unwrap();
+        assert_eq!(config.proxy_port, 9000);
+        assert_eq!(config.proxy_url(), "http://127.0.0.1:9000");
+
+        let bound = config_from(&[("CHIMERA_PROXY_BIND", "10.0.0.5")]).unwrap();
+        assert_eq!(bound.proxy_url(), "http://10.0.0.5:8080");
+        let everywhere = config_from(&[("CHIMERA_PROXY_BIND", "::")]).unwrap();
+        assert_eq!(everywhere.proxy_url(), "http://[::1]:8080");
+        assert_eq!(config.sanitization, SanitizationMode::Warn);
+        assert_eq!(config.redis_url.as_deref(), Some("redis://cache:6379"));
+        assert_eq!(config.session_ttl, Some(Duration::from_secs(600)));
+        assert_eq!(config.impersonation_target, ImpersonationTarget::Firefox120);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.trajectory_debug, Some(PathBuf::from("/tmp/trajectories.jsonl")));
+        assert_eq!(config.vision_cooldown, Duration::from_secs(90));
+        assert_eq!(config.vision_failure_threshold, 5);
+        assert_eq!(config.webrtc_policy, WebRtcPolicy::Disable);
+    }
+
+    #[test]
+    fn test_rejects_invalid_values() {
+        assert!(config_from(&[("CHIMERA_PROXY_PORT", "eighty")]).is_err());
+        assert!(config_from(&[("CHIMERA_PROXY_PORT", "0")]).is_err());
+        assert!(config_from(&[("CHIMERA_SANITIZATION", "maybe")]).is_err());
+        assert!(config_from(&[("CHIMERA_LOG_FORMAT", "xml")]).is_err());
+        assert!(config_from(&[("CHIMERA_IMPERSONATE", "safari17")]).is_err());
+        assert!(config_from(&[("CHIMERA_BINARY_PATCH", "yes")]).is_err());
+        assert!(config_from(&[("CHIMERA_NO_PROXY", "*")]).is_err());
+        assert!(config_from(&[("CHIMERA_RISK_THRESHOLD", "1.5")]).is_err());
+        assert!(config_from(&[("CHIMERA_ACTIONS_PER_MINUTE", "0")]).is_err());
+        assert!(config_from(&[("CHIMERA_PAUSE_PROBABILITY", "2")]).is_err());
+        assert!(config_from(&[("CHIMERA_NO_PROXY", "ocsp.example.com:http")]).is_err());
+        assert!(config_from(&[("CHIMERA_UPSTREAM_PROXY", "ftp://exit.example.com:21")]).is_err());
+        assert!(config_from(&[("CHIMERA_VISION_FAILURE_THRESHOLD", "0")]).is_err());
+        assert!(config_from(&[("CHIMERA_WEBRTC_POLICY", "block")]).is_err());
+    }
+
+    #[test]
+    fn test_no_proxy_matching() {
+        let config = config_from(&[("CHIMERA_NO_PROXY", "ocsp.digicert.com, *.pki.goog;.internal:8443")]).unwrap();
+        assert_eq!(config.no_proxy.to_chrome_arg(), "ocsp.digicert.com;*.pki.goog;.internal:8443");
+        assert!(config.no_proxy.matches_addr("ocsp.digicert.com:443"));
+        assert!(config.no_proxy.matches_addr("c.pki.goog:443"));
+        assert!(!config.no_proxy.matches_addr("pki.goog:443"));
+        assert!(config.no_proxy.matches_addr("metrics.internal:8443"));
+        assert!(!config.no_proxy.matches_addr("metrics.internal:443"));
+    }
+}
@@ -7,17 +7,73 @@
 /// The fusion of these two creates "God Mode" perception.
 
 use crate::browser::BrowserSession;
+use crate::ghost_mouse::MotorProfile;
+use crate::trajectory::Trajectory;
+use crate::identity_grafting::redis_timeout;
+use crate::vision_client::VisionClient;
 use anyhow::{Context, Result};
 use headless_chrome::Tab;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tracing::{debug, error, info, warn};
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use rand_distr::{Normal, Distribution};
 use std::time::Duration;
 use tokio::time::sleep;
 
+/// Scroll gestures `Cortex::scroll_into_view` makes before giving up
+const MAX_SCROLL_ATTEMPTS: u32 = 15;
+
+/// Average silent reading speed (words per minute) before `MotorProfile::speed_scale`
+const READING_WPM: f64 = 240.0;
+
+/// Share of the visible words a visitor actually reads while scanning a page
+const SKIM_FRACTION: f64 = 0.25;
+
+/// Bounds on one reading pause (ms) in `Cortex::simulate_reading`
+const READING_PAUSE_MS: (u64, u64) = (400, 4000);
+
+/// Chance that a reading scroll goes back up to re-read
+const SCROLL_BACK_PROB: f64 = 0.2;
+
+/// Words of rendered text inside the viewport: each text node whose box is on
+/// screen (script/style and `display: none` text has no box) and not hidden
+const VISIBLE_WORD_COUNT_JS: &str = r#"(() => {
+    const walker = document.createTreeWalker(document.body || document.documentElement, NodeFilter.SHOW_TEXT);
+    const range = document.createRange();
+    let words = 0;
+    for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+        const text = node.textContent.trim();
+        if (!text) continue;
+        range.selectNodeContents(node);
+        const r = range.getBoundingClientRect();
+        if (r.width === 0 || r.bottom <= 0 || r.right <= 0 || r.top >= innerHeight || r.left >= innerWidth) continue;
+        if (getComputedStyle(node.parentElement).visibility !== 'visible') continue;
+        words += text.split(/\s+/).length;
+    }
+    return words;
+})()"#;
+
+/// Reasonable `name_match_score` floor for accepting a fuzzy match
+pub const DEFAULT_FUZZY_THRESHOLD: f64 = 0.6;
+
+/// Largest gap (px) between two matches that `find_rois` still treats as one region
+const ROI_CLUSTER_GAP: f64 = 48.0;
+
+/// Rough characters per LLM token, for `AxTree::to_markdown`'s budget
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Tokens held back for the "... omitted" note when `to_markdown` truncates
+const OMISSION_NOTE_TOKENS: usize = 12;
+
+/// Names and values longer than this (chars) are cut in the Markdown outline
+const OUTLINE_MAX_TEXT: usize = 120;
+
+/// AX properties that say nothing useful to an LLM planning its next step
+const OUTLINE_NOISE_STATES: &[&str] = &["focusable", "editable", "settable", "richlyEditable", "multiline"];
+
 /// Accessibility Tree Node - The "Truth" of page structure
 /// 
 /// This represents a semantic element from the page's accessibility tree.
@@ -32,8 +88,19 @@ pub struct AxNode {
     pub parent_id: Option<String>,
     pub bounds: Option<AxBounds>, // Screen coordinates (if available)
     pub state: Vec<String>,   // ["enabled", "visible"], ["disabled"], etc.
+    
+    /// Owning frame (None = top-level document); iframe node ids are prefixed with it
+    #[serde(default)]
+    pub frame_id: Option<String>,
+    
+    /// Backing DOM node (`backendDOMNodeId`) for DOM-level CDP calls; None for virtual nodes
+    #[serde(default)]
+    pub backend_node_id: Option<i64>,
 }
 
+/// (role, name, frame) - what makes two snapshots' nodes the same element
+type IdentityKey<'a> = (&'a str, Option<&'a str>, Option<&'a str>);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AxBounds {
     pub x: f64,
@@ -42,21 +109,503 @@ pub struct AxBounds {
     pub height: f64,
 }
 
+impl AxNode {
+    /// Whether two nodes denote the same element across snapshots
+    ///
+    /// `node_id`s churn across navigations and re-renders, so identity is
+    /// role + accessible name (within the same frame).
+    pub fn same_identity(&self, other: &AxNode) -> bool {
+        self.identity_key() == other.identity_key()
+    }
+    
+    /// What `same_identity` compares
+    fn identity_key(&self) -> IdentityKey<'_> {
+        (&self.role, self.name.as_deref(), self.frame_id.as_deref())
+    }
+}
+
+/// Which captcha vendor a page is challenging us with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptchaKind {
+    Recaptcha,
+    Hcaptcha,
+    Turnstile,
+    /// Captcha-like challenge from an unrecognized vendor (or a bare image grid)
+    Unknown,
+}
+
+impl CaptchaKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Recaptcha => "recaptcha",
+            Self::Hcaptcha => "hcaptcha",
+            Self::Turnstile => "turnstile",
+            Self::Unknown => "unknown",
+        }
+    }
+    
+    /// Vendor from a widget iframe URL or accessible name, if it names one
+    fn from_signature(text: &str) -> Option<Self> {
+        let text = text.to_ascii_lowercase();
+        if text.contains("recaptcha") || text.contains("google.com/recaptcha") {
+            Some(Self::Recaptcha)
+        } else if text.contains("hcaptcha") {
+            Some(Self::Hcaptcha)
+        } else if text.contains("turnstile") || text.contains("challenges.cloudflare.com") || text.contains("cloudflare security challenge") {
+            Some(Self::Turnstile)
+        } else {
+            None
+        }
+    }
+    
+    /// Vendor of a frame URL, if the frame is a challenge shown to the user
+    /// 
+    /// reCAPTCHA serves its image challenge from a `bframe` and its checkbox
+    /// from an `anchor` frame. v3 and invisible widgets load the anchor with
+    /// `size=invisible` (plus helper frames) on perfectly ordinary pages, so
+    /// those don't count.
+    fn from_challenge_frame(url: &str) -> Option<Self> {
+        let kind = Self::from_signature(url)?;
+        let url = url.to_ascii_lowercase();
+        if url.contains("size=invisible") {
+            return None;
+        }
+        if kind == Self::Recaptcha && !(url.contains("/bframe") || url.contains("/anchor")) {
+            return None;
+        }
+        Some(kind)
+    }
+}
+
 /// Accessibility Tree - The structural truth
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AxTree {
     pub nodes: Vec<AxNode>,
 }
 
+impl AxTree {
+    /// Find the node in this tree that corresponds to `node` from an older snapshot
+    ///
+    /// Prefers the same `node_id` if it still denotes the same element,
+    /// otherwise re-resolves by role + name.
+    pub fn find_equivalent(&self, node: &AxNode) -> Option<&AxNode> {
+        self.nodes
+            .iter()
+            .find(|n| n.node_id == node.node_id && n.same_identity(node))
+            .or_else(|| self.nodes.iter().find(|n| n.same_identity(node)))
+    }
+    
+    /// The node best matching `role` and `name`
+    /// 
+    /// Exact name match first, then the best fuzzy match scoring at least
+    /// `DEFAULT_FUZZY_THRESHOLD`; with no name, the first node of the role.
+    /// Geometry isn't considered - see `Cortex::node_bounds`.
+    pub fn locate(&self, role: &str, name: Option<&str>) -> Option<&AxNode> {
+        let of_role = || self.nodes.iter().filter(|n| n.role.eq_ignore_ascii_case(role));
+        match name {
+            None => of_role().next(),
+            Some(name) => of_role()
+                .find(|n| n.name.as_deref() == Some(name))
+                .or_else(|| of_role()
+                    .filter_map(|n| {
+                        let score = name_match_score(n.name.as_deref()?, name);
+                        (score >= DEFAULT_FUZZY_THRESHOLD).then_some((score, n))
+                    })
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, n)| n)),
+        }
+    }
+    
+    /// Center of the element nearest `(x, y)`, if one lies within `radius`
+    /// 
+    /// Containers wider or taller than the search diameter are skipped - the
+    /// page root always "contains" the cursor but isn't something being read.
+    /// Only nodes with bounds are considered; live snapshots need
+    /// `Cortex::resolve_bounds` for the candidates first.
+    pub fn nearest(&self, x: f64, y: f64, radius: f64) -> Option<(f64, f64)> {
+        self.nodes
+            .iter()
+            .filter_map(|n| n.bounds.as_ref())
+            .filter(|b| b.width <= radius * 2.0 && b.height <= radius * 2.0)
+            .map(|b| (b.x + b.width / 2.0, b.y + b.height / 2.0))
+            .map(|(cx, cy)| ((cx - x).hypot(cy - y), (cx, cy)))
+            .filter(|(distance, _)| *distance <= radius)
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, center)| center)
+    }
+    
+    /// What changed from `self` (before) to `other` (after)
+    /// 
+    /// Nodes are paired by identity (`AxNode::same_identity`), in document
+    /// order when several share one, since `node_id`s churn between
+    /// snapshots. A paired node whose value or state differs is *changed*;
+    /// unpaired ones were added or removed. Bounds are ignored - layout is
+    /// what the visual hash already covers.
+    pub fn diff(&self, other: &AxTree) -> AxDiff {
+        let mut unmatched: HashMap<IdentityKey, VecDeque<&AxNode>> = HashMap::new();
+        for node in &self.nodes {
+            unmatched.entry(node.identity_key()).or_default().push_back(node);
+        }
+        
+        let mut diff = AxDiff::default();
+        for after in &other.nodes {
+            match unmatched.get_mut(&after.identity_key()).and_then(VecDeque::pop_front) {
+                Some(before) if before.value != after.value || before.state != after.state => {
+                    diff.changed.push((before.clone(), after.clone()));
+                }
+                Some(_) => {}
+                None => diff.added.push(after.clone()),
+            }
+        }
+        
+        // Whatever wasn't paired is gone; report it in document order
+        let mut removed: Vec<&AxNode> = unmatched.into_values().flatten().collect();
+        removed.sort_by_key(|node| self.nodes.iter().position(|n| std::ptr::eq(n, *node)));
+        diff.removed = removed.into_iter().cloned().collect();
+        diff
+    }
+    
+    /// Render the tree as an indented Markdown outline for an LLM prompt
+    /// 
+    /// One line per element - role, quoted name, value and interactable
+    /// state, no bounds - nested by `parent_id`. Unnamed layout containers are
+    /// flattened into their parent and text repeating its parent's name is
+    /// dropped. If the outline would exceed `token_budget` (estimated at
+    /// `CHARS_PER_TOKEN`), whole branches are cut, least important first:
+    /// generic containers, then plain text, then structure, deepest first, so
+    /// controls survive longest. Pass `usize::MAX` for no limit.
+    pub fn to_markdown(&self, token_budget: usize) -> String {
+        let lines = self.outline();
+        let cost = |line: &OutlineLine| (line.depth * 2 + line.text.len() + 1).div_ceil(CHARS_PER_TOKEN);
+        let mut total: usize = lines.iter().map(cost).sum();
+        let mut kept = vec![true; lines.len()];
+        let mut omitted = 0;
+        
+        if total > token_budget {
+            let budget = token_budget.saturating_sub(OMISSION_NOTE_TOKENS);
+            let mut order: Vec<usize> = (0..lines.len()).collect();
+            order.sort_by_key(|&i| {
+                let line = &lines[i];
+                (line.branch_priority, line.priority, std::cmp::Reverse(line.depth), std::cmp::Reverse(i))
+            });
+            for i in order {
+                if total <= budget {
+                    break;
+                }
+                let branch = i..lines[i].subtree_end;
+                for (line, kept) in lines[branch.clone()].iter().zip(&mut kept[branch]) {
+                    if *kept {
+                        *kept = false;
+                        total -= cost(line);
+                        omitted += 1;
+                    }
+                }
+            }
+        }
+        
+        let mut markdown = String::new();
+        for (line, _) in lines.iter().zip(&kept).filter(|(_, kept)| **kept) {
+            markdown.push_str(&"  ".repeat(line.depth));
+            markdown.push_str(&line.text);
+            markdown.push('\n');
+        }
+        if omitted > 0 {
+            markdown.push_str(&format!("- … {} more elements omitted\n", omitted));
+        }
+        markdown
+    }
+    
+    /// Outline lines in document order, before any budget is applied
+    fn outline(&self) -> Vec<OutlineLine> {
+        let index: HashMap<&str, usize> = self.nodes.iter()
+            .enumerate()
+            .map(|(i, n)| (n.node_id.as_str(), i))
+            .collect();
+        let mut children = vec![Vec::new(); self.nodes.len()];
+        let mut roots = Vec::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            match node.parent_id.as_deref().and_then(|p| index.get(p)) {
+                Some(&parent) if parent != i => children[parent].push(i),
+                _ => roots.push(i),
+            }
+        }
+        
+        let mut lines = Vec::new();
+        for root in roots {
+            self.push_outline(root, 0, None, &children, &mut lines);
+        }
+        lines
+    }
+    
+    /// Append node `i`'s subtree; returns the most important priority it added
+    fn push_outline(
+        &self,
+        i: usize,
+        depth: usize,
+        parent_name: Option<&str>,
+        children: &[Vec<usize>],
+        lines: &mut Vec<OutlineLine>,
+    ) -> u8 {
+        let node = &self.nodes[i];
+        let name = node.name.as_deref().map(outline_text).filter(|n| !n.is_empty());
+        let value = node.value.as_deref().map(outline_text).filter(|v| !v.is_empty());
+        let priority = outline_priority(&node.role);
+        
+        if matches!(node.role.as_str(), "InlineTextBox" | "LineBreak")
+            || (node.role == "StaticText" && (name.is_none() || name.as_deref() == parent_name))
+        {
+            return 0;
+        }
+        
+        // Unnamed layout wrappers add depth but no meaning
+        if priority == 0 && name.is_none() && value.is_none() {
+            return children[i].iter()
+                .map(|&child| self.push_outline(child, depth, parent_name, children, lines))
+                .max()
+                .unwrap_or(0);
+        }
+        
+        let mut text = format!("- {}", node.role);
+        if let Some(name) = &name {
+            text.push_str(&format!(" \"{}\"", name));
+        }
+        if let Some(value) = &value {
+            text.push_str(&format!(" = \"{}\"", value));
+        }
+        let state: Vec<&str> = node.state.iter()
+            .map(String::as_str)
+            .filter(|s| !OUTLINE_NOISE_STATES.contains(s))
+            .collect();
+        if !state.is_empty() {
+            text.push_str(&format!(" [{}]", state.join(", ")));
+        }
+        
+        let at = lines.len();
+        lines.push(OutlineLine { depth, text, priority, branch_priority: priority, subtree_end: at + 1 });
+        let branch_priority = children[i].iter()
+            .map(|&child| self.push_outline(child, depth + 1, name.as_deref(), children, lines))
+            .fold(priority, u8::max);
+        lines[at].branch_priority = branch_priority;
+        lines[at].subtree_end = lines.len();
+        branch_priority
+    }
+}
+
+/// AX states that flip without the page reacting: focus follows the click itself
+const TRANSIENT_STATES: &[&str] = &["focused", "busy"];
+
+/// What an action did to the page's semantics (see `AxTree::diff`)
+/// 
+/// Stronger evidence than the visual hash for state changes that barely move
+/// pixels: a cart count ticking up, a checkbox becoming checked, an inline
+/// validation error appearing.
+#[derive(Debug, Clone, Default)]
+pub struct AxDiff {
+    /// Nodes only in the newer snapshot
+    pub added: Vec<AxNode>,
+    
+    /// Nodes only in the older snapshot
+    pub removed: Vec<AxNode>,
+    
+    /// (before, after) of nodes whose value or state changed
+    pub changed: Vec<(AxNode, AxNode)>,
+}
+
+impl AxDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+    
+    /// Whether a node of `role` (and `name`, if given) appeared or changed
+    /// 
+    /// The check for "the element I expected to react did": e.g.
+    /// `touched("status", Some("Cart"))` after adding to cart.
+    pub fn touched(&self, role: &str, name: Option<&str>) -> bool {
+        self.added
+            .iter()
+            .chain(self.changed.iter().map(|(_, after)| after))
+            .any(|n| n.role.eq_ignore_ascii_case(role) && name.is_none_or(|name| n.name.as_deref() == Some(name)))
+    }
+    
+    /// The part of this diff an action caused, given what changed on its own
+    /// 
+    /// `ambient` is a diff over a stretch with no action in it (e.g. from the
+    /// observe snapshot to just before clicking). Nodes it touched - same
+    /// identity, same DOM node, or a same-role sibling - are timers,
+    /// carousels and tickers, and are dropped here along with changes that
+    /// only toggle `TRANSIENT_STATES`.
+    pub fn reaction(&self, ambient: &AxDiff) -> AxDiff {
+        let churned: Vec<&AxNode> = ambient.added
+            .iter()
+            .chain(&ambient.removed)
+            .chain(ambient.changed.iter().map(|(_, after)| after))
+            .collect();
+        let is_ambient = |node: &AxNode| churned.iter().any(|c| {
+            c.same_identity(node)
+                || (c.backend_node_id.is_some() && c.backend_node_id == node.backend_node_id)
+                || (c.parent_id.is_some() && c.parent_id == node.parent_id && c.role == node.role)
+        });
+        let transient_only = |before: &AxNode, after: &AxNode| {
+            before.value == after.value
+                && before.state.iter().filter(|s| !after.state.contains(*s))
+                    .chain(after.state.iter().filter(|s| !before.state.contains(*s)))
+                    .all(|s| TRANSIENT_STATES.contains(&s.as_str()))
+        };
+        
+        AxDiff {
+            added: self.added.iter().filter(|n| !is_ambient(n)).cloned().collect(),
+            removed: self.removed.iter().filter(|n| !is_ambient(n)).cloned().collect(),
+            changed: self.changed
+                .iter()
+                .filter(|(before, after)| !transient_only(before, after) && !is_ambient(after))
+                .cloned()
+                .collect(),
+        }
+    }
+    
+    /// Counts for logs, e.g. `+2 -0 ~1`
+    pub fn summary(&self) -> String {
+        format!("+{} -{} ~{}", self.added.len(), self.removed.len(), self.changed.len())
+    }
+}
+
+/// One rendered element of `AxTree::to_markdown`
+struct OutlineLine {
+    depth: usize,
+    text: String,
+    
+    /// Importance of this element alone (see `outline_priority`)
+    priority: u8,
+    
+    /// Importance of the most important element in its subtree
+    branch_priority: u8,
+    
+    /// Index one past this element's last descendant
+    subtree_end: usize,
+}
+
+/// How much an element matters to a planner: 0 layout, 1 content, 2 structure, 3 control
+fn outline_priority(role: &str) -> u8 {
+    match role {
+        "generic" | "none" | "presentation" | "group" | "section" | "div" | "Section"
+        | "LayoutTable" | "LayoutTableRow" | "LayoutTableCell" => 0,
+        "RootWebArea" | "Iframe" | "heading" | "banner" | "navigation" | "main" | "contentinfo"
+        | "complementary" | "form" | "search" | "region" | "dialog" | "alertdialog" | "alert" => 2,
+        "button" | "link" | "textbox" | "searchbox" | "checkbox" | "radio" | "combobox" | "listbox"
+        | "option" | "menuitem" | "menuitemcheckbox" | "menuitemradio" | "tab" | "switch" | "slider"
+        | "spinbutton" | "PopUpButton" | "MenuListOption" => 3,
+        _ => 1,
+    }
+}
+
+/// Collapse whitespace and cap length so one element stays on one short line
+fn outline_text(text: &str) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ").replace('"', "\\\"");
+    match collapsed.char_indices().nth(OUTLINE_MAX_TEXT) {
+        Some((cut, _)) => format!("{}…", &collapsed[..cut]),
+        None => collapsed,
+    }
+}
+
+/// Options for `Cortex::snapshot_accessibility_tree_with`
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotOptions {
+    /// Also walk shadow roots (Lit/Stencil web components) and merge any
+    /// elements the AX tree left out. Costs a DOM walk plus one CDP call per
+    /// missing element, so it's opt-in.
+    pub pierce_shadow_roots: bool,
+    
+    /// Also snapshot child frames (payment forms, captchas), tagging nodes with
+    /// their frame and translating frame-local bounds to top-level viewport
+    /// coordinates. One extra AX + box-model call per frame.
+    pub include_iframes: bool,
+}
+
 /// Cortex - Extracts the Accessibility Tree (The "Truth")
 pub struct Cortex {
     tab: Arc<Tab>,
+    
+    /// Most recent snapshot (used to re-resolve stale node ids)
+    last_tree: Mutex<Option<AxTree>>,
+    
+    /// Redis used by Identity Grafting (checked by `verify_engine_health`)
+    redis_url: Option<String>,
+    
+    /// Source of motion entropy (the session's mouse stream); None until
+    /// seeded, and only snapshots work without it
+    rng: Mutex<Option<StdRng>>,
+    
+    /// Where the cursor was last moved to (trajectories start here); shared
+    /// with the session when made by `BrowserSession::cortex`
+    mouse: Arc<Mutex<(f64, f64)>>,
+    
+    /// Pace of the user behind this tab (scroll distances, reading speed)
+    motor: MotorProfile,
 }
 
 impl Cortex {
     /// Create a new Cortex instance
     pub fn new(tab: Arc<Tab>) -> Self {
-        Self { tab }
+        Self {
+            tab,
+            last_tree: Mutex::new(None),
+            redis_url: None,
+            rng: Mutex::new(None),
+            mouse: Arc::new(Mutex::new((960.0, 540.0))),
+            motor: MotorProfile::default(),
+        }
+    }
+    
+    /// Start trajectories from a known cursor position (e.g. `BrowserSession::mouse_position`)
+    pub fn with_mouse_position(self, position: (f64, f64)) -> Self {
+        *self.mouse.lock().unwrap() = position;
+        self
+    }
+    
+    /// Track the cursor in `mouse`, so its owner sees where this Cortex leaves it
+    pub fn with_shared_mouse(mut self, mouse: Arc<Mutex<(f64, f64)>>) -> Self {
+        self.mouse = mouse;
+        self
+    }
+    
+    /// Scroll and read at this user's pace (e.g. `BrowserSession::motor_profile`)
+    pub fn with_motor_profile(mut self, profile: MotorProfile) -> Self {
+        self.motor = profile;
+        self
+    }
+    
+    /// Last known cursor position
+    pub fn mouse_position(&self) -> (f64, f64) {
+        *self.mouse.lock().unwrap()
+    }
+    
+    /// Seed the motion generator so clicks and scrolls replay identically
+    /// 
+    /// `None` leaves it unseeded: snapshots work, motion methods fail.
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        if let Some(seed) = seed {
+            self.rng = Mutex::new(Some(StdRng::seed_from_u64(seed)));
+        }
+        self
+    }
+    
+    /// Derive a per-call RNG from the Cortex generator
+    /// 
+    /// Motion methods sleep between steps, so they own their RNG rather than
+    /// holding the lock across awaits. Fails on an unseeded Cortex, so motion
+    /// never falls back to entropy outside the session's seed.
+    fn motion_rng(&self) -> Result<StdRng> {
+        let mut rng = self.rng.lock().unwrap();
+        let rng = rng
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Cortex motion needs a seed (use BrowserSession::cortex or with_seed)"))?;
+        Ok(StdRng::seed_from_u64(rng.gen()))
+    }
+    
+    /// Set the Redis URL whose profile store `verify_engine_health` checks
+    pub fn with_redis_url(mut self, redis_url: Option<String>) -> Self {
+        self.redis_url = redis_url;
+        self
     }
     
     /// Extract the full accessibility tree using CDP
@@ -64,6 +613,11 @@ impl Cortex {
     /// This calls the raw CDP method 'Accessibility.getFullAXTree'
     /// to get the structural truth of the page.
     pub fn snapshot_accessibility_tree(&self) -> Result<AxTree> {
+        self.snapshot_accessibility_tree_with(&SnapshotOptions::default())
+    }
+    
+    /// Extract the accessibility tree with explicit options (e.g. shadow-root piercing)
+    pub fn snapshot_accessibility_tree_with(&self, options: &SnapshotOptions) -> Result<AxTree> {
         debug!("Extracting accessibility tree via CDP");
         
         // Call the CDP method directly
@@ -81,11 +635,32 @@ impl Cortex {
         
         // Parse the raw CDP response
         // The response structure: { "nodes": [...] }
-        let nodes_array = result
+        let mut nodes_array = result
             .get("nodes")
             .and_then(|v| v.as_array())
-            .ok_or_else(|| anyhow::anyhow!("No AX nodes in response"))?;
+            .ok_or_else(|| anyhow::anyhow!("No AX nodes in response"))?
+            .clone();
+        
+        if options.pierce_shadow_roots {
+            self.merge_shadow_nodes(&mut nodes_array)?;
+        }
         
+        let mut clean_nodes = Self::parse_ax_nodes(&nodes_array)?;
+        
+        if options.include_iframes {
+            self.append_iframe_nodes(&mut clean_nodes)?;
+        }
+        
+        info!("Extracted {} AX nodes from accessibility tree", clean_nodes.len());
+        
+        let tree = AxTree { nodes: clean_nodes };
+        *self.last_tree.lock().unwrap() = Some(tree.clone());
+        
+        Ok(tree)
+    }
+    
+    /// Turn a raw CDP `nodes` array into clean AX nodes (noise filtered, parents linked)
+    fn parse_ax_nodes(nodes_array: &[serde_json::Value]) -> Result<Vec<AxNode>> {
         let mut clean_nodes = Vec::new();
         let mut node_map: HashMap<String, &serde_json::Value> = HashMap::new();
         
@@ -110,9 +685,163 @@ impl Cortex {
             }
         }
         
-        info!("Extracted {} AX nodes from accessibility tree", clean_nodes.len());
+        Ok(clean_nodes)
+    }
+    
+    /// Snapshot every child frame and append its nodes in top-level coordinates
+    /// 
+    /// Node ids are prefixed with `{frame_id}:` so they can't collide with the
+    /// main document's ids. Frames without a visible host element are skipped,
+    /// and so are out-of-process iframes (they live in another target) - those
+    /// are logged, since their content is missing from the tree.
+    fn append_iframe_nodes(&self, nodes: &mut Vec<AxNode>) -> Result<()> {
+        let frame_tree = self.call_cdp("Page.getFrameTree", serde_json::json!({}))?;
+        
+        let mut frame_ids = Vec::new();
+        if let Some(root) = frame_tree.get("frameTree") {
+            collect_child_frames(root, &mut frame_ids);
+        }
         
-        Ok(AxTree { nodes: clean_nodes })
+        let mut out_of_process = Vec::new();
+        for frame_id in frame_ids {
+            let Some(offset) = self.frame_offset(&frame_id)? else {
+                debug!("Frame {} has no visible host element, skipping", frame_id);
+                continue;
+            };
+            
+            let result = match self.call_cdp(
+                "Accessibility.getFullAXTree",
+                serde_json::json!({ "depth": -1, "frameId": frame_id }),
+            ) {
+                Ok(r) => r,
+                Err(e) => {
+                    // Out-of-process iframes live in another target
+                    debug!("No AX tree for frame {}: {}", frame_id, e);
+                    out_of_process.push(frame_id);
+                    continue;
+                }
+            };
+            let raw = result.get("nodes").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            
+            let mut frame_nodes = Self::parse_ax_nodes(&raw)?;
+            for node in &mut frame_nodes {
+                node.node_id = format!("{}:{}", frame_id, node.node_id);
+                node.parent_id = node.parent_id.take().map(|p| format!("{}:{}", frame_id, p));
+                if let Some(bounds) = node.bounds.as_mut() {
+                    bounds.x += offset.0;
+                    bounds.y += offset.1;
+                }
+                node.frame_id = Some(frame_id.clone());
+            }
+            
+            debug!("Frame {}: {} AX nodes at offset ({:.0}, {:.0})", frame_id, frame_nodes.len(), offset.0, offset.1);
+            nodes.extend(frame_nodes);
+        }
+        
+        if !out_of_process.is_empty() {
+            warn!(
+                "AX snapshot is missing {} out-of-process iframe(s) (cross-site content not visible to the semantic layer): {:?}",
+                out_of_process.len(),
+                out_of_process
+            );
+        }
+        
+        Ok(())
+    }
+    
+    /// Top-level viewport offset of a frame's content (its host `<iframe>` content box)
+    /// 
+    /// DOM box models are reported in main-frame viewport coordinates, so this
+    /// already includes any ancestor frames' offsets.
+    fn frame_offset(&self, frame_id: &str) -> Result<Option<(f64, f64)>> {
+        let owner = match self.call_cdp("DOM.getFrameOwner", serde_json::json!({ "frameId": frame_id })) {
+            Ok(owner) => owner,
+            Err(e) => {
+                debug!("DOM.getFrameOwner failed for frame {}: {}", frame_id, e);
+                return Ok(None);
+            }
+        };
+        let Some(backend_node_id) = owner.get("backendNodeId").and_then(|v| v.as_i64()) else {
+            return Ok(None);
+        };
+        
+        Ok(self.backend_node_bounds(backend_node_id)?.map(|b| (b.x, b.y)))
+    }
+    
+    /// Merge AX nodes for shadow-root content missing from the full tree
+    /// 
+    /// Walks the DOM with `pierce: true`, and for each shadow-tree element whose
+    /// backend node has no AX node yet, fetches it via `Accessibility.getPartialAXTree`.
+    /// Merged nodes are linked under the AX node of their nearest DOM ancestor
+    /// (ultimately the shadow host) so parent links stay correct.
+    fn merge_shadow_nodes(&self, raw_nodes: &mut Vec<serde_json::Value>) -> Result<()> {
+        let document = self.call_cdp("DOM.getDocument", serde_json::json!({ "depth": -1, "pierce": true }))?;
+        
+        // (DOM parent backendNodeId, element backendNodeId) in document order
+        let mut shadow_elements = Vec::new();
+        if let Some(root) = document.get("root") {
+            collect_shadow_elements(root, None, false, &mut shadow_elements);
+        }
+        
+        // backendDOMNodeId -> index into raw_nodes
+        let mut by_backend: HashMap<i64, usize> = raw_nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, n)| Some((n.get("backendDOMNodeId")?.as_i64()?, i)))
+            .collect();
+        
+        let mut merged = 0;
+        for (dom_parent, backend_id) in shadow_elements {
+            if by_backend.contains_key(&backend_id) {
+                continue;
+            }
+            
+            let partial = match self.call_cdp(
+                "Accessibility.getPartialAXTree",
+                serde_json::json!({ "backendNodeId": backend_id, "fetchRelatives": false }),
+            ) {
+                Ok(p) => p,
+                Err(e) => {
+                    debug!("No AX node for shadow element {}: {}", backend_id, e);
+                    continue;
+                }
+            };
+            
+            let Some(mut node) = partial
+                .get("nodes")
+                .and_then(|v| v.as_array())
+                .and_then(|nodes| nodes.first().cloned())
+            else {
+                continue;
+            };
+            let Some(node_id) = node.get("nodeId").and_then(|v| v.as_str()).map(|s| s.to_string()) else {
+                continue;
+            };
+            
+            // Link under the nearest DOM ancestor that has an AX node
+            if let Some(parent_idx) = dom_parent.and_then(|p| by_backend.get(&p).copied()) {
+                if let Some(parent_node_id) = raw_nodes[parent_idx].get("nodeId").cloned() {
+                    node["parentId"] = parent_node_id;
+                }
+                let parent = &mut raw_nodes[parent_idx];
+                match parent.get_mut("childIds").and_then(|c| c.as_array_mut()) {
+                    Some(children) => children.push(serde_json::Value::String(node_id)),
+                    None => parent["childIds"] = serde_json::json!([node_id]),
+                }
+            }
+            // Children are merged individually; don't follow stale ids from the partial tree
+            node["childIds"] = serde_json::json!([]);
+            
+            by_backend.insert(backend_id, raw_nodes.len());
+            raw_nodes.push(node);
+            merged += 1;
+        }
+        
+        if merged > 0 {
+            info!("🌑 Merged {} shadow-DOM nodes into the accessibility tree", merged);
+        }
+        
+        Ok(())
     }
     
     /// Parse AX node recursively from CDP response
@@ -157,6 +886,9 @@ impl Cortex {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
         
+        // DOM node behind it (DOM.focus, DOM.getBoxModel, DOM.scrollIntoViewIfNeeded)
+        let backend_node_id = node.get("backendDOMNodeId").and_then(|v| v.as_i64());
+        
         // Extract bounds (if available)
         let bounds = node
             .get("boundingBox")
@@ -193,30 +925,530 @@ impl Cortex {
                 parent_id: parent_id.clone(),
                 bounds,
                 state,
+                frame_id: None,
+                backend_node_id,
             };
             
             output.push(ax_node);
         }
-        
-        // Process children recursively
-        if let Some(child_ids) = node.get("childIds").and_then(|c| c.as_array()) {
-            for child_id_value in child_ids {
-                if let Some(child_id) = child_id_value.as_str() {
-                    if let Some(child_node) = node_map.get(child_id) {
-                        Self::parse_ax_node_recursive(
-                            child_node,
-                            Some(node_id.clone()),
-                            node_map,
-                            output,
-                        )?;
-                    }
-                }
+        
+        // Process children recursively
+        if let Some(child_ids) = node.get("childIds").and_then(|c| c.as_array()) {
+            for child_id_value in child_ids {
+                if let Some(child_id) = child_id_value.as_str() {
+                    if let Some(child_node) = node_map.get(child_id) {
+                        // Children of skipped noise hang off the nearest kept ancestor
+                        Self::parse_ax_node_recursive(
+                            child_node,
+                            if skip_this_node { parent_id.clone() } else { Some(node_id.clone()) },
+                            node_map,
+                            output,
+                        )?;
+                    }
+                }
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Check the page for a reCAPTCHA / hCaptcha / Turnstile challenge
+    /// 
+    /// Looks at frame URLs (widgets load from their vendor's domain) and at the
+    /// AX tree: challenge iframe titles and image-challenge grids ("Select all
+    /// images with..."). Only a challenge actually in front of the user counts;
+    /// invisible reCAPTCHA v3 frames and the "protected by reCAPTCHA" badge sit
+    /// on ordinary pages. Any failure to inspect the page counts as no captcha.
+    pub fn detect_captcha(&self) -> Option<CaptchaKind> {
+        if let Ok(frame_tree) = self.call_cdp("Page.getFrameTree", serde_json::json!({})) {
+            let mut urls = Vec::new();
+            if let Some(root) = frame_tree.get("frameTree") {
+                collect_frame_urls(root, &mut urls);
+            }
+            if let Some(kind) = urls.iter().find_map(|url| CaptchaKind::from_challenge_frame(url)) {
+                debug!("Captcha frame detected: {:?}", kind);
+                return Some(kind);
+            }
+        }
+        
+        let tree = self.snapshot_accessibility_tree().ok()?;
+        let kind = detect_captcha_in_tree(&tree);
+        if let Some(kind) = kind {
+            debug!("Captcha detected in AX tree: {:?}", kind);
+        }
+        kind
+    }
+    
+    /// Call a raw CDP method on this tab and return the JSON result
+    fn call_cdp(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value> {
+        self.tab
+            .call_method(method, params)
+            .with_context(|| format!("Failed to call {}", method))
+    }
+
+    /// Look up the backing DOM `backendNodeId` of an AX node in the live tree
+    /// 
+    /// Iframe nodes (`{frame_id}:{id}`, see `append_iframe_nodes`) are looked
+    /// up in their frame's tree.
+    fn lookup_backend_node_id(&self, node_id: &str) -> Result<Option<i64>> {
+        let (params, node_id) = match node_id.split_once(':') {
+            Some((frame_id, id)) => (serde_json::json!({ "depth": -1, "frameId": frame_id }), id),
+            None => (serde_json::json!({ "depth": -1 }), node_id),
+        };
+        let tree = self.call_cdp("Accessibility.getFullAXTree", params)?;
+
+        Ok(tree
+            .get("nodes")
+            .and_then(|v| v.as_array())
+            .and_then(|nodes| {
+                nodes.iter().find(|n| n.get("nodeId").and_then(|v| v.as_str()) == Some(node_id))
+            })
+            .and_then(|n| n.get("backendDOMNodeId"))
+            .and_then(|v| v.as_i64()))
+    }
+
+    /// Resolve the *current* on-screen bounds of an AX node via CDP
+    ///
+    /// AX snapshot bounds are a picture of the past - if the page scrolled or
+    /// re-laid out since the snapshot, they point at the wrong pixels. This
+    /// re-reads the node's backing DOM node and asks `DOM.getBoxModel` where it
+    /// is right now.
+    ///
+    /// Returns `Ok(None)` if the node has no DOM backing or no box (e.g. hidden).
+    pub fn resolve_node_bounds(&self, node_id: &str) -> Result<Option<AxBounds>> {
+        let Some(backend_node_id) = self.lookup_backend_node_id(node_id)? else {
+            debug!("AX node {} has no backing DOM node", node_id);
+            return Ok(None);
+        };
+
+        self.backend_node_bounds(backend_node_id)
+    }
+
+    /// Bounds of `node`: its snapshot bounds if it has any, else `DOM.getBoxModel`
+    ///
+    /// `Accessibility.getFullAXTree` reports no geometry, so nodes from a live
+    /// snapshot only get bounds this way. `Ok(None)` for virtual or hidden nodes.
+    pub fn node_bounds(&self, node: &AxNode) -> Result<Option<AxBounds>> {
+        if let Some(bounds) = &node.bounds {
+            return Ok(Some(bounds.clone()));
+        }
+        match node.backend_node_id {
+            Some(backend_node_id) => self.backend_node_bounds(backend_node_id),
+            None => Ok(None),
+        }
+    }
+
+    /// Fill in `node_bounds` for the nodes of `tree` that `wanted` picks out
+    ///
+    /// One `DOM.getBoxModel` round trip per node, so callers narrow this to
+    /// the candidates they're about to look at.
+    pub fn resolve_bounds(&self, tree: &mut AxTree, wanted: impl Fn(&AxNode) -> bool) -> Result<()> {
+        for node in &mut tree.nodes {
+            if node.bounds.is_none() && wanted(node) {
+                node.bounds = self.node_bounds(node)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Content-box bounds of a DOM node in top-level viewport coordinates
+    pub fn backend_node_bounds(&self, backend_node_id: i64) -> Result<Option<AxBounds>> {
+        let box_model = match self.call_cdp(
+            "DOM.getBoxModel",
+            serde_json::json!({ "backendNodeId": backend_node_id }),
+        ) {
+            Ok(model) => model,
+            Err(e) => {
+                debug!("DOM.getBoxModel failed for backend node {}: {}", backend_node_id, e);
+                return Ok(None);
+            }
+        };
+
+        // content quad: [x1, y1, x2, y2, x3, y3, x4, y4]
+        let quad: Vec<f64> = box_model
+            .get("model")
+            .and_then(|m| m.get("content"))
+            .and_then(|c| c.as_array())
+            .map(|c| c.iter().filter_map(|v| v.as_f64()).collect())
+            .unwrap_or_default();
+
+        if quad.len() != 8 {
+            return Ok(None);
+        }
+
+        let xs = [quad[0], quad[2], quad[4], quad[6]];
+        let ys = [quad[1], quad[3], quad[5], quad[7]];
+        let min_x = xs.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max_x = xs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let min_y = ys.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max_y = ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        if max_x - min_x <= 0.0 || max_y - min_y <= 0.0 {
+            return Ok(None);
+        }
+
+        Ok(Some(AxBounds {
+            x: min_x,
+            y: min_y,
+            width: max_x - min_x,
+            height: max_y - min_y,
+        }))
+    }
+
+    /// Re-snapshot the tree and find the live equivalent of a (possibly stale) node
+    ///
+    /// Returns `Ok(None)` if the element no longer exists on the page.
+    pub fn refresh_node(&self, stale: &AxNode) -> Result<Option<AxNode>> {
+        let options = SnapshotOptions {
+            include_iframes: stale.frame_id.is_some(),
+            ..SnapshotOptions::default()
+        };
+        let fresh_tree = self.snapshot_accessibility_tree_with(&options)?;
+        let fresh = fresh_tree.find_equivalent(stale).cloned();
+
+        match &fresh {
+            Some(node) if node.node_id != stale.node_id => {
+                info!(
+                    "🔄 AX node {} was stale, re-resolved by role '{}' name {:?} -> {}",
+                    stale.node_id, stale.role, stale.name, node.node_id
+                );
+            }
+            None => {
+                warn!(
+                    "AX node {} (role '{}' name {:?}) no longer exists after refresh",
+                    stale.node_id, stale.role, stale.name
+                );
+            }
+            _ => {}
+        }
+
+        Ok(fresh)
+    }
+
+    /// Click an element by AX node id
+    ///
+    /// Unlike clicking raw coordinates, this re-resolves the node's bounds right
+    /// before moving the mouse, so a scroll between observation and action
+    /// doesn't turn into a misclick. Falls back to the snapshot bounds when the
+    /// live bounds can't be resolved.
+    ///
+    /// If the id came from an earlier snapshot and now points nowhere (or at a
+    /// different element), the tree is refreshed and the node re-resolved by
+    /// role + name.
+    pub async fn click_node(&self, node_id: &str) -> Result<()> {
+        let previous = self.last_tree.lock().unwrap()
+            .as_ref()
+            .and_then(|t| t.nodes.iter().find(|n| n.node_id == node_id).cloned());
+
+        let node_id = match previous {
+            Some(stale) => self
+                .refresh_node(&stale)?
+                .map(|n| n.node_id)
+                .ok_or_else(|| anyhow::anyhow!(
+                    "AX node {} (role '{}' name {:?}) no longer exists",
+                    stale.node_id, stale.role, stale.name
+                ))?,
+            None => node_id.to_string(),
+        };
+        let node_id = node_id.as_str();
+
+        let bounds = match self.resolve_node_bounds(node_id)? {
+            Some(bounds) => bounds,
+            None => {
+                warn!("Could not resolve live bounds for AX node {}, falling back to snapshot bounds", node_id);
+                let tree = self.snapshot_accessibility_tree()?;
+                tree.nodes
+                    .iter()
+                    .find(|n| n.node_id == node_id)
+                    .and_then(|n| n.bounds.clone())
+                    .ok_or_else(|| anyhow::anyhow!("AX node {} has no resolvable bounds", node_id))?
+            }
+        };
+
+        let center_x = bounds.x + bounds.width / 2.0;
+        let center_y = bounds.y + bounds.height / 2.0;
+
+        debug!("Clicking AX node {} at fresh center ({:.1}, {:.1})", node_id, center_x, center_y);
+
+        self.human_click(center_x, center_y, None, None, None).await
+    }
+
+    /// Give keyboard focus to an AX node's backing DOM node
+    ///
+    /// Keystrokes then go to that element no matter what sits on top of it,
+    /// which a coordinate click can't promise on pages with overlapping inputs.
+    /// Errors if the node has no DOM backing or isn't focusable.
+    pub fn focus_node(&self, node_id: &str) -> Result<()> {
+        let backend_node_id = self.lookup_backend_node_id(node_id)?
+            .ok_or_else(|| anyhow::anyhow!("AX node {} has no backing DOM node", node_id))?;
+
+        self.call_cdp("DOM.focus", serde_json::json!({ "backendNodeId": backend_node_id }))?;
+        debug!("Focused AX node {} (backend node {})", node_id, backend_node_id);
+        Ok(())
+    }
+
+    /// Scroll a DOM node into view, if it isn't already (`DOM.scrollIntoViewIfNeeded`)
+    ///
+    /// Takes an `AxNode::backend_node_id`. Chrome scrolls every scrollable
+    /// ancestor as needed, so nested scroll containers are handled too.
+    pub fn scroll_node_into_view(&self, backend_node_id: i64) -> Result<()> {
+        self.call_cdp(
+            "DOM.scrollIntoViewIfNeeded",
+            serde_json::json!({ "backendNodeId": backend_node_id }),
+        )?;
+        debug!("Scrolled backend node {} into view", backend_node_id);
+        Ok(())
+    }
+
+    /// Rendered text of a DOM node (`innerText`), e.g. a price or title found with `find_node`
+    ///
+    /// Takes an `AxNode::backend_node_id`. Text nodes and elements without
+    /// layout fall back to `textContent`; a node with no text at all gives an
+    /// empty string rather than an error.
+    pub fn get_node_text(&self, backend_node_id: i64) -> Result<String> {
+        let object_id = self.resolve_backend_node(backend_node_id)?;
+        let result = self.call_cdp(
+            "Runtime.callFunctionOn",
+            serde_json::json!({
+                "objectId": object_id,
+                "functionDeclaration": "function() { return (typeof this.innerText === 'string' ? this.innerText : this.textContent) || ''; }",
+                "returnByValue": true,
+            }),
+        )?;
+
+        let text = result
+            .get("result")
+            .and_then(|r| r.get("value"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        debug!("Backend node {} text: {} chars", backend_node_id, text.len());
+        Ok(text)
+    }
+
+    /// Outer HTML of a DOM node (`DOM.getOuterHTML`), for markup `get_node_text` flattens away
+    pub fn get_node_html(&self, backend_node_id: i64) -> Result<String> {
+        let result = self.call_cdp(
+            "DOM.getOuterHTML",
+            serde_json::json!({ "backendNodeId": backend_node_id }),
+        )?;
+
+        Ok(result
+            .get("outerHTML")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string())
+    }
+
+    /// Runtime `objectId` for a backend DOM node (`DOM.resolveNode`)
+    fn resolve_backend_node(&self, backend_node_id: i64) -> Result<String> {
+        let resolved = self.call_cdp(
+            "DOM.resolveNode",
+            serde_json::json!({ "backendNodeId": backend_node_id }),
+        )?;
+        resolved
+            .get("object")
+            .and_then(|o| o.get("objectId"))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("Could not resolve backend node {}", backend_node_id))
+    }
+
+    /// Viewport size in CSS pixels
+    fn viewport_size(&self) -> Result<(f64, f64)> {
+        let metrics = self.call_cdp("Page.getLayoutMetrics", serde_json::json!({}))?;
+        let viewport = metrics
+            .get("cssVisualViewport")
+            .or_else(|| metrics.get("visualViewport"))
+            .ok_or_else(|| anyhow::anyhow!("Page.getLayoutMetrics returned no viewport"))?;
+        let width = viewport.get("clientWidth").and_then(|v| v.as_f64()).unwrap_or(1920.0);
+        let height = viewport.get("clientHeight").and_then(|v| v.as_f64()).unwrap_or(1080.0);
+        Ok((width, height))
+    }
+
+    /// Scroll until the node matching role + name is on screen
+    ///
+    /// Each round re-snapshots the tree, reads the node's box from the DOM
+    /// (`node_bounds`), checks whether its center is inside the viewport, and
+    /// otherwise human-scrolls toward it (up or down)
+    /// by at most most of a screen. If a scroll didn't move the node, it lives
+    /// in a scrollable sub-container, so later gestures wheel over that
+    /// container instead of the page.
+    ///
+    /// Returns the node's final on-screen center, ready to click. The name is
+    /// matched loosely (case and whitespace insensitive, substring).
+    pub async fn scroll_into_view(&self, role: &str, name: Option<&str>) -> Result<(f64, f64)> {
+        let (viewport_width, viewport_height) = self.viewport_size()?;
+        let mut wheel_at = (viewport_width / 2.0, viewport_height / 2.0);
+        let mut previous_y: Option<f64> = None;
+
+        for attempt in 0..MAX_SCROLL_ATTEMPTS {
+            let mut tree = self.snapshot_accessibility_tree()?;
+            let node = tree
+                .nodes
+                .iter()
+                .find(|n| {
+                    n.role.eq_ignore_ascii_case(role)
+                        && name.map_or(true, |query| n.name.as_deref().is_some_and(|name| loose_match(name, query)))
+                })
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No '{}' node named {:?} in AX tree", role, name))?;
+            let bounds = self
+                .node_bounds(&node)?
+                .ok_or_else(|| anyhow::anyhow!("AX node {} has no box (hidden?)", node.node_id))?;
+
+            let center_x = bounds.x + bounds.width / 2.0;
+            let center_y = bounds.y + bounds.height / 2.0;
+            if (0.0..viewport_width).contains(&center_x) && (0.0..viewport_height).contains(&center_y) {
+                debug!("AX node {} in view at ({:.1}, {:.1}) after {} scrolls", node.node_id, center_x, center_y, attempt);
+                return Ok((center_x, center_y));
+            }
+
+            // The page scrolled but the node didn't move: wheel over its container instead
+            if previous_y.is_some_and(|y| (y - center_y).abs() < 1.0) {
+                // Only the ancestors' boxes matter for finding the container
+                let mut ancestors: Vec<String> = Vec::new();
+                let mut parent_id = node.parent_id.clone();
+                while let Some(id) = parent_id.filter(|id| !ancestors.contains(id)) {
+                    parent_id = tree.nodes.iter().find(|n| n.node_id == id).and_then(|n| n.parent_id.clone());
+                    ancestors.push(id);
+                }
+                self.resolve_bounds(&mut tree, |n| ancestors.contains(&n.node_id))?;
+                match scroll_container_point(&tree, &node, viewport_width, viewport_height) {
+                    Some(point) => {
+                        debug!("AX node {} is inside a scroll container, wheeling at {:?}", node.node_id, point);
+                        wheel_at = point;
+                    }
+                    None => warn!("AX node {} didn't move after scrolling and has no visible container", node.node_id),
+                }
+            }
+            previous_y = Some(center_y);
+
+            // Aim for the middle of the screen, a screenful at most per gesture
+            let delta = (center_y - viewport_height / 2.0).clamp(-viewport_height * 0.8, viewport_height * 0.8);
+            self.human_scroll(0.0, delta, Some(wheel_at.0), Some(wheel_at.1)).await?;
+
+            // Let smooth scrolling and lazy content settle before re-reading
+            sleep(Duration::from_millis(250)).await;
+        }
+
+        anyhow::bail!(
+            "'{}' node {:?} still off-screen after {} scrolls",
+            role, name, MAX_SCROLL_ATTEMPTS
+        )
+    }
+
+    /// Select an option in a dropdown by accessible names
+    ///
+    /// Handles both flavours of dropdown:
+    /// - Native `<select>`: focused with a human click, then the option is chosen
+    ///   through the DOM (the native popup isn't reliably clickable under CDP) and
+    ///   `input`/`change` events are dispatched so frameworks notice.
+    /// - ARIA comboboxes/listboxes: opened with a human click, then we wait for the
+    ///   option nodes to appear and human-click the matching one.
+    ///
+    /// Both names are matched loosely (case and whitespace insensitive), an
+    /// exact name before any that merely contains it.
+    pub async fn select_option(&self, combobox_name: &str, option: &str) -> Result<()> {
+        let tree = self.snapshot_accessibility_tree()?;
+        let comboboxes = tree
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.role.as_str(), "combobox" | "listbox" | "PopUpButton"));
+        let combobox = best_name_match(comboboxes, combobox_name)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Combobox '{}' not found in AX tree", combobox_name))?;
+
+        info!("Selecting '{}' in combobox '{}'", option, combobox_name);
+
+        // Open / focus it like a human would
+        self.click_node(&combobox.node_id).await?;
+
+        if let Some(backend_node_id) = self.lookup_backend_node_id(&combobox.node_id)? {
+            if self.is_native_select(backend_node_id)? {
+                return self.select_native_option(backend_node_id, option);
+            }
+        }
+
+        // ARIA listbox: wait for the options to render
+        for attempt in 0..10 {
+            let tree = self.snapshot_accessibility_tree()?;
+            let target = best_name_match(
+                tree.nodes.iter().filter(|n| {
+                    matches!(n.role.as_str(), "option" | "menuitem" | "menuitemradio" | "MenuListOption")
+                }),
+                option,
+            );
+
+            if let Some(target) = target {
+                debug!("Option '{}' visible after {} polls", option, attempt + 1);
+                return self.click_node(&target.node_id).await;
             }
+
+            sleep(Duration::from_millis(150)).await;
         }
-        
+
+        anyhow::bail!("Option '{}' did not appear in combobox '{}'", option, combobox_name)
+    }
+
+    /// Whether a backend DOM node is a native `<select>` element
+    fn is_native_select(&self, backend_node_id: i64) -> Result<bool> {
+        let described = self.call_cdp(
+            "DOM.describeNode",
+            serde_json::json!({ "backendNodeId": backend_node_id }),
+        )?;
+
+        Ok(described
+            .get("node")
+            .and_then(|n| n.get("nodeName"))
+            .and_then(|v| v.as_str())
+            .map_or(false, |name| name.eq_ignore_ascii_case("select")))
+    }
+
+    /// Choose an option on a native `<select>` and fire the events a user would
+    fn select_native_option(&self, backend_node_id: i64, option: &str) -> Result<()> {
+        let object_id = self.resolve_backend_node(backend_node_id)
+            .context("Could not resolve <select> element")?;
+
+        let function = r#"
+            function(query) {
+                const norm = s => (s || '').replace(/\s+/g, ' ').trim().toLowerCase();
+                const q = norm(query);
+                const options = Array.from(this.options);
+                const match = options.find(o => norm(o.text) === q)
+                    || options.find(o => norm(o.text).includes(q));
+                if (!match) return false;
+                this.value = match.value;
+                this.dispatchEvent(new Event('input', { bubbles: true }));
+                this.dispatchEvent(new Event('change', { bubbles: true }));
+                return true;
+            }
+        "#;
+
+        let result = self.call_cdp(
+            "Runtime.callFunctionOn",
+            serde_json::json!({
+                "objectId": object_id,
+                "functionDeclaration": function,
+                "arguments": [{ "value": option }],
+                "returnByValue": true,
+            }),
+        )?;
+
+        let selected = result
+            .get("result")
+            .and_then(|r| r.get("value"))
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !selected {
+            anyhow::bail!("Option '{}' not found in native <select>", option);
+        }
+
+        debug!("Selected native option '{}'", option);
         Ok(())
     }
-    
+
     /// Verify Engine Health - Native Engine Verification
     /// 
     /// Modern anti-bot suites in 2026 use Function Integrity Checks.
@@ -385,19 +1617,16 @@ impl Cortex {
     /// on a target site (e.g., stay logged in) without re-authenticating, proving
     /// that the Identity Grafting is seamless.
     fn verify_redis_session(&self) -> Result<()> {
-        // Check if Redis is configured via environment variable
-        let redis_url = std::env::var("REDIS_URL")
-            .or_else(|_| std::env::var("CHIMERA_REDIS_URL"))
-            .ok();
-        
-        if redis_url.is_none() {
-            debug!("Redis not configured (REDIS_URL or CHIMERA_REDIS_URL not set)");
-            return Ok(()); // Not an error, just not using Redis
-        }
+        // Redis URL comes from ChimeraConfig (REDIS_URL / CHIMERA_REDIS_URL)
+        let redis_url = match &self.redis_url {
+            Some(url) => url.clone(),
+            None => {
+                debug!("Redis not configured (REDIS_URL or CHIMERA_REDIS_URL not set)");
+                return Ok(()); // Not an error, just not using Redis
+            }
+        };
         
         // Try to connect to Redis and verify profile store is accessible
-        let redis_url = redis_url.unwrap();
-        
         // Use tokio runtime handle if available, otherwise create new runtime
         let rt = tokio::runtime::Handle::try_current();
         
@@ -409,16 +1638,13 @@ impl Cortex {
                 let client = redis::Client::open(&redis_url)
                     .context("Failed to create Redis client")?;
                 
-                let mut conn = client.get_async_connection().await
-                    .context("Failed to connect to Redis")?;
+                let mut conn = redis_timeout("connect", client.get_async_connection()).await?;
                 
                 // Check if Redis is accessible
-                let _: String = conn.ping().await
-                    .context("Redis ping failed")?;
+                let _: String = redis_timeout("PING", conn.ping()).await?;
                 
                 // Check if profile keys exist
-                let keys: Vec<String> = conn.keys("profile:*").await
-                    .context("Failed to check profile keys")?;
+                let keys: Vec<String> = redis_timeout("KEYS", conn.keys("profile:*")).await?;
                 
                 let profile_count = keys.len();
                 
@@ -441,16 +1667,13 @@ impl Cortex {
                 let client = redis::Client::open(&redis_url)
                     .context("Failed to create Redis client")?;
                 
-                let mut conn = client.get_async_connection().await
-                    .context("Failed to connect to Redis")?;
+                let mut conn = redis_timeout("connect", client.get_async_connection()).await?;
                 
                 // Check if Redis is accessible
-                let _: String = conn.ping().await
-                    .context("Redis ping failed")?;
+                let _: String = redis_timeout("PING", conn.ping()).await?;
                 
                 // Check if profile keys exist
-                let keys: Vec<String> = conn.keys("profile:*").await
-                    .context("Failed to check profile keys")?;
+                let keys: Vec<String> = redis_timeout("KEYS", conn.keys("profile:*")).await?;
                 
                 let profile_count = keys.len();
                 
@@ -478,47 +1701,42 @@ impl Cortex {
     /// Uses fast AX tree scan to identify regions containing specific content
     /// (e.g., "product table", "search results", "form fields")
     /// 
-    /// Returns bounding box (x, y, width, height) if found
+    /// Returns one bounding box (x, y, width, height) around every match; see
+    /// `find_rois` to keep disjoint regions apart.
     pub fn find_roi(&self, role_pattern: &str, name_pattern: Option<&str>) -> Result<Option<(f64, f64, f64, f64)>> {
-        let ax_tree = self.snapshot_accessibility_tree()?;
-        
-        // Find all matching nodes
-        let mut matching_nodes = Vec::new();
-        
-        for node in &ax_tree.nodes {
-            if node.role.contains(role_pattern) {
-                if let Some(target_name) = name_pattern {
-                    if let Some(node_name) = &node.name {
-                        if !node_name.to_lowercase().contains(&target_name.to_lowercase()) {
-                            continue;
-                        }
-                    } else {
-                        continue;
-                    }
-                }
-                
-                if let Some(bounds) = &node.bounds {
-                    matching_nodes.push(bounds);
+        let rois = self.find_rois(role_pattern, name_pattern)?;
+        Ok(rois.into_iter().reduce(|a, b| {
+            let (min_x, min_y) = (a.0.min(b.0), a.1.min(b.1));
+            let (max_x, max_y) = ((a.0 + a.2).max(b.0 + b.2), (a.1 + a.3).max(b.1 + b.3));
+            (min_x, min_y, max_x - min_x, max_y - min_y)
+        }))
+    }
+    
+    /// Find every coherent Region of Interest matching the pattern
+    /// 
+    /// Matching nodes closer than `ROI_CLUSTER_GAP` are grouped into one box,
+    /// so results in a main column and a left sidebar come back as two ROIs
+    /// instead of one box spanning the viewport. Sorted smallest area first.
+    /// Boxes come from the DOM (`resolve_bounds`); hidden matches have none.
+    pub fn find_rois(&self, role_pattern: &str, name_pattern: Option<&str>) -> Result<Vec<(f64, f64, f64, f64)>> {
+        let mut ax_tree = self.snapshot_accessibility_tree()?;
+        let name_pattern = name_pattern.map(|n| n.to_lowercase());
+        let matches = |node: &AxNode| {
+            node.role.contains(role_pattern)
+                && match &name_pattern {
+                    Some(target) => node.name.as_ref().map_or(false, |n| n.to_lowercase().contains(target)),
+                    None => true,
                 }
-            }
-        }
-        
-        if matching_nodes.is_empty() {
-            return Ok(None);
-        }
+        };
+        self.resolve_bounds(&mut ax_tree, &matches)?;
         
-        // Calculate bounding box that contains all matching nodes
-        let min_x = matching_nodes.iter().map(|b| b.x).fold(f64::INFINITY, f64::min);
-        let min_y = matching_nodes.iter().map(|b| b.y).fold(f64::INFINITY, f64::min);
-        let max_x = matching_nodes.iter().map(|b| b.x + b.width).fold(0.0, f64::max);
-        let max_y = matching_nodes.iter().map(|b| b.y + b.height).fold(0.0, f64::max);
+        let matching: Vec<&AxBounds> = ax_tree.nodes
+            .iter()
+            .filter(|node| matches(node))
+            .filter_map(|node| node.bounds.as_ref())
+            .collect();
         
-        Ok(Some((
-            min_x,
-            min_y,
-            max_x - min_x,
-            max_y - min_y,
-        )))
+        Ok(cluster_regions(&matching, ROI_CLUSTER_GAP))
     }
     
     /// Behavioral Engine - Human Jitter (Mouse & Scroll Entropy)
@@ -535,6 +1753,7 @@ impl Cortex {
     /// Uses Gaussian Micro-Movements to ensure no two movements between
     /// Point A and Point B are ever identical. Implements 2-3 pixel
     /// "overshoot and correction" pattern typical of human motor control.
+    /// Tremor and pace follow the Cortex's motor profile (`with_motor_profile`).
     /// 
     /// Args:
     ///   - target_x, target_y: Target coordinates
@@ -548,12 +1767,12 @@ impl Cortex {
         current_y: Option<f64>,
         precision: Option<f64>, // 0.0 = low precision (more human), 1.0 = high precision
     ) -> Result<()> {
-        let mut rng = rand::thread_rng();
+        let mut rng = self.motion_rng()?;
         
-        // Get current position or default to center
+        // Get current position or the last tracked one
         let (start_x, start_y) = match (current_x, current_y) {
             (Some(x), Some(y)) => (x, y),
-            _ => (960.0, 540.0), // Default to center
+            _ => self.mouse_position(),
         };
         
         // Apply precision-based targeting offset
@@ -575,15 +1794,20 @@ impl Cortex {
         );
         
         // Generate WindMouse trajectory (simulates gravity, wind, muscle tremors)
-        let trajectory = self.generate_windmouse_trajectory(
+        let trajectory = Self::generate_windmouse_trajectory(
+            &mut rng,
             start_x, start_y,
             adjusted_target_x, adjusted_target_y,
         )?;
         
-        // Execute trajectory with Gaussian micro-movements
+        // Execute trajectory with Gaussian micro-movements at the user's own pace
+        let tremor_dist = Normal::new(0.0, self.motor.tremor_std.abs())
+            .context("Invalid motor profile tremor")?;
+        let pace = self.motor.speed_scale.max(0.1);
+        let mut recorded = crate::trajectory::enabled().then(Vec::new);
         for (x, y, delay) in trajectory {
             // Add Gaussian tremor to each point (muscle jitter)
-            let tremor_dist = Normal::new(0.0, 0.5).unwrap(); // 0.5px standard deviation
+            let delay = delay.div_f64(pace);
             let tremor_x = tremor_dist.sample(&mut rng);
             let tremor_y = tremor_dist.sample(&mut rng);
             
@@ -592,11 +1816,19 @@ impl Cortex {
             
             self.tab.move_mouse(final_x, final_y)
                 .context("Failed to move mouse in trajectory")?;
+            *self.mouse.lock().unwrap() = (final_x, final_y);
+            if let Some(steps) = recorded.as_mut() {
+                steps.push((final_x, final_y, delay));
+            }
             
             if !delay.is_zero() {
                 sleep(delay).await;
             }
         }
+        if let Some(steps) = recorded {
+            let target = (adjusted_target_x, adjusted_target_y);
+            crate::trajectory::record(&Trajectory::from_steps("windmouse", target, steps));
+        }
         
         // Hick's Law Latency: Variable "think time" before clicking
         // Mimics human cognitive load required to process a page before clicking
@@ -625,6 +1857,8 @@ impl Cortex {
     /// - Variable scroll speed (acceleration/deceleration)
     /// - Gaussian jitter in scroll distance
     /// - Natural pauses during scrolling
+    /// 
+    /// Positive deltas scroll down/right, negative deltas up/left.
     pub async fn human_scroll(
         &self,
         delta_x: f64,
@@ -632,28 +1866,30 @@ impl Cortex {
         current_x: Option<f64>,
         current_y: Option<f64>,
     ) -> Result<()> {
-        let mut rng = rand::thread_rng();
+        let mut rng = self.motion_rng()?;
         
         // Get current position
         let (scroll_x, scroll_y) = match (current_x, current_y) {
             (Some(x), Some(y)) => (x, y),
-            _ => (960.0, 540.0),
+            _ => self.mouse_position(),
         };
         
         // Add Gaussian jitter to scroll distance (human inaccuracy)
         let jitter_dist = Normal::new(0.0, delta_y.abs() * 0.1).unwrap();
         let jitter = jitter_dist.sample(&mut rng);
         let adjusted_delta_y = delta_y + jitter;
+        let adjusted_delta_x = delta_x + Normal::new(0.0, delta_x.abs() * 0.1).unwrap().sample(&mut rng);
         
         debug!(
-            "Human scroll: delta_y={:.1} -> {:.1} (jitter: {:.2})",
-            delta_y, adjusted_delta_y, jitter
+            "Human scroll: delta=({:.1}, {:.1}) -> ({:.1}, {:.1})",
+            delta_x, delta_y, adjusted_delta_x, adjusted_delta_y
         );
         
         // Break scroll into multiple steps with variable speed
         // Humans don't scroll in one smooth motion
         let steps = rng.gen_range(3..8);
         let step_size = adjusted_delta_y / steps as f64;
+        let step_size_x = adjusted_delta_x / steps as f64;
         
         for i in 0..steps {
             // Variable scroll speed (faster at start, slower at end)
@@ -666,12 +1902,15 @@ impl Cortex {
             
             let step_delta = step_size * speed_factor;
             
-            // Add micro-jitter to each step
-            let micro_jitter = rng.gen_range(-2.0..2.0);
+            // Add micro-jitter to each step (only along axes that are actually scrolling)
+            let micro_jitter = if delta_y != 0.0 { rng.gen_range(-2.0..2.0) } else { 0.0 };
             let final_delta = step_delta + micro_jitter;
+            let final_delta_x = step_size_x * speed_factor;
             
-            self.tab.scroll(scroll_x, scroll_y, 0.0, final_delta)
+            self.tab.scroll(scroll_x, scroll_y, final_delta_x, final_delta)
                 .context("Failed to scroll")?;
+            // Wheel events are dispatched at the pointer
+            *self.mouse.lock().unwrap() = (scroll_x, scroll_y);
             
             // Variable delay between scroll steps (humans pause)
             let delay = rng.gen_range(20..80);
@@ -682,6 +1921,56 @@ impl Cortex {
         Ok(())
     }
     
+    /// Scan the page like a first-time visitor for about `duration`
+    /// 
+    /// Alternates reading pauses with `human_scroll` gestures: each pause is
+    /// proportional to the words currently on screen, each scroll moves part of
+    /// a screen down, and now and then one goes back up to re-read. Pauses and
+    /// scroll distances follow the motor profile's `speed_scale`, so the same
+    /// identity reads at the same pace it moves the mouse.
+    /// 
+    /// Interacting the instant a page loads is a behavioral-biometrics tell.
+    pub async fn simulate_reading(&self, duration: Duration) -> Result<()> {
+        let deadline = tokio::time::Instant::now() + duration;
+        let (_, viewport_height) = self.viewport_size()?;
+        let mut rng = self.motion_rng()?;
+        let mut scrolled = 0.0;
+        
+        loop {
+            let words = self.visible_word_count()?;
+            let pause = reading_pause(words, &self.motor).mul_f64(rng.gen_range(0.7..1.3));
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            debug!("📖 Reading {} visible words for {:?}", words, pause.min(remaining));
+            sleep(pause.min(remaining)).await;
+            if tokio::time::Instant::now() >= deadline {
+                break;
+            }
+            
+            // Mostly onward, sometimes back over what was just skimmed
+            let distance = viewport_height * rng.gen_range(0.25..0.6) * self.motor.speed_scale;
+            let delta = if scrolled > 0.0 && rng.gen_bool(SCROLL_BACK_PROB) {
+                -(distance * 0.5).min(scrolled)
+            } else {
+                distance
+            };
+            let wheel_at = self.mouse_position();
+            self.human_scroll(0.0, delta, Some(wheel_at.0), Some(wheel_at.1)).await?;
+            scrolled += delta;
+        }
+        
+        Ok(())
+    }
+    
+    /// Words of text currently rendered in the viewport (`VISIBLE_WORD_COUNT_JS`)
+    /// 
+    /// Read from the DOM: AX snapshots carry no geometry to tell what's on screen.
+    fn visible_word_count(&self) -> Result<usize> {
+        let result = self.tab
+            .evaluate(VISIBLE_WORD_COUNT_JS, false)
+            .context("Failed to count visible words")?;
+        Ok(result.value.and_then(|v| v.as_u64()).unwrap_or(0) as usize)
+    }
+    
     /// Generate WindMouse trajectory
     /// 
     /// WindMouse algorithm simulates:
@@ -690,15 +1979,15 @@ impl Cortex {
     /// - Muscle tremors (Gaussian jitter)
     /// 
     /// This ensures no two movements are ever identical, avoiding
-    /// the "sharp peaks" characteristic of bots.
-    fn generate_windmouse_trajectory(
-        &self,
+    /// the "sharp peaks" characteristic of bots - unless the same seeded
+    /// RNG is passed in, in which case the path is reproduced exactly.
+    fn generate_windmouse_trajectory<R: Rng>(
+        rng: &mut R,
         start_x: f64,
         start_y: f64,
         end_x: f64,
         end_y: f64,
     ) -> Result<Vec<(f64, f64, Duration)>> {
-        let mut rng = rand::thread_rng();
         let distance = ((end_x - start_x).powi(2) + (end_y - start_y).powi(2)).sqrt();
         
         // Calculate number of steps based on distance
@@ -771,8 +2060,8 @@ impl Cortex {
             
             // Add Gaussian tremor (muscle jitter)
             let tremor_dist = Normal::new(0.0, 0.3).unwrap();
-            let tremor_x = tremor_dist.sample(&mut rng);
-            let tremor_y = tremor_dist.sample(&mut rng);
+            let tremor_x = tremor_dist.sample(rng);
+            let tremor_y = tremor_dist.sample(rng);
             
             current_x += tremor_x;
             current_y += tremor_y;
@@ -825,7 +2114,7 @@ impl Cortex {
         let base_time = a + b * (n + 1.0).log2();
         
         // Add randomness (humans are not perfectly consistent)
-        let mut rng = rand::thread_rng();
+        let mut rng = self.motion_rng()?;
         let variance = rng.gen_range(0.7..1.3); // 30% variance
         let total_time = (base_time * variance) as u64;
         
@@ -842,6 +2131,245 @@ impl Cortex {
     }
 }
 
+/// Accessible-name fragments of image-challenge grids (vendor-independent)
+const CAPTCHA_GRID_PHRASES: &[&str] = &[
+    "select all images",
+    "select all squares",
+    "click each image",
+    "click verify once there are none left",
+];
+
+/// Accessible-name fragments of a vendor widget that is actually challenging the user
+const CAPTCHA_CHALLENGE_PHRASES: &[&str] = &["challenge", "not a robot", "i am human"];
+
+/// Accessible-name fragments of the reCAPTCHA badge, shown on ordinary pages
+const CAPTCHA_BADGE_PHRASES: &[&str] = &["protected by recaptcha", "privacy - terms"];
+
+/// Group boxes into regions: boxes within `gap` of each other (edge to edge)
+/// share a region, transitively. Returns (x, y, width, height), smallest area first.
+fn cluster_regions(boxes: &[&AxBounds], gap: f64) -> Vec<(f64, f64, f64, f64)> {
+    let mut regions: Vec<(f64, f64, f64, f64)> = Vec::new(); // (min_x, min_y, max_x, max_y)
+    
+    for b in boxes {
+        let mut region = (b.x, b.y, b.x + b.width, b.y + b.height);
+        // Absorb every region this one touches; repeat since growing can bridge more
+        loop {
+            let before = regions.len();
+            regions.retain(|r| {
+                let near = r.0 - gap <= region.2 && region.0 - gap <= r.2
+                    && r.1 - gap <= region.3 && region.1 - gap <= r.3;
+                if near {
+                    region = (region.0.min(r.0), region.1.min(r.1), region.2.max(r.2), region.3.max(r.3));
+                }
+                !near
+            });
+            if regions.len() == before {
+                break;
+            }
+        }
+        regions.push(region);
+    }
+    
+    let mut regions: Vec<_> = regions
+        .into_iter()
+        .map(|(x0, y0, x1, y1)| (x0, y0, x1 - x0, y1 - y0))
+        .collect();
+    regions.sort_by(|a, b| (a.2 * a.3).total_cmp(&(b.2 * b.3)));
+    regions
+}
+
+/// How long `profile`'s user spends skimming `words` words, within `READING_PAUSE_MS`
+fn reading_pause(words: usize, profile: &MotorProfile) -> Duration {
+    let words_per_ms = READING_WPM * profile.speed_scale.max(0.1) / 60_000.0;
+    let ms = (words as f64 * SKIM_FRACTION / words_per_ms) as u64;
+    Duration::from_millis(ms.clamp(READING_PAUSE_MS.0, READING_PAUSE_MS.1))
+}
+
+/// Find a captcha challenge in an AX tree
+/// 
+/// A vendor name only counts in a challenge title ("hCaptcha security
+/// challenge", "recaptcha challenge expires in two minutes"): a bare
+/// "reCAPTCHA" iframe is just as likely the invisible v3 widget, and the
+/// "protected by reCAPTCHA" badge text is ignored outright. A generic
+/// "captcha" / "verify you are human" label or an image grid without a
+/// recognizable vendor is `Unknown`.
+pub fn detect_captcha_in_tree(tree: &AxTree) -> Option<CaptchaKind> {
+    let mut generic = false;
+    for node in &tree.nodes {
+        let name = node.name.as_deref().unwrap_or("").to_ascii_lowercase();
+        if CAPTCHA_BADGE_PHRASES.iter().any(|phrase| name.contains(phrase)) {
+            continue;
+        }
+        
+        let vendor = CaptchaKind::from_signature(&name);
+        if vendor.is_some() && CAPTCHA_CHALLENGE_PHRASES.iter().any(|phrase| name.contains(phrase)) {
+            return vendor;
+        }
+        
+        generic |= (vendor.is_none() && name.contains("captcha"))
+            || name.contains("verify you are human")
+            || CAPTCHA_GRID_PHRASES.iter().any(|phrase| name.contains(phrase));
+    }
+    generic.then_some(CaptchaKind::Unknown)
+}
+
+/// Collect the URL of every frame below the main frame (depth-first)
+fn collect_frame_urls(frame_tree: &serde_json::Value, out: &mut Vec<String>) {
+    if let Some(children) = frame_tree.get("childFrames").and_then(|v| v.as_array()) {
+        for child in children {
+            if let Some(url) = child.get("frame").and_then(|f| f.get("url")).and_then(|v| v.as_str()) {
+                out.push(url.to_string());
+            }
+            collect_frame_urls(child, out);
+        }
+    }
+}
+
+/// Collect the ids of every frame below the main frame (depth-first)
+fn collect_child_frames(frame_tree: &serde_json::Value, out: &mut Vec<String>) {
+    if let Some(children) = frame_tree.get("childFrames").and_then(|v| v.as_array()) {
+        for child in children {
+            if let Some(id) = child.get("frame").and_then(|f| f.get("id")).and_then(|v| v.as_str()) {
+                out.push(id.to_string());
+            }
+            collect_child_frames(child, out);
+        }
+    }
+}
+
+/// Collect (nearest element ancestor, element) backend ids for everything inside shadow roots
+///
+/// `inside_shadow` is set once the walk descends through a `shadowRoots` entry;
+/// `nearest_element` tracks the closest element ancestor (the host, at the boundary).
+fn collect_shadow_elements(
+    node: &serde_json::Value,
+    nearest_element: Option<i64>,
+    inside_shadow: bool,
+    out: &mut Vec<(Option<i64>, i64)>,
+) {
+    const ELEMENT_NODE: i64 = 1;
+    
+    let backend_id = node.get("backendNodeId").and_then(|v| v.as_i64());
+    let is_element = node.get("nodeType").and_then(|v| v.as_i64()) == Some(ELEMENT_NODE);
+    
+    if inside_shadow && is_element {
+        if let Some(id) = backend_id {
+            out.push((nearest_element, id));
+        }
+    }
+    
+    let next_parent = if is_element { backend_id.or(nearest_element) } else { nearest_element };
+    
+    if let Some(shadow_roots) = node.get("shadowRoots").and_then(|v| v.as_array()) {
+        for shadow_root in shadow_roots {
+            collect_shadow_elements(shadow_root, next_parent, true, out);
+        }
+    }
+    if let Some(children) = node.get("children").and_then(|v| v.as_array()) {
+        for child in children {
+            collect_shadow_elements(child, next_parent, inside_shadow, out);
+        }
+    }
+}
+
+/// Where to wheel to scroll `node`'s nearest visible container
+///
+/// Walks up the ancestors for the first one that is partly on screen but
+/// smaller than the viewport (a scrollable panel, not the page), and returns
+/// the center of its visible part.
+fn scroll_container_point(tree: &AxTree, node: &AxNode, viewport_width: f64, viewport_height: f64) -> Option<(f64, f64)> {
+    let by_id: HashMap<&str, &AxNode> = tree.nodes.iter().map(|n| (n.node_id.as_str(), n)).collect();
+    let mut parent_id = node.parent_id.as_deref();
+
+    while let Some(parent) = parent_id.and_then(|id| by_id.get(id)) {
+        if let Some(b) = &parent.bounds {
+            let left = b.x.max(0.0);
+            let top = b.y.max(0.0);
+            let right = (b.x + b.width).min(viewport_width);
+            let bottom = (b.y + b.height).min(viewport_height);
+            let on_screen = right > left && bottom > top;
+            let smaller_than_page = b.height < viewport_height || b.width < viewport_width;
+            if on_screen && smaller_than_page {
+                return Some(((left + right) / 2.0, (top + bottom) / 2.0));
+            }
+        }
+        parent_id = parent.parent_id.as_deref();
+    }
+    None
+}
+
+/// Collapse whitespace runs and lowercase, so "  Sign In\n" == "sign in"
+fn normalize_name(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// Loose accessible-name match: case/whitespace-insensitive equality or substring
+fn loose_match(candidate: &str, query: &str) -> bool {
+    let candidate = normalize_name(candidate);
+    let query = normalize_name(query);
+    !query.is_empty() && (candidate == query || candidate.contains(&query))
+}
+
+/// The node named exactly `query` (normalized), else the first loosely matching one
+/// 
+/// "Canada" must pick "Canada", not an earlier "Canada (French)".
+fn best_name_match<'a>(nodes: impl Iterator<Item = &'a AxNode>, query: &str) -> Option<&'a AxNode> {
+    let named: Vec<(&AxNode, &str)> = nodes.filter_map(|n| Some((n, n.name.as_deref()?))).collect();
+    let exact = normalize_name(query);
+    named
+        .iter()
+        .find(|(_, name)| normalize_name(name) == exact)
+        .or_else(|| named.iter().find(|(_, name)| loose_match(name, query)))
+        .map(|(node, _)| *node)
+}
+
+/// Score how well an accessible name matches a query, from 0.0 to 1.0
+/// 
+/// Normalized equality scores 1.0. A name containing the query ("Sign in to
+/// continue" for "sign in") scores 0.8-1.0 depending on how much of the name
+/// the query covers; a query containing the name scores at most 0.7. Anything
+/// else falls back to Levenshtein similarity, which absorbs typos and
+/// decorations like "Submit »".
+pub fn name_match_score(candidate: &str, query: &str) -> f64 {
+    let candidate = normalize_name(candidate);
+    let query = normalize_name(query);
+    if candidate.is_empty() || query.is_empty() {
+        return 0.0;
+    }
+    if candidate == query {
+        return 1.0;
+    }
+    
+    let (c_len, q_len) = (candidate.chars().count() as f64, query.chars().count() as f64);
+    let similarity = 1.0 - levenshtein(&candidate, &query) as f64 / c_len.max(q_len);
+    let containment = if candidate.contains(&query) {
+        0.8 + 0.2 * (q_len / c_len)
+    } else if query.contains(&candidate) {
+        0.7 * (c_len / q_len)
+    } else {
+        0.0
+    };
+    // Strictly below an exact match, whichever route scores higher
+    containment.max(similarity).min(0.99)
+}
+
+/// Edit distance between two strings (in chars)
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
 /// Dual-Sense State - Combines visual and semantic information
 pub struct FusionState {
     /// Screenshot (visual context)
@@ -863,9 +2391,10 @@ impl FusionState {
         let screenshot = session.capture_screenshot()?;
         
         // Get accessibility tree (semantic) - THE TRUTH
+        // Includes iframes so every region is in top-level screenshot coordinates
         let tab = session.get_tab()?;
         let cortex = Cortex::new(tab);
-        let ax_tree = cortex.snapshot_accessibility_tree()?;
+        let ax_tree = cortex.snapshot_accessibility_tree_with(&Self::snapshot_options())?;
         
         // Build mapping of nodes to screen regions
         let mut node_to_region = HashMap::new();
@@ -880,6 +2409,14 @@ impl FusionState {
         })
     }
     
+    /// Fusion needs every node in top-level coordinates, including iframe content
+    fn snapshot_options() -> SnapshotOptions {
+        SnapshotOptions {
+            include_iframes: true,
+            ..SnapshotOptions::default()
+        }
+    }
+    
     /// Build a map of node IDs to screen regions
     fn build_node_map(tree: &AxTree, map: &mut HashMap<String, AxBounds>) {
         for node in &tree.nodes {
@@ -906,21 +2443,88 @@ impl FusionState {
         None
     }
     
+    /// Find the node of `role` whose name best matches `name_query`
+    /// 
+    /// Role comparison ignores case; names are scored with `name_match_score`.
+    /// Returns the best-scoring node at or above `threshold`, with its score,
+    /// so callers can decide whether e.g. a 0.6 match is good enough.
+    pub fn find_node_fuzzy(&self, role: &str, name_query: &str, threshold: f64) -> Option<(&AxNode, f64)> {
+        self.best_fuzzy_match(role, name_query, threshold, false)
+    }
+    
+    fn best_fuzzy_match(&self, role: &str, name_query: &str, threshold: f64, need_bounds: bool) -> Option<(&AxNode, f64)> {
+        self.ax_tree
+            .nodes
+            .iter()
+            .filter(|node| node.role.eq_ignore_ascii_case(role))
+            .filter(|node| !need_bounds || node.bounds.is_some())
+            .filter_map(|node| {
+                let score = name_match_score(node.name.as_deref()?, name_query);
+                (score >= threshold).then_some((node, score))
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+    
+    /// Get screen coordinates for a node id held from this fusion state
+    /// 
+    /// Validates the id against the live page first. If the id is unknown to the
+    /// live tree (or now denotes a different element), the tree is re-snapshotted,
+    /// the node is re-resolved by role + name, and this fusion state is refreshed.
+    pub fn get_node_coordinates(
+        &mut self,
+        session: &BrowserSession,
+        node_id: &str,
+    ) -> Result<Option<(f64, f64)>> {
+        let Some(stale) = self.ax_tree.nodes.iter().find(|n| n.node_id == node_id).cloned() else {
+            return Ok(None);
+        };
+        
+        let cortex = Cortex::new(session.get_tab()?);
+        let fresh_tree = cortex.snapshot_accessibility_tree_with(&Self::snapshot_options())?;
+        
+        let fresh = fresh_tree.find_equivalent(&stale).cloned();
+        if fresh.as_ref().map(|n| n.node_id.as_str()) != Some(node_id) {
+            info!(
+                "🔄 Stale AX node {} (role '{}' name {:?}) - refreshed fusion state",
+                node_id, stale.role, stale.name
+            );
+            let mut node_to_region = HashMap::new();
+            Self::build_node_map(&fresh_tree, &mut node_to_region);
+            self.ax_tree = fresh_tree;
+            self.node_to_region = node_to_region;
+        }
+        
+        Ok(fresh.and_then(|node| {
+            node.bounds.map(|b| (b.x + b.width / 2.0, b.y + b.height / 2.0))
+        }))
+    }
+    
     /// Get screen coordinates for a semantic element
     /// 
     /// This is the "God Mode" - we know WHAT it is (from AX tree)
     /// and WHERE it is (from bounds), without parsing HTML/CSS
-    pub fn get_coordinates(&self, role: &str, name: Option<&str>) -> Option<(f64, f64)> {
-        if let Some(node) = self.find_node(role, name) {
-            if let Some(bounds) = &node.bounds {
-                // Return center of element
-                return Some((
-                    bounds.x + bounds.width / 2.0,
-                    bounds.y + bounds.height / 2.0,
-                ));
-            }
+    /// 
+    /// Exact role + name match only, unless `fuzzy_threshold` is given: then
+    /// the best fuzzy match scoring at least that is the fallback.
+    pub fn get_coordinates(&self, role: &str, name: Option<&str>, fuzzy_threshold: Option<f64>) -> Option<(f64, f64)> {
+        if let Some(bounds) = self.find_node(role, name).and_then(|node| node.bounds.as_ref()) {
+            // Return center of element
+            return Some((
+                bounds.x + bounds.width / 2.0,
+                bounds.y + bounds.height / 2.0,
+            ));
         }
-        None
+        
+        self.get_coordinates_fuzzy(role, name?, fuzzy_threshold?)
+            .map(|(point, _)| point)
+    }
+    
+    /// Center of the best fuzzy match with bounds, plus its match score
+    pub fn get_coordinates_fuzzy(&self, role: &str, name_query: &str, threshold: f64) -> Option<((f64, f64), f64)> {
+        let (node, score) = self.best_fuzzy_match(role, name_query, threshold, true)?;
+        let bounds = node.bounds.as_ref()?;
+        debug!("Fuzzy AX match {:?} for '{}' (score {:.2})", node.name, name_query, score);
+        Some(((bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0), score))
     }
     
     /// Get all nodes of a specific role
@@ -951,22 +2555,63 @@ pub struct ChainOfCommand {
     /// Commander: Mid-level tactics (local VLM)
     pub commander_instruction: Option<String>,
     
+    /// Commander's region of interest: AX role the vision fallback is cropped to
+    /// (`None` = "main" landmark; full screenshot if the page has none)
+    pub commander_roi_role: Option<String>,
+    
     /// Soldier: Low-level execution (coordinates)
     pub soldier_target: Option<(f64, f64)>,
 }
 
+/// How the Commander resolved the Soldier's target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetSource {
+    /// Found in the accessibility tree
+    Semantic,
+    /// Located by the vision service
+    Vision,
+    /// Preset `soldier_target`, no lookup needed
+    Preset,
+}
+
+/// What a `ChainOfCommand::execute` run did at each level
+#[derive(Debug, Clone, Default)]
+pub struct ChainReport {
+    /// General's strategy for this step
+    pub strategy: Option<String>,
+    
+    /// Where the target came from (`None` = no target found)
+    pub target_source: Option<TargetSource>,
+    
+    /// Region the vision fallback found the target in (x, y, width, height; `None` = full screenshot)
+    pub roi: Option<(f64, f64, f64, f64)>,
+    
+    /// Where the Soldier clicked
+    pub clicked_at: Option<(f64, f64)>,
+}
+
 impl ChainOfCommand {
     /// Execute the full chain
+    /// 
+    /// The Commander tries the AX tree first; on a miss it asks the vision
+    /// service, cropped to its ROI. The Soldier then clicks with the session's
+    /// human-like mouse. Errors only if the vision lookup or the click fails.
     pub async fn execute(
         &mut self,
         session: &BrowserSession,
         fusion_state: &FusionState,
-    ) -> Result<()> {
+        vision: &mut VisionClient,
+    ) -> Result<ChainReport> {
+        let mut report = ChainReport::default();
+        if self.soldier_target.is_some() {
+            report.target_source = Some(TargetSource::Preset);
+        }
+        
         // Step 1: General plans the strategy
         if let Some(prompt) = &self.general_prompt {
             info!("General: {}", prompt);
-            // In production, this would call a cloud LLM (GPT-4, Claude, etc.)
-            // For now, we skip to commander
+            // No strategy model yet: the General's prompt is the strategy
+            report.strategy = Some(prompt.clone());
         }
         
         // Step 2: Commander finds the target
@@ -975,22 +2620,352 @@ impl ChainOfCommand {
             
             // Use dual-sense to find the target
             // First try semantic (fast)
-            if let Some((x, y)) = fusion_state.get_coordinates("button", Some(instruction)) {
+            if let Some((x, y)) = fusion_state.get_coordinates("button", Some(instruction), None) {
                 self.soldier_target = Some((x, y));
+                report.target_source = Some(TargetSource::Semantic);
                 info!("Commander found target via AX tree: ({:.0}, {:.0})", x, y);
             } else {
-                // Fall back to vision (slower but more reliable)
+                // Fall back to vision (slower but more reliable), cropped to the ROI:
+                // smallest region first, the full screenshot last
                 info!("Commander falling back to vision model");
-                // This would call the vision service
+                let roi_role = self.commander_roi_role.as_deref().unwrap_or("main");
+                let mut candidates: Vec<Option<(f64, f64, f64, f64)>> = Cortex::new(session.get_tab()?)
+                    .find_rois(roi_role, None)?
+                    .into_iter()
+                    .map(Some)
+                    .collect();
+                candidates.push(None);
+                
+                // AX bounds are CSS pixels; the screenshot is device pixels
+                let scale = session.viewport().device_scale_factor;
+                let mut found = None;
+                let mut last_error = None;
+                for roi in candidates {
+                    match vision
+                        .get_coordinates_with_roi(
+                            fusion_state.screenshot.clone(),
+                            instruction.clone(),
+                            roi.map(|(x, y, w, h)| (x * scale, y * scale, w * scale, h * scale)),
+                        )
+                        .await
+                    {
+                        Ok((x, y, _)) => {
+                            found = Some((x, y, roi));
+                            break;
+                        }
+                        Err(e) => {
+                            debug!("Vision miss in ROI {:?}: {}", roi, e);
+                            last_error = Some(e);
+                        }
+                    }
+                }
+                let (x, y, roi) = match (found, last_error) {
+                    (Some(found), _) => found,
+                    (None, Some(e)) => return Err(e.into()),
+                    (None, None) => unreachable!("the full screenshot is always tried"),
+                };
+                
+                // Vision answers relative to the crop
+                let (offset_x, offset_y) = roi.map_or((0.0, 0.0), |(x, y, _, _)| (x, y));
+                let target = (x as f64 / scale + offset_x, y as f64 / scale + offset_y);
+                self.soldier_target = Some(target);
+                report.target_source = Some(TargetSource::Vision);
+                report.roi = roi;
+                info!("Commander found target via vision: ({:.0}, {:.0})", target.0, target.1);
             }
         }
         
         // Step 3: Soldier executes
         if let Some((x, y)) = self.soldier_target {
             info!("Soldier: Clicking at ({:.0}, {:.0})", x, y);
-            // Execute the click
+            session.click_human_like(x.round() as i32, y.round() as i32, None).await?;
+            report.clicked_at = Some((x, y));
         }
         
-        Ok(())
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_windmouse_is_reproducible() {
+        let mut a = StdRng::seed_from_u64(42);
+        let mut b = StdRng::seed_from_u64(42);
+
+        let first = Cortex::generate_windmouse_trajectory(&mut a, 10.0, 20.0, 640.0, 480.0).unwrap();
+        let second = Cortex::generate_windmouse_trajectory(&mut b, 10.0, 20.0, 640.0, 480.0).unwrap();
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    fn node(id: &str, role: &str, name: &str) -> AxNode {
+        AxNode {
+            node_id: id.to_string(),
+            role: role.to_string(),
+            name: Some(name.to_string()),
+            value: None,
+            parent_id: None,
+            bounds: Some(AxBounds { x: 0.0, y: 0.0, width: 100.0, height: 20.0 }),
+            state: Vec::new(),
+            frame_id: None,
+            backend_node_id: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_pairs_nodes_by_identity() {
+        let mut cart = node("7", "status", "Cart");
+        cart.value = Some("0 items".to_string());
+        let before = AxTree {
+            nodes: vec![node("1", "button", "Add to cart"), cart.clone(), node("3", "link", "Promo")],
+        };
+        
+        // Every node_id churned; only the cart value and the promo/toast differ
+        let mut cart_after = node("70", "status", "Cart");
+        cart_after.value = Some("1 item".to_string());
+        let after = AxTree {
+            nodes: vec![node("10", "button", "Add to cart"), cart_after, node("11", "alert", "Added!")],
+        };
+        
+        let diff = before.diff(&after);
+        assert_eq!(diff.summary(), "+1 -1 ~1");
+        assert_eq!(diff.added[0].role, "alert");
+        assert_eq!(diff.removed[0].node_id, "3");
+        assert_eq!(diff.changed[0].1.value.as_deref(), Some("1 item"));
+        assert!(diff.touched("status", Some("Cart")));
+        assert!(!diff.touched("button", None));
+        assert!(before.diff(&before).is_empty());
+    }
+
+    #[test]
+    fn test_find_equivalent_prefers_same_id_then_identity() {
+        let target = node("5", "button", "Buy");
+        
+        // Two identical "Buy" buttons: the one that kept its node_id wins
+        let tree = AxTree { nodes: vec![node("2", "button", "Buy"), node("5", "button", "Buy")] };
+        assert_eq!(tree.find_equivalent(&target).unwrap().node_id, "5");
+        
+        // node_id reused by a different element: re-resolve by role + name
+        let tree = AxTree { nodes: vec![node("5", "link", "Help"), node("9", "button", "Buy")] };
+        assert_eq!(tree.find_equivalent(&target).unwrap().node_id, "9");
+        
+        // Same name in another frame is a different element
+        let mut framed = node("9", "button", "Buy");
+        framed.frame_id = Some("checkout".to_string());
+        let tree = AxTree { nodes: vec![node("5", "button", "Buy now"), framed] };
+        assert!(tree.find_equivalent(&target).is_none());
+    }
+
+    #[test]
+    fn test_reaction_ignores_focus_and_ambient_churn() {
+        let ticker = |id: &str, name: &str| AxNode { parent_id: Some("timer".to_string()), ..node(id, "StaticText", name) };
+        let observed = AxTree { nodes: vec![node("1", "button", "Subscribe"), ticker("2", "0:43")] };
+        let pre_click = AxTree { nodes: vec![node("1", "button", "Subscribe"), ticker("3", "0:42")] };
+        
+        // The click only focused the button while the countdown kept ticking
+        let mut focused = node("1", "button", "Subscribe");
+        focused.state = vec!["focused".to_string()];
+        let after = AxTree { nodes: vec![focused.clone(), ticker("4", "0:41")] };
+        let ambient = observed.diff(&pre_click);
+        assert!(!pre_click.diff(&after).is_empty());
+        assert!(pre_click.diff(&after).reaction(&ambient).is_empty());
+        
+        // A real reaction survives the filter
+        let mut pressed = focused;
+        pressed.state.push("pressed".to_string());
+        let after = AxTree { nodes: vec![pressed, ticker("4", "0:41"), node("5", "alert", "Subscribed!")] };
+        let reaction = pre_click.diff(&after).reaction(&ambient);
+        assert_eq!(reaction.summary(), "+1 -0 ~1");
+        assert!(reaction.touched("alert", None));
+    }
+
+    #[test]
+    fn test_to_markdown_outline_and_budget() {
+        let child = |id: &str, role: &str, name: Option<&str>, parent: Option<&str>| AxNode {
+            node_id: id.to_string(),
+            role: role.to_string(),
+            name: name.map(str::to_string),
+            value: None,
+            parent_id: parent.map(str::to_string),
+            bounds: None,
+            state: Vec::new(),
+            frame_id: None,
+            backend_node_id: None,
+        };
+        let mut email = child("7", "textbox", Some("Email"), Some("5"));
+        email.value = Some("a@b.c".to_string());
+        email.state = vec!["focusable".to_string(), "required".to_string()];
+        let tree = AxTree {
+            nodes: vec![
+                child("1", "RootWebArea", Some("Shop"), None),
+                child("2", "navigation", None, Some("1")),
+                child("3", "link", Some("Home"), Some("2")),
+                child("4", "StaticText", Some("Home"), Some("3")),
+                child("5", "group", None, Some("1")),
+                child("6", "heading", Some("Sign in"), Some("5")),
+                email,
+                child("8", "group", Some("Ads"), Some("1")),
+                child("9", "StaticText", Some("Buy   more\nstuff"), Some("8")),
+            ],
+        };
+        
+        let full = tree.to_markdown(usize::MAX);
+        assert_eq!(full, concat!(
+            "- RootWebArea \"Shop\"\n",
+            "  - navigation\n",
+            "    - link \"Home\"\n",
+            "  - heading \"Sign in\"\n",
+            "  - textbox \"Email\" = \"a@b.c\" [required]\n",
+            "  - group \"Ads\"\n",
+            "    - StaticText \"Buy more stuff\"\n",
+        ));
+        
+        // The full outline costs 45 tokens; one short, the ads branch goes first
+        let cut = tree.to_markdown(44);
+        assert!(!cut.contains("Ads"));
+        assert!(cut.contains("heading \"Sign in\""));
+        assert!(cut.ends_with("- … 2 more elements omitted\n"));
+        
+        // Squeezed harder, controls outlast the heading
+        let squeezed = tree.to_markdown(40);
+        assert!(!squeezed.contains("heading"));
+        assert!(squeezed.contains("textbox \"Email\""));
+    }
+
+    #[test]
+    fn test_parse_ax_nodes_keeps_backend_node_id() {
+        let raw = vec![
+            serde_json::json!({ "nodeId": "1", "role": { "value": "RootWebArea" }, "childIds": ["2"], "backendDOMNodeId": 3 }),
+            serde_json::json!({ "nodeId": "2", "parentId": "1", "role": { "value": "button" }, "name": { "value": "Buy" }, "backendDOMNodeId": 42 }),
+            serde_json::json!({ "nodeId": "9", "role": { "value": "StaticText" }, "name": { "value": "virtual" } }),
+        ];
+        let nodes = Cortex::parse_ax_nodes(&raw).unwrap();
+        let id_of = |node_id: &str| nodes.iter().find(|n| n.node_id == node_id).unwrap().backend_node_id;
+        assert_eq!(id_of("2"), Some(42));
+        assert_eq!(id_of("9"), None);
+    }
+
+    #[test]
+    fn test_detect_captcha_in_tree() {
+        let tree = |nodes| AxTree { nodes };
+        assert_eq!(
+            detect_captcha_in_tree(&tree(vec![node("1", "Iframe", "recaptcha challenge expires in two minutes")])),
+            Some(CaptchaKind::Recaptcha)
+        );
+        assert_eq!(
+            detect_captcha_in_tree(&tree(vec![node("1", "Iframe", "Widget containing checkbox for hCaptcha security challenge")])),
+            Some(CaptchaKind::Hcaptcha)
+        );
+        assert_eq!(
+            detect_captcha_in_tree(&tree(vec![node("1", "Iframe", "Widget containing a Cloudflare security challenge")])),
+            Some(CaptchaKind::Turnstile)
+        );
+        assert_eq!(
+            detect_captcha_in_tree(&tree(vec![node("1", "heading", "Select all images with traffic lights")])),
+            Some(CaptchaKind::Unknown)
+        );
+        assert_eq!(detect_captcha_in_tree(&tree(vec![node("1", "button", "Sign in")])), None);
+    }
+
+    #[test]
+    fn test_invisible_recaptcha_and_badge_are_not_challenges() {
+        // reCAPTCHA v3 on an ordinary page: invisible anchor frame plus the badge
+        let badge = AxTree {
+            nodes: vec![
+                node("1", "Iframe", "reCAPTCHA"),
+                node("2", "StaticText", "protected by reCAPTCHA"),
+                node("3", "link", "Privacy - Terms"),
+            ],
+        };
+        assert_eq!(detect_captcha_in_tree(&badge), None);
+        
+        let anchor = "https://www.google.com/recaptcha/api2/anchor?ar=1&k=6Le&co=aHR0&hl=en&v=x&size=invisible";
+        assert_eq!(CaptchaKind::from_challenge_frame(anchor), None);
+        assert_eq!(CaptchaKind::from_challenge_frame("https://www.google.com/recaptcha/api2/aframe"), None);
+        
+        // The visible checkbox and the image challenge do count
+        assert_eq!(
+            CaptchaKind::from_challenge_frame(&anchor.replace("size=invisible", "size=normal")),
+            Some(CaptchaKind::Recaptcha)
+        );
+        assert_eq!(
+            CaptchaKind::from_challenge_frame("https://www.google.com/recaptcha/api2/bframe?hl=en&v=x&k=6Le"),
+            Some(CaptchaKind::Recaptcha)
+        );
+    }
+
+    #[test]
+    fn test_cluster_regions_keeps_disjoint_areas_apart() {
+        let bounds = |x, y, width, height| AxBounds { x, y, width, height };
+        // Sidebar at the left edge, two results stacked in the main column
+        let sidebar = bounds(0.0, 100.0, 200.0, 40.0);
+        let first = bounds(600.0, 100.0, 500.0, 80.0);
+        let second = bounds(600.0, 200.0, 500.0, 80.0);
+        
+        let regions = cluster_regions(&[&first, &sidebar, &second], ROI_CLUSTER_GAP);
+        assert_eq!(regions, vec![(0.0, 100.0, 200.0, 40.0), (600.0, 100.0, 500.0, 180.0)]);
+        
+        // A huge gap threshold merges everything into one box
+        let merged = cluster_regions(&[&first, &sidebar, &second], 1000.0);
+        assert_eq!(merged, vec![(0.0, 100.0, 1100.0, 180.0)]);
+    }
+
+    #[test]
+    fn test_reading_pause_tracks_visible_text() {
+        // 5 of 20 words skimmed at 240 wpm; faster users read faster, all within bounds
+        assert_eq!(reading_pause(20, &MotorProfile::average()), Duration::from_millis(1250));
+        assert!(reading_pause(20, &MotorProfile::gamer()) < reading_pause(20, &MotorProfile::careful()));
+        assert_eq!(reading_pause(0, &MotorProfile::gamer()), Duration::from_millis(READING_PAUSE_MS.0));
+        assert_eq!(reading_pause(200, &MotorProfile::average()), Duration::from_millis(READING_PAUSE_MS.1));
+    }
+
+    #[test]
+    fn test_name_match_score() {
+        assert_eq!(name_match_score("  Sign In\n", "sign in"), 1.0);
+        assert!(name_match_score("Sign in to continue", "sign in") >= 0.8);
+        assert!(name_match_score("Submit »", "submit") >= DEFAULT_FUZZY_THRESHOLD);
+        assert!(name_match_score("Checkuot", "checkout") >= DEFAULT_FUZZY_THRESHOLD);
+        assert!(name_match_score("Privacy policy", "sign in") < DEFAULT_FUZZY_THRESHOLD);
+        assert_eq!(name_match_score("", "sign in"), 0.0);
+    }
+
+    #[test]
+    fn test_best_name_match_prefers_exact_name() {
+        let nodes = vec![
+            node("1", "option", "Canada (French)"),
+            node("2", "option", " canada "),
+            node("3", "option", "Canadian Dollar"),
+        ];
+        assert_eq!(best_name_match(nodes.iter(), "Canada").unwrap().node_id, "2");
+        assert_eq!(best_name_match(nodes.iter(), "french").unwrap().node_id, "1");
+        assert!(best_name_match(nodes.iter(), "Mexico").is_none());
+    }
+
+    #[test]
+    fn test_find_node_fuzzy_prefers_best_score() {
+        let fusion = FusionState {
+            screenshot: Vec::new(),
+            ax_tree: AxTree {
+                nodes: vec![
+                    node("1", "button", "Sign in with Google"),
+                    node("2", "button", "Sign In "),
+                    node("3", "link", "Sign in"),
+                ],
+            },
+            node_to_region: HashMap::new(),
+        };
+
+        let (best, score) = fusion.find_node_fuzzy("Button", "sign in", DEFAULT_FUZZY_THRESHOLD).unwrap();
+        assert_eq!(best.node_id, "2");
+        assert_eq!(score, 1.0);
+        assert!(fusion.find_node("button", Some("sign in")).is_none());
+        // Fuzzy fallback only when asked for
+        assert!(fusion.get_coordinates("button", Some("sign in"), None).is_none());
+        assert!(fusion.get_coordinates("button", Some("sign in"), Some(DEFAULT_FUZZY_THRESHOLD)).is_some());
+        assert!(fusion.find_node_fuzzy("button", "register", DEFAULT_FUZZY_THRESHOLD).is_none());
     }
 }
@@ -7,9 +7,37 @@
 /// particularly for Canvas/WebGL operations to inject organic entropy.
 
 use anyhow::{Context, Result};
+use std::net::IpAddr;
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+/// What the injected scripts do about WebRTC
+/// 
+/// WebRTC gathers ICE candidates straight from the network stack, bypassing the
+/// HTTP proxy: `host` candidates carry local interface IPs and `srflx`
+/// candidates the real public IP as seen by a STUN server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WebRtcPolicy {
+    /// Leave `RTCPeerConnection` untouched
+    Allow,
+    /// Force relay-only ICE so no host/srflx candidates are gathered
+    #[default]
+    StripLeaks,
+    /// Remove `RTCPeerConnection` entirely
+    Disable,
+}
+
+impl WebRtcPolicy {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "allow" => Some(Self::Allow),
+            "strip" | "strip_leaks" => Some(Self::StripLeaks),
+            "disable" => Some(Self::Disable),
+            _ => None,
+        }
+    }
+}
+
 /// DBI hook configuration
 pub struct DbiConfig {
     /// Enable Canvas entropy injection
@@ -23,6 +51,13 @@ pub struct DbiConfig {
     
     /// Session-unique seed for entropy
     pub session_seed: u64,
+    
+    /// Draw independent noise for R, G and B instead of one shared offset
+    /// (identical per-channel offsets are a detectable correlation)
+    pub per_channel_entropy: bool,
+    
+    /// WebRTC IP leak handling
+    pub webrtc_policy: WebRtcPolicy,
 }
 
 impl Default for DbiConfig {
@@ -35,6 +70,8 @@ impl Default for DbiConfig {
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap()
                 .as_secs(),
+            per_channel_entropy: true,
+            webrtc_policy: WebRtcPolicy::default(),
         }
     }
 }
@@ -64,6 +101,7 @@ impl DbiManager {
     pub fn get_canvas_hook_script(&self) -> String {
         let seed = self.config.session_seed;
         let strength = self.config.entropy_strength;
+        let per_channel = self.config.per_channel_entropy;
         
         format!(r#"
             (function() {{
@@ -72,6 +110,7 @@ impl DbiManager {
                 // Session-unique seed for entropy
                 const SESSION_SEED = {};
                 const ENTROPY_STRENGTH = {};
+                const PER_CHANNEL = {};
                 
                 // Simple PRNG seeded with session ID
                 function seededRandom(seed) {{
@@ -82,7 +121,23 @@ impl DbiManager {
                     }};
                 }}
                 
-                const rng = seededRandom(SESSION_SEED);
+                // Add seeded noise to the RGB channels of an RGBA buffer
+                // A fresh PRNG per read keeps repeated reads of the same
+                // pixels identical within the session
+                function applyEntropy(data) {{
+                    const rng = seededRandom(SESSION_SEED);
+                    const sample = () => (rng() - 0.5) * 2 * ENTROPY_STRENGTH * 255;
+                    for (let i = 0; i < data.length; i += 4) {{
+                        // Only modify RGB channels (not alpha)
+                        const noiseR = sample();
+                        const noiseG = PER_CHANNEL ? sample() : noiseR;
+                        const noiseB = PER_CHANNEL ? sample() : noiseR;
+                        data[i] = Math.max(0, Math.min(255, data[i] + noiseR));     // R
+                        data[i + 1] = Math.max(0, Math.min(255, data[i + 1] + noiseG)); // G
+                        data[i + 2] = Math.max(0, Math.min(255, data[i + 2] + noiseB)); // B
+                        // Alpha channel (i + 3) unchanged
+                    }}
+                }}
                 
                 // Hook Canvas getImageData
                 if (typeof HTMLCanvasElement !== 'undefined') {{
@@ -92,16 +147,7 @@ impl DbiManager {
                         
                         // Add microscopic entropy to pixel data
                         // This makes each session's canvas fingerprint unique
-                        const data = imageData.data;
-                        for (let i = 0; i < data.length; i += 4) {{
-                            // Only modify RGB channels (not alpha)
-                            // Add tiny random offset (-1 to +1) scaled by strength
-                            const noise = (rng() - 0.5) * 2 * ENTROPY_STRENGTH * 255;
-                            data[i] = Math.max(0, Math.min(255, data[i] + noise));     // R
-                            data[i + 1] = Math.max(0, Math.min(255, data[i + 1] + noise)); // G
-                            data[i + 2] = Math.max(0, Math.min(255, data[i + 2] + noise)); // B
-                            // Alpha channel (i + 3) unchanged
-                        }}
+                        applyEntropy(imageData.data);
                         
                         return imageData;
                     }};
@@ -115,12 +161,7 @@ impl DbiManager {
                         
                         if (pixels && pixels instanceof Uint8Array) {{
                             // Add entropy to WebGL pixel data
-                            for (let i = 0; i < pixels.length; i += 4) {{
-                                const noise = (rng() - 0.5) * 2 * ENTROPY_STRENGTH * 255;
-                                pixels[i] = Math.max(0, Math.min(255, pixels[i] + noise));     // R
-                                pixels[i + 1] = Math.max(0, Math.min(255, pixels[i + 1] + noise)); // G
-                                pixels[i + 2] = Math.max(0, Math.min(255, pixels[i + 2] + noise)); // B
-                            }}
+                            applyEntropy(pixels);
                         }}
                         
                         return result;
@@ -140,7 +181,7 @@ impl DbiManager {
                     }};
                 }}
             }})();
-        "#, seed, strength)
+        "#, seed, strength, per_channel)
     }
     
     /// Get JavaScript code to inject WebGL entropy hooks
@@ -153,6 +194,83 @@ impl DbiManager {
         self.get_canvas_hook_script()
     }
     
+    /// Get JavaScript code that keeps WebRTC from revealing the non-proxy IP
+    /// 
+    /// `StripLeaks` wraps `RTCPeerConnection` so every configuration is forced
+    /// to `iceTransportPolicy: 'relay'`; Chrome then never gathers host or srflx
+    /// candidates. The wrapper shares the native prototype (whose `constructor`
+    /// points back at it), so `instanceof` and the API surface are unchanged,
+    /// and it and the hooked `setConfiguration` report themselves as native
+    /// code. Returns `None` for `Allow`.
+    pub fn get_webrtc_hook_script(&self) -> Option<String> {
+        let policy = match self.config.webrtc_policy {
+            WebRtcPolicy::Allow => return None,
+            WebRtcPolicy::StripLeaks => "strip",
+            WebRtcPolicy::Disable => "disable",
+        };
+        
+        Some(format!(r#"
+            (function() {{
+                'use strict';
+                
+                const POLICY = '{}';
+                const NAMES = ['RTCPeerConnection', 'webkitRTCPeerConnection'];
+                
+                if (POLICY === 'disable') {{
+                    for (const name of NAMES) {{
+                        try {{
+                            Object.defineProperty(window, name, {{ value: undefined, writable: true, configurable: true }});
+                        }} catch (e) {{}}
+                    }}
+                    return;
+                }}
+                
+                const Native = window.RTCPeerConnection;
+                if (typeof Native !== 'function') return;
+                
+                const relayOnly = (config) => Object.assign({{}}, config, {{ iceTransportPolicy: 'relay' }});
+                
+                const Wrapped = function RTCPeerConnection(config, ...rest) {{
+                    if (!new.target) {{
+                        throw new TypeError("Failed to construct 'RTCPeerConnection': Please use the 'new' operator, this DOM object constructor cannot be called as a function.");
+                    }}
+                    return new Native(relayOnly(config), ...rest);
+                }};
+                const asNative = (fn, name) => {{
+                    Object.defineProperty(fn, 'toString', {{ value: () => 'function ' + name + '() {{ [native code] }}' }});
+                    return fn;
+                }};
+                
+                Wrapped.prototype = Native.prototype;
+                Object.setPrototypeOf(Wrapped, Native);
+                asNative(Wrapped, 'RTCPeerConnection');
+                // Instances must report the constructor they were made with
+                Object.defineProperty(Native.prototype, 'constructor', {{
+                    value: Wrapped, writable: true, enumerable: false, configurable: true,
+                }});
+                
+                // setConfiguration() must not be a way back to host candidates.
+                // Method shorthand: like native methods, it has no own prototype.
+                const setConfigurationDesc = Object.getOwnPropertyDescriptor(Native.prototype, 'setConfiguration');
+                if (setConfigurationDesc && typeof setConfigurationDesc.value === 'function') {{
+                    const originalSetConfiguration = setConfigurationDesc.value;
+                    const hooked = {{
+                        setConfiguration(config) {{
+                            return originalSetConfiguration.call(this, relayOnly(config));
+                        }},
+                    }}.setConfiguration;
+                    Object.defineProperty(Native.prototype, 'setConfiguration', Object.assign({{}}, setConfigurationDesc, {{
+                        value: asNative(hooked, 'setConfiguration'),
+                    }}));
+                }}
+                
+                for (const name of NAMES) {{
+                    Object.defineProperty(window, name, {{ value: Wrapped, writable: true, configurable: true }});
+                }}
+            }})();
+        "#, policy))
+    }
+    
     /// Inject all hooks into a browser tab
     /// 
     /// This should be called before any page loads to ensure hooks are active.
@@ -169,6 +287,14 @@ impl DbiManager {
         )
         .context("Failed to inject DBI hooks")?;
         
+        if let Some(webrtc_script) = self.get_webrtc_hook_script() {
+            tab.call_method(
+                "Page.addScriptToEvaluateOnNewDocument",
+                serde_json::json!({ "source": webrtc_script }),
+            )
+            .context("Failed to inject WebRTC hooks")?;
+        }
+        
         info!("DBI hooks injected successfully");
         Ok(())
     }
@@ -189,6 +315,66 @@ pub fn initialize_dbi(config: Option<DbiConfig>) -> DbiManager {
     DbiManager::new(config)
 }
 
+/// Gather ICE candidates from a throwaway peer connection and fail if any leaks an IP
+/// 
+/// Runs in the current document, so call it after a navigation (the hooks are
+/// installed on new documents). A disabled `RTCPeerConnection` passes trivially.
+/// `proxy_ip` is the address sites already see, so it's never counted as a leak.
+pub fn verify_webrtc_leak_free(tab: &headless_chrome::Tab, proxy_ip: Option<IpAddr>) -> Result<()> {
+    let probe = r#"
+        (async () => {
+            if (typeof RTCPeerConnection !== 'function') return '[]';
+            const pc = new RTCPeerConnection({ iceServers: [{ urls: 'stun:stun.l.google.com:19302' }] });
+            const candidates = [];
+            pc.onicecandidate = (e) => { if (e.candidate) candidates.push(e.candidate.candidate); };
+            pc.createDataChannel('probe');
+            await pc.setLocalDescription(await pc.createOffer());
+            await new Promise((resolve) => setTimeout(resolve, 2000));
+            pc.close();
+            return JSON.stringify(candidates);
+        })()
+    "#;
+    
+    let result = tab.evaluate(probe, true).context("Failed to run WebRTC probe")?;
+    let candidates: Vec<String> = result.value
+        .as_ref()
+        .and_then(|v| v.as_str())
+        .map(serde_json::from_str)
+        .transpose()
+        .context("Malformed WebRTC probe result")?
+        .unwrap_or_default();
+    
+    let leaks: Vec<String> = candidates.iter().filter_map(|c| leaked_address(c, proxy_ip)).collect();
+    if !leaks.is_empty() {
+        warn!("⚠️  WebRTC leaked addresses: {:?}", leaks);
+        anyhow::bail!("WebRTC gathered {} leaking candidate(s): {:?}", leaks.len(), leaks);
+    }
+    
+    debug!("WebRTC probe gathered {} candidate(s), none leaking", candidates.len());
+    Ok(())
+}
+
+/// Address an ICE candidate line exposes, if it's a leak
+/// 
+/// Any literal IP in a host, srflx or prflx candidate other than `proxy_ip`
+/// leaks: private interface addresses and public ones alike (a host candidate
+/// on a machine with a public interface is the real IP). mDNS `.local` names
+/// and relay candidates, which carry the TURN server's address, don't.
+fn leaked_address(candidate: &str, proxy_ip: Option<IpAddr>) -> Option<String> {
+    // candidate:<foundation> <component> <transport> <priority> <address> <port> typ <type> ...
+    let fields: Vec<&str> = candidate.split_whitespace().collect();
+    let address = *fields.get(4)?;
+    let kind = fields.iter().position(|f| *f == "typ").and_then(|i| fields.get(i + 1))?;
+    
+    match *kind {
+        "host" | "srflx" | "prflx" => {
+            let ip = address.parse::<IpAddr>().ok()?;
+            (!ip.is_unspecified() && Some(ip) != proxy_ip).then(|| address.to_string())
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +394,54 @@ mod tests {
         assert!(script.contains("getImageData"));
         assert!(script.contains("readPixels"));
     }
+    
+    #[test]
+    fn test_per_channel_flag_in_script() {
+        let shared = DbiManager::new(DbiConfig {
+            per_channel_entropy: false,
+            ..DbiConfig::default()
+        });
+        assert!(shared.get_canvas_hook_script().contains("const PER_CHANNEL = false;"));
+        
+        let independent = DbiManager::new(DbiConfig::default());
+        assert!(independent.get_canvas_hook_script().contains("const PER_CHANNEL = true;"));
+    }
+    
+    #[test]
+    fn test_webrtc_policy_script() {
+        let script = |policy| DbiManager::new(DbiConfig { webrtc_policy: policy, ..DbiConfig::default() })
+            .get_webrtc_hook_script();
+        
+        assert!(script(WebRtcPolicy::Allow).is_none());
+        assert!(script(WebRtcPolicy::StripLeaks).unwrap().contains("iceTransportPolicy: 'relay'"));
+        assert!(script(WebRtcPolicy::Disable).unwrap().contains("const POLICY = 'disable';"));
+    }
+    
+    #[test]
+    fn test_leaked_address() {
+        assert_eq!(
+            leaked_address("candidate:1 1 udp 2122260223 192.168.1.20 54321 typ host generation 0", None).as_deref(),
+            Some("192.168.1.20")
+        );
+        assert_eq!(
+            leaked_address("candidate:2 1 udp 1686052607 203.0.113.7 54321 typ srflx raddr 0.0.0.0 rport 0", None).as_deref(),
+            Some("203.0.113.7")
+        );
+        // A public interface address is the real IP too
+        assert_eq!(
+            leaked_address("candidate:5 1 udp 2122260223 203.0.113.7 54321 typ host generation 0", None).as_deref(),
+            Some("203.0.113.7")
+        );
+        assert_eq!(leaked_address("candidate:3 1 udp 2122260223 3f1c0a52-7d0e-4b8e.local 54321 typ host", None), None);
+        assert_eq!(leaked_address("candidate:4 1 udp 41885439 198.51.100.9 3478 typ relay raddr 0.0.0.0 rport 0", None), None);
+    }
+    
+    #[test]
+    fn test_proxy_address_is_not_a_leak() {
+        let proxy = "198.51.100.20".parse().ok();
+        let srflx = |ip: &str| format!("candidate:2 1 udp 1686052607 {} 54321 typ srflx raddr 0.0.0.0 rport 0", ip);
+        
+        assert_eq!(leaked_address(&srflx("198.51.100.20"), proxy), None);
+        assert_eq!(leaked_address(&srflx("203.0.113.7"), proxy).as_deref(), Some("203.0.113.7"));
+    }
 }
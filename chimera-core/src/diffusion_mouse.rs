@@ -9,11 +9,17 @@
 /// Architecture: Pre-trained Diffusion Transformer (DiT) exported to ONNX,
 /// running natively in Rust via ONNX Runtime for micro-second latency.
 
-use anyhow::{Context, Result};
+use anyhow::Context;
+use crate::error::{ChimeraError, Result};
+use crate::ghost_mouse::MotorProfile;
+use crate::trajectory::Trajectory;
 use ort::{GraphOptimizationLevel, Session, SessionBuilder, Value};
 use ndarray::{Array, Array2, Array3, Axis};
+use rand::rngs::StdRng;
 use rand::Rng;
+use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 use serde::{Deserialize, Serialize};
@@ -35,6 +41,140 @@ impl Point {
     }
 }
 
+/// Start/end positions are bucketed to this many pixels for cache lookups
+const CACHE_BUCKET_PX: f64 = 50.0;
+
+/// Target sizes are bucketed to this many pixels for cache lookups
+const CACHE_SIZE_BUCKET_PX: f64 = 20.0;
+
+/// Inferred variants kept per bucket before draws stop running the model
+const CACHE_VARIANTS_PER_BUCKET: usize = 4;
+
+/// Quantized (start, end, target size) a cached trajectory is filed under
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct BucketKey {
+    start: (i64, i64),
+    end: (i64, i64),
+    size: i64,
+}
+
+impl BucketKey {
+    fn new(start: Point, end: Point, target_size: f64) -> Self {
+        let q = |v: f64, step: f64| (v / step).round() as i64;
+        Self {
+            start: (q(start.x, CACHE_BUCKET_PX), q(start.y, CACHE_BUCKET_PX)),
+            end: (q(end.x, CACHE_BUCKET_PX), q(end.y, CACHE_BUCKET_PX)),
+            size: q(target_size, CACHE_SIZE_BUCKET_PX),
+        }
+    }
+}
+
+/// Trajectory variants for one bucket, with the endpoints they were generated for
+struct CacheBucket {
+    variants: Vec<(Point, Point, Vec<(Point, Duration)>)>,
+    last_drawn: Option<usize>,
+    last_used: u64,
+}
+
+/// LRU cache of Diffusion trajectories, several variants per bucket
+/// 
+/// A bucket is filled by real inference until it holds
+/// `CACHE_VARIANTS_PER_BUCKET` variants; after that, draws pick a random
+/// variant other than the previous one (humans never repeat a path exactly),
+/// warped onto the requested endpoints with re-jittered timing.
+struct TrajectoryCache {
+    capacity: usize,
+    buckets: HashMap<BucketKey, CacheBucket>,
+    clock: u64,
+}
+
+impl TrajectoryCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            buckets: HashMap::new(),
+            clock: 0,
+        }
+    }
+    
+    /// A stored variant for this bucket, or None if the bucket still needs inference
+    fn draw(
+        &mut self,
+        start: Point,
+        end: Point,
+        target_size: f64,
+        rng: &mut StdRng,
+    ) -> Option<Vec<(Point, Duration)>> {
+        self.clock += 1;
+        let bucket = self.buckets.get_mut(&BucketKey::new(start, end, target_size))?;
+        if bucket.variants.len() < CACHE_VARIANTS_PER_BUCKET {
+            return None;
+        }
+        
+        // Never hand out the same variant twice in a row
+        let index = match bucket.last_drawn {
+            Some(last) => (last + rng.gen_range(1..bucket.variants.len())) % bucket.variants.len(),
+            None => rng.gen_range(0..bucket.variants.len()),
+        };
+        bucket.last_drawn = Some(index);
+        bucket.last_used = self.clock;
+        
+        let (from, to, path) = &bucket.variants[index];
+        Some(warp_trajectory(path, *from, *to, start, end, rng.gen_range(0.9..1.1)))
+    }
+    
+    /// Remember a freshly inferred trajectory, evicting the least recently used bucket if full
+    fn store(&mut self, start: Point, end: Point, target_size: f64, path: Vec<(Point, Duration)>) {
+        if self.capacity == 0 {
+            return;
+        }
+        
+        self.clock += 1;
+        let key = BucketKey::new(start, end, target_size);
+        if !self.buckets.contains_key(&key) && self.buckets.len() >= self.capacity {
+            if let Some(oldest) = self.buckets.iter().min_by_key(|(_, b)| b.last_used).map(|(k, _)| *k) {
+                self.buckets.remove(&oldest);
+            }
+        }
+        
+        let bucket = self.buckets.entry(key).or_insert_with(|| CacheBucket {
+            variants: Vec::with_capacity(CACHE_VARIANTS_PER_BUCKET),
+            last_drawn: None,
+            last_used: 0,
+        });
+        bucket.last_used = self.clock;
+        if bucket.variants.len() < CACHE_VARIANTS_PER_BUCKET {
+            bucket.variants.push((start, end, path));
+        }
+    }
+}
+
+/// Map a trajectory generated for `from -> to` onto `start -> end`
+/// 
+/// Each point is shifted by a blend of the start and end offsets (more of the
+/// start offset early on, more of the end offset late), so the path's shape
+/// survives and it still lands exactly where requested. Delays are scaled by
+/// `time_scale`.
+fn warp_trajectory(
+    path: &[(Point, Duration)],
+    from: Point,
+    to: Point,
+    start: Point,
+    end: Point,
+    time_scale: f64,
+) -> Vec<(Point, Duration)> {
+    let last = path.len().saturating_sub(1).max(1) as f64;
+    path.iter()
+        .enumerate()
+        .map(|(i, (p, delay))| {
+            let t = i as f64 / last;
+            let dx = (start.x - from.x) * (1.0 - t) + (end.x - to.x) * t;
+            let dy = (start.y - from.y) * (1.0 - t) + (end.y - to.y) * t;
+            (Point::new(p.x + dx, p.y + dy), delay.mul_f64(time_scale))
+        })
+        .collect()
+}
+
 /// Diffusion Mouse - Generates human-like trajectories using Diffusion models
 pub struct DiffusionMouse {
     /// ONNX Runtime session for the Diffusion model
@@ -46,6 +186,12 @@ pub struct DiffusionMouse {
     
     /// Fallback to physics-based movement if model not available
     use_fallback: bool,
+    
+    /// Recently inferred trajectories, reused to skip redundant inference
+    cache: Mutex<TrajectoryCache>,
+    
+    /// Motor personality of the physics fallback
+    motor_profile: MotorProfile,
 }
 
 impl DiffusionMouse {
@@ -53,12 +199,17 @@ impl DiffusionMouse {
     /// 
     /// If model_path is provided and valid, loads the ONNX model.
     /// Otherwise, falls back to physics-based movement.
-    pub fn new(model_path: Option<&str>) -> Result<Self> {
+    /// 
+    /// `cache_size` is how many (start, end, size) buckets of inferred
+    /// trajectories to keep; 0 runs inference for every movement.
+    pub fn new(model_path: Option<&str>, cache_size: usize) -> Result<Self> {
         let mut mouse = Self {
             #[cfg(feature = "onnx")]
             model: None,
             model_path: model_path.map(|s| s.to_string()),
             use_fallback: true,
+            cache: Mutex::new(TrajectoryCache::new(cache_size)),
+            motor_profile: MotorProfile::default(),
         };
         
         // Try to load the model
@@ -92,16 +243,23 @@ impl DiffusionMouse {
         Ok(mouse)
     }
     
+    /// Move like `profile` whenever the physics fallback runs
+    pub fn with_motor_profile(mut self, profile: MotorProfile) -> Self {
+        self.motor_profile = profile;
+        self
+    }
+    
     /// Load ONNX model from file
     #[cfg(feature = "onnx")]
     fn load_model(&mut self, path: &str) -> Result<()> {
         debug!("Loading Diffusion model from: {}", path);
         
-        let session = SessionBuilder::new()?
-            .with_optimization_level(GraphOptimizationLevel::Level3)?
-            .with_intra_threads(1)?  // Single thread for deterministic results
+        let onnx_err = |e: ort::Error| ChimeraError::Onnx(format!("Failed to load ONNX model {}: {}", path, e));
+        let session = SessionBuilder::new().map_err(onnx_err)?
+            .with_optimization_level(GraphOptimizationLevel::Level3).map_err(onnx_err)?
+            .with_intra_threads(1).map_err(onnx_err)?  // Single thread for deterministic results
             .commit_from_file(path)
-            .context("Failed to load ONNX model file")?;
+            .map_err(onnx_err)?;
         
         self.model = Some(session);
         Ok(())
@@ -119,23 +277,34 @@ impl DiffusionMouse {
     /// - Micro-tremors
     /// - Variable acceleration
     /// - Human-like corrections
+    /// 
+    /// All randomness (noise, timing, variant choice) comes from `rng`, so a
+    /// seeded session replays the same trajectories.
     pub fn generate_trajectory(
         &self,
         start: Point,
         end: Point,
         target_size: f64,
+        rng: &mut StdRng,
     ) -> Vec<(Point, Duration)> {
         #[cfg(feature = "onnx")]
         {
             if let Some(ref model) = self.model {
+                if let Some(cached) = self.cache.lock().unwrap().draw(start, end, target_size, rng) {
+                    debug!("Reusing cached Diffusion trajectory variant");
+                    return cached;
+                }
+                
                 // Use Diffusion model
-                return self.generate_diffusion_trajectory(model, start, end, target_size);
+                let trajectory = self.generate_diffusion_trajectory(model, start, end, target_size, rng);
+                self.cache.lock().unwrap().store(start, end, target_size, trajectory.clone());
+                return trajectory;
             }
         }
         
         // Fallback to physics-based movement
         warn!("Using fallback physics-based trajectory (Diffusion model not available)");
-        self.generate_fallback_trajectory(start, end, target_size)
+        self.generate_fallback_trajectory(start, end, target_size, rng)
     }
     
     /// Generate trajectory using Diffusion model
@@ -146,6 +315,7 @@ impl DiffusionMouse {
         start: Point,
         end: Point,
         target_size: f64,
+        rng: &mut StdRng,
     ) -> Vec<(Point, Duration)> {
         debug!("Generating Diffusion trajectory from ({:.0}, {:.0}) to ({:.0}, {:.0})", 
                start.x, start.y, end.x, end.y);
@@ -164,12 +334,11 @@ impl DiffusionMouse {
         
         // Generate random Gaussian noise (the "seed" for Diffusion)
         // This is what makes each trajectory unique
-        let mut rng = rand::thread_rng();
         let noise_shape = (1, num_steps, 2); // [batch, steps, x/y]
         use rand_distr::Distribution;
         let normal = rand_distr::StandardNormal;
         let noise: Vec<f32> = (0..noise_shape.0 * noise_shape.1 * noise_shape.2)
-            .map(|_| normal.sample(&mut rng) as f32)
+            .map(|_| normal.sample(rng) as f32)
             .collect();
         
         // Prepare inputs for the model
@@ -204,7 +373,7 @@ impl DiffusionMouse {
             .context("Failed to extract output")
             .unwrap();
         
-        let trajectory = self.parse_trajectory(&output_array, start, end, distance);
+        let trajectory = self.parse_trajectory(&output_array, start, end, distance, rng);
         
         debug!("Generated {} point trajectory via Diffusion", trajectory.len());
         trajectory
@@ -217,6 +386,7 @@ impl DiffusionMouse {
         start: Point,
         end: Point,
         distance: f64,
+        rng: &mut StdRng,
     ) -> Vec<(Point, Duration)> {
         let mut trajectory = Vec::new();
         let num_steps = output.shape()[1];
@@ -228,7 +398,6 @@ impl DiffusionMouse {
         let movement_time_ms = a + b * (distance / target_size + 1.0).log2();
         
         // Add randomness for human-like variation
-        let mut rng = rand::thread_rng();
         let time_variance = rng.gen_range(0.8..1.2);
         let total_time_ms = (movement_time_ms * time_variance) as u64;
         
@@ -261,13 +430,14 @@ impl DiffusionMouse {
         start: Point,
         end: Point,
         target_size: f64,
+        rng: &mut StdRng,
     ) -> Vec<(Point, Duration)> {
         // Use the existing neuromotor physics as fallback
         // This is the code from ghost_mouse.rs
         use crate::ghost_mouse::NeuromotorMouse;
         
-        let mut neuromotor = NeuromotorMouse::new(start.x, start.y);
-        let path = neuromotor.generate_human_path(end.x, end.y, target_size);
+        let mut neuromotor = NeuromotorMouse::new(start.x, start.y, self.motor_profile.clone());
+        let path = neuromotor.generate_human_path(end.x, end.y, target_size, rng);
         
         path.into_iter()
             .map(|(x, y, delay)| (Point::new(x, y), delay))
@@ -301,6 +471,7 @@ pub async fn diffusion_click(
     target_y: f64,
     target_size: f64,
     current_pos: Option<Point>,
+    rng: &mut StdRng,
 ) -> Result<()> {
     use tokio::time::sleep;
     
@@ -310,12 +481,17 @@ pub async fn diffusion_click(
     debug!("Starting Diffusion-based click to ({:.0}, {:.0})", target_x, target_y);
     
     // Generate trajectory
-    let trajectory = mouse.generate_trajectory(start, end, target_size);
+    let trajectory = mouse.generate_trajectory(start, end, target_size, rng);
+    
+    if crate::trajectory::enabled() {
+        let steps = trajectory.iter().map(|(point, delay)| (point.x, point.y, *delay));
+        crate::trajectory::record(&Trajectory::from_steps("diffusion", (target_x, target_y), steps));
+    }
     
     // Move along the trajectory
     for (point, delay) in trajectory {
         tab.move_mouse(point.x, point.y)
-            .map_err(|e| anyhow::anyhow!("Failed to move mouse: {}", e))?;
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to move mouse: {}", e)))?;
         
         if !delay.is_zero() {
             sleep(delay).await;
@@ -323,13 +499,12 @@ pub async fn diffusion_click(
     }
     
     // Small pause before clicking (humans don't click instantly)
-    let mut rng = rand::thread_rng();
     let pre_click_delay = rng.gen_range(50..150);
     sleep(Duration::from_millis(pre_click_delay)).await;
     
     // Press mouse button
     tab.click(headless_chrome::types::MouseButton::Left)
-        .map_err(|e| anyhow::anyhow!("Failed to click: {}", e))?;
+        .map_err(|e| ChimeraError::ActionFailed(format!("Failed to click: {}", e)))?;
     
     // Variable hold time
     let hold_time = rng.gen_range(50..200);
@@ -341,12 +516,84 @@ pub async fn diffusion_click(
 }
 
 /// Generate Gaussian noise for Diffusion model
-fn generate_gaussian_noise(shape: (usize, usize)) -> Vec<f32> {
+fn generate_gaussian_noise(shape: (usize, usize), rng: &mut StdRng) -> Vec<f32> {
     use rand_distr::{Distribution, StandardNormal};
-    let mut rng = rand::thread_rng();
     let normal = StandardNormal;
     
     (0..shape.0 * shape.1)
-        .map(|_| normal.sample(&mut rng) as f32)
+        .map(|_| normal.sample(rng) as f32)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    /// Three-point path from `from` to `to` whose midpoint is pushed down by `bend`
+    fn path(from: Point, to: Point, bend: f64) -> Vec<(Point, Duration)> {
+        let mid = Point::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0 + bend);
+        [from, mid, to]
+            .iter()
+            .map(|&p| (p, Duration::from_millis(10)))
+            .collect()
+    }
+
+    #[test]
+    fn test_cache_infers_until_bucket_is_full() {
+        let mut cache = TrajectoryCache::new(8);
+        let mut rng = StdRng::seed_from_u64(1);
+        let (start, end) = (Point::new(100.0, 100.0), Point::new(500.0, 300.0));
+
+        for bend in 0..CACHE_VARIANTS_PER_BUCKET - 1 {
+            cache.store(start, end, 40.0, path(start, end, bend as f64 * 10.0));
+            assert!(cache.draw(start, end, 40.0, &mut rng).is_none());
+        }
+        cache.store(start, end, 40.0, path(start, end, 100.0));
+        assert!(cache.draw(start, end, 40.0, &mut rng).is_some());
+    }
+
+    #[test]
+    fn test_cache_never_draws_the_same_variant_twice_in_a_row() {
+        let mut cache = TrajectoryCache::new(8);
+        let mut rng = StdRng::seed_from_u64(7);
+        let (start, end) = (Point::new(100.0, 100.0), Point::new(500.0, 300.0));
+        for bend in 0..CACHE_VARIANTS_PER_BUCKET {
+            cache.store(start, end, 40.0, path(start, end, bend as f64 * 10.0));
+        }
+
+        // Same endpoints, so the warp is a no-op and the midpoint identifies the variant
+        let mut seen = std::collections::HashSet::new();
+        let mut previous = None;
+        for _ in 0..50 {
+            let drawn = cache.draw(start, end, 40.0, &mut rng).unwrap();
+            let bend = (drawn[1].0.y - 200.0).round() as i64;
+            assert_ne!(Some(bend), previous, "variant repeated back to back");
+            seen.insert(bend);
+            previous = Some(bend);
+        }
+        assert_eq!(seen.len(), CACHE_VARIANTS_PER_BUCKET);
+    }
+
+    #[test]
+    fn test_cached_variant_is_warped_onto_requested_endpoints() {
+        let mut cache = TrajectoryCache::new(8);
+        let mut rng = StdRng::seed_from_u64(3);
+        let (from, to) = (Point::new(100.0, 100.0), Point::new(500.0, 300.0));
+        for bend in 0..CACHE_VARIANTS_PER_BUCKET {
+            cache.store(from, to, 40.0, path(from, to, bend as f64 * 10.0));
+        }
+
+        // Nearby endpoints land in the same bucket but must still be hit exactly
+        let (start, end) = (Point::new(110.0, 95.0), Point::new(490.0, 310.0));
+        let drawn = cache.draw(start, end, 40.0, &mut rng).unwrap();
+        let (first, last) = (drawn[0].0, drawn[drawn.len() - 1].0);
+        assert!(first.distance_to(&start) < 1e-9, "starts at {:?}", first);
+        assert!(last.distance_to(&end) < 1e-9, "ends at {:?}", last);
+
+        // Timing is re-jittered within +/-10%
+        for (_, delay) in &drawn {
+            assert!(*delay >= Duration::from_millis(9) && *delay <= Duration::from_millis(11));
+        }
+    }
+}
@@ -0,0 +1,198 @@
+/// Download Capture - "Click Export, Get the File"
+///
+/// Plenty of tasks end in a file rather than a page: a CSV export, an invoice
+/// PDF. Headless Chrome denies downloads by default, so the session has to opt
+/// in (`BrowserSession::enable_downloads`) and then find out when the file is
+/// actually on disk.
+///
+/// Chrome is told to save into the session's download directory under each
+/// download's guid (`Browser.setDownloadBehavior` with `allowAndName`), so a
+/// half-written file never shows up under its real name. `Page.downloadWillBegin`
+/// gives the guid its suggested filename; once `Page.downloadProgress` reports
+/// it completed, the file is renamed to that name and queued for
+/// `wait_for_download`.
+
+use anyhow::{Context, Result};
+use headless_chrome::protocol::cdp::types::Event;
+use headless_chrome::protocol::cdp::Page::DownloadProgressEventStateOption;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tracing::{debug, info, warn};
+
+/// Name used when the server suggests nothing usable
+const FALLBACK_FILENAME: &str = "download";
+
+/// How a download ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadState {
+    InProgress,
+    Completed,
+    Canceled,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    /// Downloads in flight: guid -> suggested filename
+    pending: HashMap<String, String>,
+
+    /// Finished downloads not yet handed out, oldest first
+    finished: VecDeque<std::result::Result<PathBuf, String>>,
+
+    /// Tabs already reporting download events here
+    tabs: HashSet<String>,
+}
+
+/// Follows a session's downloads from `downloadWillBegin` to a file on disk
+#[derive(Debug)]
+pub struct DownloadTracker {
+    dir: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl DownloadTracker {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Directory Chrome saves into
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Route `tab`'s download events here (once per tab)
+    pub fn attach(self: &Arc<Self>, tab: &Arc<headless_chrome::Tab>) -> Result<()> {
+        if !self.inner.lock().unwrap().tabs.insert(tab.get_target_id().to_string()) {
+            return Ok(());
+        }
+
+        let tracker = Arc::clone(self);
+        tab.add_event_listener(Arc::new(move |event: &Event| match event {
+            Event::PageDownloadWillBegin(e) => tracker.began(&e.params.guid, &e.params.suggested_filename),
+            Event::PageDownloadProgress(e) => tracker.progressed(
+                &e.params.guid,
+                match e.params.state {
+                    DownloadProgressEventStateOption::InProgress => DownloadState::InProgress,
+                    DownloadProgressEventStateOption::Completed => DownloadState::Completed,
+                    DownloadProgressEventStateOption::Canceled => DownloadState::Canceled,
+                },
+            ),
+            _ => {}
+        }))
+        .map_err(|e| anyhow::anyhow!("Failed to listen for downloads on tab {}: {}", tab.get_target_id(), e))?;
+        Ok(())
+    }
+
+    /// `Page.downloadWillBegin`
+    pub fn began(&self, guid: &str, suggested_filename: &str) {
+        debug!("Download {} began: {}", guid, suggested_filename);
+        self.inner
+            .lock()
+            .unwrap()
+            .pending
+            .insert(guid.to_string(), suggested_filename.to_string());
+    }
+
+    /// `Page.downloadProgress`
+    pub fn progressed(&self, guid: &str, state: DownloadState) {
+        if state == DownloadState::InProgress {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        let Some(suggested) = inner.pending.remove(guid) else {
+            return;
+        };
+        let outcome = match state {
+            DownloadState::Completed => self.finalize(guid, &suggested).map_err(|e| format!("{:#}", e)),
+            _ => Err(format!("Download of '{}' was canceled", suggested)),
+        };
+        match &outcome {
+            Ok(path) => info!("📥 Download complete: {}", path.display()),
+            Err(e) => warn!("📥 Download {} failed: {}", guid, e),
+        }
+        inner.finished.push_back(outcome);
+    }
+
+    /// Oldest finished download not yet handed out
+    pub fn take_finished(&self) -> Option<std::result::Result<PathBuf, String>> {
+        self.inner.lock().unwrap().finished.pop_front()
+    }
+
+    /// Give the completed file its suggested name
+    ///
+    /// Saved under its guid (`allowAndName`) it's renamed; if Chrome only
+    /// took `Page.setDownloadBehavior`, it's already under the suggested name.
+    fn finalize(&self, guid: &str, suggested: &str) -> Result<PathBuf> {
+        let by_guid = self.dir.join(guid);
+        if !by_guid.exists() {
+            let by_name = self.dir.join(sanitize_filename(suggested));
+            anyhow::ensure!(by_name.exists(), "Completed download {} not found in {}", guid, self.dir.display());
+            return Ok(by_name);
+        }
+
+        let target = unique_path(&self.dir, &sanitize_filename(suggested));
+        std::fs::rename(&by_guid, &target)
+            .with_context(|| format!("Failed to rename download to {}", target.display()))?;
+        Ok(target)
+    }
+}
+
+/// Strip anything that would escape the download directory
+fn sanitize_filename(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+    match name {
+        "" | "." | ".." => FALLBACK_FILENAME.to_string(),
+        name => name.to_string(),
+    }
+}
+
+/// `dir/name`, or `dir/name (1).ext`, `(2)`... if taken - as Chrome itself does
+fn unique_path(dir: &Path, name: &str) -> PathBuf {
+    let candidate = dir.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let (stem, ext) = match name.rfind('.') {
+        Some(dot) if dot > 0 => (&name[..dot], &name[dot..]),
+        _ => (name, ""),
+    };
+    (1..)
+        .map(|n| dir.join(format!("{} ({}){}", stem, n, ext)))
+        .find(|path| !path.exists())
+        .expect("unbounded counter")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completed_download_gets_suggested_name() {
+        let dir = std::env::temp_dir().join(format!("chimera-downloads-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("report.csv"), "older").unwrap();
+        let tracker = DownloadTracker::new(dir.clone());
+
+        tracker.began("guid-1", "../report.csv");
+        std::fs::write(dir.join("guid-1"), "a,b\n1,2\n").unwrap();
+        tracker.progressed("guid-1", DownloadState::InProgress);
+        assert!(tracker.take_finished().is_none());
+
+        // Existing file is kept; path components can't escape the directory
+        tracker.progressed("guid-1", DownloadState::Completed);
+        let path = tracker.take_finished().unwrap().unwrap();
+        assert_eq!(path, dir.join("report (1).csv"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "a,b\n1,2\n");
+
+        tracker.began("guid-2", "invoice.pdf");
+        tracker.progressed("guid-2", DownloadState::Canceled);
+        assert!(tracker.take_finished().unwrap().is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
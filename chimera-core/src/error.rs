@@ -8,12 +8,38 @@ pub enum ChimeraError {
     #[error("Vision service error: {0}")]
     Vision(String),
     
+    /// The channel itself failed (connect refused, connection reset); worth reconnecting
+    #[error("Vision service unavailable: {0}")]
+    VisionUnavailable(String),
+    
     #[error("Session not found: {0}")]
     SessionNotFound(String),
     
     #[error("Action failed: {0}")]
     ActionFailed(String),
     
+    #[error("Unsafe action: {0}")]
+    UnsafeAction(String),
+    
+    /// The click landed on a different element than the one aimed at (e.g. a transparent overlay)
+    #[error("Click intercepted: {0}")]
+    ClickIntercepted(String),
+    
+    /// Phantom Proxy setup, serving or self-test failed
+    #[error("Proxy error: {0}")]
+    Proxy(String),
+    
+    /// Identity grafting: profile storage, lookup or Redis coordination failed
+    #[error("Profile error: {0}")]
+    Profile(String),
+    
+    #[error("World model error: {0}")]
+    WorldModel(String),
+    
+    /// Diffusion model loading or inference failed
+    #[error("ONNX error: {0}")]
+    Onnx(String),
+    
     #[error("gRPC error: {0}")]
     Grpc(#[from] tonic::Status),
     
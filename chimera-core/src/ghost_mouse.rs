@@ -7,27 +7,113 @@
 /// - Micro-tremors (hand jitter)
 /// - Variable acceleration based on distance
 
+use crate::seed::{SeedStream, SessionSeed};
+use rand::rngs::StdRng;
 use rand::Rng;
 use rand_distr::{Normal, Distribution};
+use serde::{Deserialize, Serialize};
 use std::time::{Duration, Instant};
 use tracing::debug;
+use crate::trajectory::Trajectory;
+
+/// A person's motor habits: how often they overshoot, by how much, how shaky
+/// and how fast they are
+/// 
+/// Commit to one per identity (see `SyntheticProfile::motor_profile`): the
+/// same user should move the same way every session, while different users
+/// shouldn't share one signature.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MotorProfile {
+    /// Probability (0.0-1.0) of overshooting a target more than 200px away
+    pub overshoot_prob: f64,
+    
+    /// Overshoot distance in px (min, max)
+    pub overshoot_range: (f64, f64),
+    
+    /// Standard deviation of hand tremor in px
+    pub tremor_std: f64,
+    
+    /// Movement speed relative to average (2.0 = twice as fast)
+    pub speed_scale: f64,
+}
+
+impl Default for MotorProfile {
+    fn default() -> Self {
+        Self::average()
+    }
+}
+
+impl MotorProfile {
+    /// Typical office user
+    pub fn average() -> Self {
+        Self {
+            overshoot_prob: 0.3,
+            overshoot_range: (5.0, 20.0),
+            tremor_std: 1.5,
+            speed_scale: 1.0,
+        }
+    }
+    
+    /// Fast, flick-heavy mouse user: overshoots often and far, steady hand
+    pub fn gamer() -> Self {
+        Self {
+            overshoot_prob: 0.5,
+            overshoot_range: (10.0, 35.0),
+            tremor_std: 0.8,
+            speed_scale: 1.5,
+        }
+    }
+    
+    /// Slow, deliberate user: rarely overshoots, shakier hand
+    pub fn careful() -> Self {
+        Self {
+            overshoot_prob: 0.1,
+            overshoot_range: (2.0, 8.0),
+            tremor_std: 2.5,
+            speed_scale: 0.65,
+        }
+    }
+    
+    /// A random personality somewhere between `careful` and `gamer`
+    pub fn sample(rng: &mut StdRng) -> Self {
+        let overshoot_min = rng.gen_range(2.0..10.0);
+        Self {
+            overshoot_prob: rng.gen_range(0.1..0.5),
+            overshoot_range: (overshoot_min, overshoot_min + rng.gen_range(6.0..25.0)),
+            tremor_std: rng.gen_range(0.8..2.5),
+            speed_scale: rng.gen_range(0.65..1.5),
+        }
+    }
+    
+    /// The personality of grafted profile `profile_id`, the same on every load
+    pub fn for_profile(profile_id: &str) -> Self {
+        Self::sample(&mut SessionSeed::for_profile(profile_id).rng(SeedStream::Motor))
+    }
+}
 
 /// Neuromotor Mouse - Simulates human arm/hand physics
 pub struct NeuromotorMouse {
     current_x: f64,
     current_y: f64,
     last_move_time: Option<Instant>,
+    profile: MotorProfile,
 }
 
 impl NeuromotorMouse {
-    pub fn new(start_x: f64, start_y: f64) -> Self {
+    pub fn new(start_x: f64, start_y: f64, profile: MotorProfile) -> Self {
         Self {
             current_x: start_x,
             current_y: start_y,
             last_move_time: None,
+            profile,
         }
     }
     
+    /// Motor personality this mouse moves with
+    pub fn profile(&self) -> &MotorProfile {
+        &self.profile
+    }
+    
     /// Generate a human-like path using Fitts's Law and neuromotor simulation
     /// 
     /// Fitts's Law: Movement time = a + b * log2(distance/target_size + 1)
@@ -37,11 +123,14 @@ impl NeuromotorMouse {
     /// 2. Decelerates at the end (precision correction)
     /// 3. May overshoot and correct (human imperfection)
     /// 4. Has micro-tremors (hand jitter)
+    /// 
+    /// The same `rng` state always yields the same path.
     pub fn generate_human_path(
         &mut self,
         target_x: f64,
         target_y: f64,
         target_size: f64, // Size of target (for Fitts's Law)
+        rng: &mut StdRng,
     ) -> Vec<(f64, f64, Duration)> {
         let distance = ((target_x - self.current_x).powi(2) + (target_y - self.current_y).powi(2)).sqrt();
         
@@ -51,17 +140,17 @@ impl NeuromotorMouse {
         // Constants based on human motor control research
         let a = 100.0; // Base time (ms)
         let b = 200.0; // Difficulty coefficient
-        let movement_time_ms = a + b * (distance / target_size.max(1.0) + 1.0).log2();
+        let movement_time_ms = (a + b * (distance / target_size.max(1.0) + 1.0).log2()) / self.profile.speed_scale.max(0.1);
         
         // Add randomness (humans are not perfectly consistent)
-        let mut rng = rand::thread_rng();
         let time_variance = rng.gen_range(0.8..1.2);
         let total_time_ms = (movement_time_ms * time_variance) as u64;
         
-        // Determine if we'll overshoot (humans do this ~30% of the time for large movements)
-        let will_overshoot = distance > 200.0 && rng.gen_bool(0.3);
+        // Determine if we'll overshoot (on large movements, as often as this person does)
+        let will_overshoot = distance > 200.0 && rng.gen_bool(self.profile.overshoot_prob.clamp(0.0, 1.0));
         let overshoot_distance = if will_overshoot {
-            rng.gen_range(5.0..20.0)
+            let (min, max) = self.profile.overshoot_range;
+            if max > min { rng.gen_range(min..max) } else { min }
         } else {
             0.0
         };
@@ -77,7 +166,7 @@ impl NeuromotorMouse {
         };
         
         // Normal distribution for micro-tremors (hand jitter)
-        let tremor_dist = Normal::new(0.0, 1.5).unwrap();
+        let tremor_dist = Normal::new(0.0, self.profile.tremor_std.max(0.0)).unwrap();
         
         let mut path = Vec::with_capacity(steps + 1);
         let start_time = Instant::now();
@@ -115,8 +204,8 @@ impl NeuromotorMouse {
             
             // Add micro-tremors (hand jitter)
             // These are tiny random movements that occur naturally
-            let tremor_x = tremor_dist.sample(&mut rng);
-            let tremor_y = tremor_dist.sample(&mut rng);
+            let tremor_x = tremor_dist.sample(rng);
+            let tremor_y = tremor_dist.sample(rng);
             
             x += tremor_x;
             y += tremor_y;
@@ -182,13 +271,19 @@ pub async fn neuromotor_click(
     target_x: f64,
     target_y: f64,
     target_size: f64,
+    rng: &mut StdRng,
 ) -> anyhow::Result<()> {
     use tokio::time::sleep;
     
     debug!("Starting neuromotor click to ({:.0}, {:.0})", target_x, target_y);
     
     // Generate the path
-    let path = mouse.generate_human_path(target_x, target_y, target_size);
+    let path = mouse.generate_human_path(target_x, target_y, target_size, rng);
+    
+    if crate::trajectory::enabled() {
+        let trajectory = Trajectory::from_steps("neuromotor", (target_x, target_y), path.iter().copied());
+        crate::trajectory::record(&trajectory);
+    }
     
     // Move along the path
     for (x, y, delay) in path {
@@ -201,7 +296,6 @@ pub async fn neuromotor_click(
     }
     
     // Small pause before clicking (humans don't click instantly)
-    let mut rng = rand::thread_rng();
     let pre_click_delay = rng.gen_range(50..150);
     sleep(Duration::from_millis(pre_click_delay)).await;
     
@@ -217,3 +311,37 @@ pub async fn neuromotor_click(
     
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Points of the path a session seeded with `seed` takes (delays depend on wall time)
+    fn session_path(seed: SessionSeed) -> Vec<(f64, f64)> {
+        let mut rng = seed.rng(SeedStream::Mouse);
+        let profile = MotorProfile::sample(&mut rng);
+        let mut mouse = NeuromotorMouse::new(120.0, 80.0, profile);
+        mouse
+            .generate_human_path(900.0, 640.0, 40.0, &mut rng)
+            .into_iter()
+            .map(|(x, y, _)| (x, y))
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed_replays_same_path() {
+        assert_eq!(session_path(SessionSeed::new(7)), session_path(SessionSeed::new(7)));
+        assert_ne!(session_path(SessionSeed::new(7)), session_path(SessionSeed::new(8)));
+
+        // Same profile, fresh nonce per session: different hands, replayable with the nonce
+        let profile_seed = |nonce| SessionSeed::for_profile("synth_1").with_nonce(nonce);
+        assert_eq!(session_path(profile_seed(3)), session_path(profile_seed(3)));
+        assert_ne!(session_path(profile_seed(3)), session_path(profile_seed(4)));
+    }
+
+    #[test]
+    fn test_profile_personality_is_stable() {
+        assert_eq!(MotorProfile::for_profile("synth_1"), MotorProfile::for_profile("synth_1"));
+        assert_ne!(MotorProfile::for_profile("synth_1"), MotorProfile::for_profile("synth_2"));
+    }
+}
@@ -13,12 +13,294 @@
 /// - Local storage
 /// - Browser fingerprint consistency
 
-use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::circuit_breaker::CircuitBreaker;
+use crate::error::{ChimeraError, Result};
+use crate::ghost_mouse::MotorProfile;
+
+/// Upper bound on any single Redis connect/command (a dead Redis must not hang a mission)
+const REDIS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Sites an aging profile browses, most popular first (visits follow a power law over rank)
+const BROWSING_SITES: &[(&str, &str)] = &[
+    ("https://www.google.com", "Google"),
+    ("https://www.youtube.com", "YouTube"),
+    ("https://www.reddit.com", "Reddit"),
+    ("https://www.amazon.com", "Amazon.com"),
+    ("https://en.wikipedia.org", "Wikipedia"),
+    ("https://mail.google.com", "Gmail"),
+    ("https://www.facebook.com", "Facebook"),
+    ("https://www.cnn.com", "CNN"),
+    ("https://weather.com", "Weather Forecast"),
+    ("https://www.nytimes.com", "The New York Times"),
+    ("https://www.espn.com", "ESPN"),
+    ("https://www.ebay.com", "eBay"),
+    ("https://www.imdb.com", "IMDb"),
+    ("https://www.linkedin.com", "LinkedIn"),
+    ("https://stackoverflow.com", "Stack Overflow"),
+    ("https://www.allrecipes.com", "Allrecipes"),
+];
+
+/// Zipf exponent for site popularity when aging a profile
+const VISIT_POWER_LAW: f64 = 1.1;
+
+/// Cache size (MB) a long-lived profile levels off at
+const CACHE_ASYMPTOTE_MB: f64 = 1500.0;
+
+/// Fraction of the remaining gap to the cache asymptote closed per page visit
+const CACHE_GROWTH_PER_VISIT: f64 = 0.004;
+
+/// Consecutive Redis failures before the breaker opens
+const REDIS_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long to stay on the filesystem before retrying Redis
+const REDIS_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A crashed worker's profile lock expires after this long
+const PROFILE_LOCK_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Waits between rounds when every candidate profile is locked by another worker
+const LOCK_BACKOFF: [Duration; 3] = [
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+];
+
+/// Run a Redis future with `REDIS_TIMEOUT`
+pub(crate) async fn redis_timeout<T>(what: &str, fut: impl std::future::Future<Output = redis::RedisResult<T>>) -> Result<T> {
+    match tokio::time::timeout(REDIS_TIMEOUT, fut).await {
+        Ok(result) => result.map_err(|e| ChimeraError::Profile(format!("Redis {} failed: {}", what, e))),
+        Err(_) => Err(ChimeraError::Profile(format!("Redis {} timed out after {:?}", what, REDIS_TIMEOUT))),
+    }
+}
+
+/// Async Redis profile store holding one pooled connection
+/// 
+/// Cheap to clone: clones share the multiplexed connection and the circuit
+/// breaker, so a caller can take a handle out from under the
+/// `IdentityGrafting` lock and await Redis without holding it.
+#[derive(Clone)]
+pub struct RedisProfileStore {
+    inner: Arc<RedisStoreInner>,
+}
+
+struct RedisStoreInner {
+    client: redis::Client,
+    
+    /// Established lazily, dropped on error so the next call reconnects
+    conn: tokio::sync::Mutex<Option<redis::aio::MultiplexedConnection>>,
+    
+    /// Skips Redis for a cooldown after repeated failures (filesystem keeps working)
+    breaker: CircuitBreaker,
+}
+
+impl RedisProfileStore {
+    /// Create a store for `redis_url` (no I/O until first use)
+    pub fn open(redis_url: &str) -> Result<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| ChimeraError::Profile(format!("Failed to create Redis client: {}", e)))?;
+        
+        Ok(Self {
+            inner: Arc::new(RedisStoreInner {
+                client,
+                conn: tokio::sync::Mutex::new(None),
+                breaker: CircuitBreaker::new("Redis profile store", REDIS_FAILURE_THRESHOLD, REDIS_COOLDOWN),
+            }),
+        })
+    }
+    
+    /// The pooled connection, connecting on first use
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection> {
+        let mut conn = self.inner.conn.lock().await;
+        if let Some(conn) = conn.as_ref() {
+            return Ok(conn.clone());
+        }
+        
+        let fresh = redis_timeout("connect", self.inner.client.get_multiplexed_async_connection()).await?;
+        *conn = Some(fresh.clone());
+        Ok(fresh)
+    }
+    
+    /// Record a call's outcome; failures also drop the pooled connection
+    async fn record<T>(&self, result: &Result<T>) {
+        match result {
+            Ok(_) => self.inner.breaker.record_success(),
+            Err(_) => {
+                self.inner.breaker.record_failure();
+                *self.inner.conn.lock().await = None;
+            }
+        }
+    }
+    
+    /// Every profile stored under `profile:*`
+    /// 
+    /// Profile Swapping: Workers pull a persistent Browser Context (cookies,
+    /// localStorage, and session cache) from Redis on startup.
+    pub async fn load_profiles(&self) -> Result<Vec<SyntheticProfile>> {
+        use redis::AsyncCommands;
+        
+        let result = async {
+            let mut conn = self.connection().await?;
+            
+            // Get all profile keys
+            let keys: Vec<String> = redis_timeout("KEYS", conn.keys("profile:*")).await?;
+            
+            let mut profiles = Vec::with_capacity(keys.len());
+            for key in keys {
+                match redis_timeout("GET", conn.get::<_, String>(&key)).await {
+                    Ok(profile_json) => match serde_json::from_str::<SyntheticProfile>(&profile_json) {
+                        Ok(profile) => {
+                            debug!("Loaded profile from Redis: {}", key);
+                            profiles.push(profile);
+                        }
+                        Err(e) => warn!("Failed to parse profile from Redis key {}: {}", key, e),
+                    },
+                    Err(e) => warn!("Failed to get profile from Redis key {}: {}", key, e),
+                }
+            }
+            Ok(profiles)
+        }.await;
+        
+        self.record(&result).await;
+        result
+    }
+    
+    /// Take the swarm-wide lock on a profile (`SET lock:profile:<id> <token> NX EX`)
+    /// 
+    /// Returns `false` if another worker holds it.
+    pub async fn try_lock_profile(&self, profile_id: &str, token: &str) -> Result<bool> {
+        if !self.inner.breaker.allow() {
+            return Err(ChimeraError::Profile("Redis circuit open".to_string()));
+        }
+        
+        let result = async {
+            let mut conn = self.connection().await?;
+            let reply: Option<String> = redis_timeout(
+                "SET NX",
+                redis::cmd("SET")
+                    .arg(format!("lock:profile:{}", profile_id))
+                    .arg(token)
+                    .arg("NX")
+                    .arg("EX")
+                    .arg(PROFILE_LOCK_TTL.as_secs())
+                    .query_async(&mut conn),
+            ).await?;
+            Ok(reply.is_some())
+        }.await;
+        
+        self.record(&result).await;
+        result
+    }
+    
+    /// Release a profile lock, but only if `token` still owns it
+    pub async fn unlock_profile(&self, profile_id: &str, token: &str) -> Result<()> {
+        // Compare-and-delete: never release a lock that expired and was re-taken
+        let script = redis::Script::new(
+            "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end",
+        );
+        
+        let result = async {
+            let mut conn = self.connection().await?;
+            redis_timeout(
+                "unlock",
+                script
+                    .key(format!("lock:profile:{}", profile_id))
+                    .arg(token)
+                    .invoke_async::<i64>(&mut conn),
+            ).await?;
+            Ok(())
+        }.await;
+        
+        self.record(&result).await;
+        result
+    }
+    
+    /// Store `profile` for the rest of the swarm (expires after 30 days)
+    pub async fn save_profile(&self, profile: &SyntheticProfile) -> Result<()> {
+        use redis::AsyncCommands;
+        
+        // Redis is down: the breaker already logged it once, the filesystem copy suffices
+        if !self.inner.breaker.allow() {
+            debug!("Redis circuit open, skipping save of profile {}", profile.id);
+            return Ok(());
+        }
+        
+        let key = format!("profile:{}", profile.id);
+        let profile_json = serde_json::to_string(profile)
+            .map_err(|e| ChimeraError::Profile(format!("Failed to serialize profile: {}", e)))?;
+        
+        let result = async {
+            let mut conn = self.connection().await?;
+            redis_timeout("SETEX", conn.set_ex::<_, _, ()>(&key, &profile_json, 30 * 24 * 60 * 60)).await
+        }.await;
+        
+        self.record(&result).await;
+        if result.is_ok() {
+            debug!("Saved profile to Redis: {}", key);
+        }
+        result
+    }
+}
+
+/// A checked-out profile, exclusively ours across the swarm until released
+/// 
+/// Dropping the lease releases the Redis lock (on the current runtime, best
+/// effort); `release` does it deterministically. Without Redis there is no
+/// swarm to coordinate with and the lease holds no lock.
+pub struct ProfileLease {
+    profile: SyntheticProfile,
+    
+    /// Store holding our lock, and the token proving we own it
+    lock: Option<(RedisProfileStore, String)>,
+}
+
+impl ProfileLease {
+    pub fn profile(&self) -> &SyntheticProfile {
+        &self.profile
+    }
+    
+    /// Release the swarm lock now
+    pub async fn release(mut self) {
+        if let Some((redis, token)) = self.lock.take() {
+            if let Err(e) = redis.unlock_profile(&self.profile.id, &token).await {
+                warn!("Failed to release lock on profile {} (expires in {:?}): {}", self.profile.id, PROFILE_LOCK_TTL, e);
+            }
+        }
+    }
+}
+
+impl Drop for ProfileLease {
+    fn drop(&mut self) {
+        let Some((redis, token)) = self.lock.take() else {
+            return;
+        };
+        
+        let profile_id = self.profile.id.clone();
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = redis.unlock_profile(&profile_id, &token).await {
+                        warn!("Failed to release lock on profile {} (expires in {:?}): {}", profile_id, PROFILE_LOCK_TTL, e);
+                    }
+                });
+            }
+            Err(_) => warn!("No runtime to release lock on profile {}, it expires in {:?}", profile_id, PROFILE_LOCK_TTL),
+        }
+    }
+}
+
+/// Back off before the next round of lock attempts
+async fn sleep_for_lock(delay: Duration) {
+    debug!("All candidate profiles leased, retrying in {:?}", delay);
+    tokio::time::sleep(delay).await;
+}
+
 /// Synthetic browser profile
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyntheticProfile {
@@ -37,11 +319,157 @@ pub struct SyntheticProfile {
     /// Cookie count
     pub cookie_count: usize,
     
+    /// Cookie jar loaded into Chrome at launch and refreshed on session close
+    #[serde(default)]
+    pub cookies: Vec<CookieRecord>,
+    
     /// Browser fingerprint
     pub fingerprint: BrowserFingerprint,
     
     /// Profile directory path
     pub profile_dir: PathBuf,
+    
+    /// Burned profiles were detected/flagged and must never be grafted again
+    #[serde(default)]
+    pub burned: bool,
+    
+    /// Where this identity "comes from" when entering a site; `{query}` is
+    /// replaced with search terms derived from the target
+    #[serde(default = "default_referrer_pool")]
+    pub referrer_pool: Vec<String>,
+    
+    /// How this identity moves the mouse, when set explicitly; otherwise
+    /// derived from the id (see `motor_profile()`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motor_profile: Option<MotorProfile>,
+}
+
+/// What a Chromium browser advertises through User-Agent Client Hints
+struct ClientHints {
+    brand: &'static str,
+    major: u32,
+    platform: &'static str,
+    platform_version: &'static str,
+    mobile: bool,
+}
+
+impl ClientHints {
+    /// (brand, version) list, in the order Chrome sends it
+    fn brands(&self) -> [(&'static str, String); 3] {
+        [
+            ("Chromium", self.major.to_string()),
+            (self.brand, self.major.to_string()),
+            ("Not-A.Brand", "99".to_string()),
+        ]
+    }
+}
+
+/// Search engines a typical user arrives from
+fn default_referrer_pool() -> Vec<String> {
+    vec![
+        "https://www.google.com/search?q={query}".to_string(),
+        "https://www.google.com/search?q={query}".to_string(),
+        "https://www.bing.com/search?q={query}".to_string(),
+        "https://duckduckgo.com/?q={query}".to_string(),
+    ]
+}
+
+impl SyntheticProfile {
+    /// How this identity moves the mouse: the stored personality, or one
+    /// derived from the id so it stays the same across loads and hosts
+    pub fn motor_profile(&self) -> MotorProfile {
+        self.motor_profile.clone().unwrap_or_else(|| MotorProfile::for_profile(&self.id))
+    }
+    
+    /// Pick a plausible referrer for landing on `target`
+    /// 
+    /// Search templates get the target's host words as the query
+    /// (`shop.example.com/p/123` -> `shop example`), so the referrer reads like
+    /// the search that led here.
+    pub fn pick_referrer(&self, target: &str) -> Option<String> {
+        use rand::seq::SliceRandom;
+        
+        let template = self.referrer_pool.choose(&mut rand::thread_rng())?;
+        let query = url::Url::parse(target)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_string()))
+            .map(|host| {
+                host.split('.')
+                    .filter(|part| !matches!(*part, "www" | "com" | "net" | "org" | "co" | "io"))
+                    .collect::<Vec<_>>()
+                    .join("+")
+            })
+            .unwrap_or_default();
+        
+        Some(template.replace("{query}", &query))
+    }
+}
+
+/// Fonts a stock Windows 10/11 install exposes to pages
+const WINDOWS_FONTS: &[&str] = &[
+    "Arial", "Arial Black", "Bahnschrift", "Calibri", "Cambria", "Cambria Math", "Candara",
+    "Comic Sans MS", "Consolas", "Constantia", "Corbel", "Courier New", "Ebrima",
+    "Franklin Gothic Medium", "Gabriola", "Gadugi", "Georgia", "Impact", "Ink Free",
+    "Javanese Text", "Leelawadee UI", "Lucida Console", "Lucida Sans Unicode", "Malgun Gothic",
+    "Marlett", "Microsoft Himalaya", "Microsoft JhengHei", "Microsoft New Tai Lue",
+    "Microsoft PhagsPa", "Microsoft Sans Serif", "Microsoft Tai Le", "Microsoft YaHei",
+    "Microsoft Yi Baiti", "MingLiU-ExtB", "Mongolian Baiti", "MS Gothic", "MV Boli",
+    "Myanmar Text", "Nirmala UI", "Palatino Linotype", "Segoe MDL2 Assets", "Segoe Print",
+    "Segoe Script", "Segoe UI", "Segoe UI Emoji", "Segoe UI Historic", "Segoe UI Symbol",
+    "SimSun", "Sitka", "Sylfaen", "Symbol", "Tahoma", "Times New Roman", "Trebuchet MS",
+    "Verdana", "Webdings", "Wingdings", "Yu Gothic",
+];
+
+/// Fonts a stock macOS 13/14 install exposes to pages
+const MACOS_FONTS: &[&str] = &[
+    "American Typewriter", "Andale Mono", "Apple Chancery", "Apple Color Emoji",
+    "Apple SD Gothic Neo", "Arial", "Arial Black", "Arial Narrow", "Arial Rounded MT Bold",
+    "Avenir", "Avenir Next", "Baskerville", "Big Caslon", "Bradley Hand", "Brush Script MT",
+    "Chalkboard", "Chalkboard SE", "Chalkduster", "Charter", "Cochin", "Comic Sans MS",
+    "Copperplate", "Courier", "Courier New", "Didot", "Futura", "Geneva", "Georgia",
+    "Gill Sans", "Helvetica", "Helvetica Neue", "Herculanum", "Hoefler Text", "Impact",
+    "Lucida Grande", "Luminari", "Marker Felt", "Menlo", "Monaco", "Noteworthy", "Optima",
+    "Palatino", "Papyrus", "Phosphate", "Rockwell", "Savoye LET", "SignPainter", "Skia",
+    "Snell Roundhand", "Tahoma", "Times", "Times New Roman", "Trattatello", "Trebuchet MS",
+    "Verdana", "Zapfino",
+];
+
+/// Fonts a typical desktop Linux distribution exposes to pages
+const LINUX_FONTS: &[&str] = &[
+    "DejaVu Sans", "DejaVu Sans Mono", "DejaVu Serif", "Liberation Mono", "Liberation Sans",
+    "Liberation Serif", "Noto Color Emoji", "Noto Mono", "Noto Sans", "Noto Serif", "Ubuntu",
+    "Ubuntu Mono",
+];
+
+/// Font families measurable on `os` (as named in `ProfileMetadata::os`)
+pub fn fonts_for_os(os: &str) -> &'static [&'static str] {
+    let os = os.to_ascii_lowercase();
+    if os.contains("windows") {
+        WINDOWS_FONTS
+    } else if os.contains("mac") {
+        MACOS_FONTS
+    } else {
+        LINUX_FONTS
+    }
+}
+
+/// `navigator.plugins` of desktop Chromium since 92: the built-in PDF viewer
+/// under each name sites have historically sniffed for
+const CHROMIUM_PDF_PLUGINS: &[&str] = &[
+    "PDF Viewer", "Chrome PDF Viewer", "Chromium PDF Viewer", "Microsoft Edge PDF Viewer",
+    "WebKit built-in PDF",
+];
+
+/// Plugin names `browser` (as named in `ProfileMetadata::browser`) lists in `navigator.plugins`
+/// 
+/// Chromium browsers report the PDF viewer set; other browsers get none.
+pub fn plugins_for_browser(browser: &str) -> &'static [&'static str] {
+    let browser = browser.to_ascii_lowercase();
+    if browser.contains("chrom") || browser.contains("edge") {
+        CHROMIUM_PDF_PLUGINS
+    } else {
+        &[]
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -68,6 +496,96 @@ pub struct ProfileMetadata {
     pub last_used: u64,
 }
 
+impl ProfileMetadata {
+    /// `Accept-Language` value a browser set to `language` sends ("en-US" -> "en-US,en;q=0.9")
+    pub fn accept_language(&self) -> String {
+        match self.language.split_once('-') {
+            Some((base, _)) => format!("{},{};q=0.9", self.language, base),
+            None => self.language.clone(),
+        }
+    }
+    
+    /// Request headers the claimed browser sends on every request
+    /// 
+    /// `Accept-Language` always; User-Agent Client Hints only for Chromium
+    /// browsers (Firefox and Safari don't send them, so sending them would be
+    /// the mismatch). Chrome derives the hints from `user_agent_metadata`;
+    /// this is what they should read on the wire.
+    pub fn client_headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![("Accept-Language", self.accept_language())];
+        let Some(hints) = self.client_hints() else {
+            return headers;
+        };
+        
+        let brands: Vec<String> = hints.brands()
+            .iter()
+            .map(|(brand, version)| format!("\"{}\";v=\"{}\"", brand, version))
+            .collect();
+        headers.push(("Sec-CH-UA", brands.join(", ")));
+        headers.push(("Sec-CH-UA-Mobile", if hints.mobile { "?1" } else { "?0" }.to_string()));
+        headers.push(("Sec-CH-UA-Platform", format!("\"{}\"", hints.platform)));
+        headers
+    }
+    
+    /// CDP `UserAgentMetadata` for `Network.setUserAgentOverride`
+    /// 
+    /// Drives both the `Sec-CH-UA*` headers and `navigator.userAgentData`, so
+    /// the two agree with each other and with the profile. None for browsers
+    /// without Client Hints.
+    pub fn user_agent_metadata(&self) -> Option<serde_json::Value> {
+        let hints = self.client_hints()?;
+        let brands: Vec<serde_json::Value> = hints.brands()
+            .iter()
+            .map(|(brand, version)| serde_json::json!({ "brand": brand, "version": version }))
+            .collect();
+        Some(serde_json::json!({
+            "brands": brands,
+            "platform": hints.platform,
+            "platformVersion": hints.platform_version,
+            "architecture": if hints.mobile { "" } else { "x86" },
+            "bitness": "64",
+            "model": "",
+            "mobile": hints.mobile,
+        }))
+    }
+    
+    /// Client Hints of the claimed browser, if it's Chromium-based
+    fn client_hints(&self) -> Option<ClientHints> {
+        let browser = self.browser.to_ascii_lowercase();
+        let major = browser.split_whitespace().find_map(|part| part.split('.').next()?.parse::<u32>().ok())?;
+        let brand = if browser.contains("edge") {
+            "Microsoft Edge"
+        } else if browser.contains("chrome") {
+            "Google Chrome"
+        } else {
+            return None;
+        };
+        
+        let os = self.os.to_ascii_lowercase();
+        let (platform, platform_version, mobile) = if os.contains("windows") {
+            // Windows 11 reports 13+ (the UA still says NT 10.0)
+            ("Windows", if os.contains("11") { "15.0.0" } else { "10.0.0" }, false)
+        } else if os.contains("mac") {
+            ("macOS", "14.5.0", false)
+        } else if os.contains("android") {
+            ("Android", "14.0.0", true)
+        } else {
+            ("Linux", "", false)
+        };
+        Some(ClientHints { brand, major, platform, platform_version, mobile })
+    }
+    
+    /// Font families a machine running this profile's OS would have
+    pub fn fonts(&self) -> &'static [&'static str] {
+        fonts_for_os(&self.os)
+    }
+    
+    /// Plugins the claimed browser exposes (see `plugins_for_browser`)
+    pub fn plugins(&self) -> &'static [&'static str] {
+        plugins_for_browser(&self.browser)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VisitRecord {
     pub url: String,
@@ -77,6 +595,61 @@ pub struct VisitRecord {
     pub duration_seconds: u32,
 }
 
+/// One persisted cookie (mirrors CDP's `Network.Cookie`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CookieRecord {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    
+    /// Expiry as Unix seconds; `None` for session cookies
+    pub expires: Option<f64>,
+    
+    pub http_only: bool,
+    pub secure: bool,
+    
+    /// "Strict", "Lax" or "None"
+    pub same_site: Option<String>,
+}
+
+impl CookieRecord {
+    /// Parse a cookie from `Network.getAllCookies`
+    pub fn from_cdp(cookie: &serde_json::Value) -> Option<Self> {
+        // CDP reports session cookies with expires = -1
+        let expires = cookie["expires"].as_f64().filter(|e| *e > 0.0);
+        Some(Self {
+            name: cookie["name"].as_str()?.to_string(),
+            value: cookie["value"].as_str()?.to_string(),
+            domain: cookie["domain"].as_str()?.to_string(),
+            path: cookie["path"].as_str().unwrap_or("/").to_string(),
+            expires,
+            http_only: cookie["httpOnly"].as_bool().unwrap_or(false),
+            secure: cookie["secure"].as_bool().unwrap_or(false),
+            same_site: cookie["sameSite"].as_str().map(|s| s.to_string()),
+        })
+    }
+    
+    /// Cookie param for `Network.setCookies`
+    pub fn to_cdp(&self) -> serde_json::Value {
+        let mut param = serde_json::json!({
+            "name": self.name,
+            "value": self.value,
+            "domain": self.domain,
+            "path": self.path,
+            "httpOnly": self.http_only,
+            "secure": self.secure,
+        });
+        if let Some(expires) = self.expires {
+            param["expires"] = serde_json::json!(expires);
+        }
+        if let Some(same_site) = &self.same_site {
+            param["sameSite"] = serde_json::json!(same_site);
+        }
+        param
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BrowserFingerprint {
     /// User agent
@@ -97,8 +670,95 @@ pub struct BrowserFingerprint {
     /// Hardware concurrency
     pub hardware_concurrency: u32,
     
-    /// Device memory (GB)
-    pub device_memory: u32,
+    /// Device memory (GB); `None` where the browser doesn't expose `navigator.deviceMemory` (Safari, Firefox)
+    pub device_memory: Option<u32>,
+    
+    /// WebGL `UNMASKED_VENDOR_WEBGL`
+    #[serde(default = "default_gpu_vendor")]
+    pub gpu_vendor: String,
+    
+    /// WebGL `UNMASKED_RENDERER_WEBGL`
+    #[serde(default = "default_gpu_renderer")]
+    pub gpu_renderer: String,
+}
+
+/// GPU reported by profiles saved before the fingerprint carried one
+fn default_gpu_vendor() -> String {
+    "Intel Inc.".to_string()
+}
+
+fn default_gpu_renderer() -> String {
+    "Intel(R) Iris(R) Xe Graphics".to_string()
+}
+
+/// (vendor, renderer) pairs Chrome reports through ANGLE on Windows
+const WINDOWS_GPUS: &[(&str, &str)] = &[
+    ("Google Inc. (Intel)", "ANGLE (Intel, Intel(R) UHD Graphics 620 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (Intel)", "ANGLE (Intel, Intel(R) Iris(R) Xe Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (NVIDIA)", "ANGLE (NVIDIA, NVIDIA GeForce GTX 1650 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (NVIDIA)", "ANGLE (NVIDIA, NVIDIA GeForce RTX 3060 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (NVIDIA)", "ANGLE (NVIDIA, NVIDIA GeForce RTX 4070 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (AMD)", "ANGLE (AMD, AMD Radeon(TM) Graphics Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+    ("Google Inc. (AMD)", "ANGLE (AMD, AMD Radeon RX 6600 Direct3D11 vs_5_0 ps_5_0, D3D11)"),
+];
+
+/// (vendor, renderer) pairs Chrome reports on Apple Silicon Macs
+const MACOS_GPUS: &[(&str, &str)] = &[
+    ("Google Inc. (Apple)", "ANGLE (Apple, ANGLE Metal Renderer: Apple M1, Unspecified Version)"),
+    ("Google Inc. (Apple)", "ANGLE (Apple, ANGLE Metal Renderer: Apple M1 Pro, Unspecified Version)"),
+    ("Google Inc. (Apple)", "ANGLE (Apple, ANGLE Metal Renderer: Apple M2, Unspecified Version)"),
+    ("Google Inc. (Apple)", "ANGLE (Apple, ANGLE Metal Renderer: Apple M3, Unspecified Version)"),
+];
+
+/// (vendor, renderer) pairs Chrome reports on desktop Linux (Mesa)
+const LINUX_GPUS: &[(&str, &str)] = &[
+    ("Google Inc. (Intel)", "ANGLE (Intel, Mesa Intel(R) UHD Graphics 620 (KBL GT2), OpenGL 4.6)"),
+    ("Google Inc. (Intel)", "ANGLE (Intel, Mesa Intel(R) Xe Graphics (TGL GT2), OpenGL 4.6)"),
+    ("Google Inc. (AMD)", "ANGLE (AMD, AMD Radeon Graphics (radeonsi, renoir, LLVM 15.0.7, DRM 3.49), OpenGL 4.6)"),
+];
+
+/// GPUs plausible for `os` (as named in `ProfileMetadata::os`)
+fn gpus_for_os(os: &str) -> &'static [(&'static str, &'static str)] {
+    let os = os.to_ascii_lowercase();
+    if os.contains("windows") {
+        WINDOWS_GPUS
+    } else if os.contains("mac") {
+        MACOS_GPUS
+    } else {
+        LINUX_GPUS
+    }
+}
+
+/// Logical core counts of consumer machines
+const CPU_CORES: &[u32] = &[4, 8, 12, 16];
+
+/// RAM sizes (GB) of consumer machines
+const DEVICE_MEMORY_GB: &[u32] = &[4, 8, 16];
+
+/// Cores and RAM of a plausible consumer machine, as `browser` (as named in
+/// `ProfileMetadata::browser`) reports them
+/// 
+/// Big core counts come with at least half as many GB, so no 16-core/4 GB
+/// oddities. Memory is only reported by Chromium browsers: Safari and Firefox
+/// don't expose `deviceMemory`, whatever OS they run on.
+fn hardware_for_browser(browser: &str) -> (u32, Option<u32>) {
+    use rand::seq::SliceRandom;
+    
+    let mut rng = rand::thread_rng();
+    let cores = *CPU_CORES.choose(&mut rng).unwrap_or(&8);
+    let browser = browser.to_ascii_lowercase();
+    if !(browser.contains("chrom") || browser.contains("edge")) {
+        return (cores, None);
+    }
+    let memory = DEVICE_MEMORY_GB
+        .iter()
+        .filter(|&&gb| gb >= cores / 2)
+        .copied()
+        .collect::<Vec<_>>()
+        .choose(&mut rng)
+        .copied()
+        .unwrap_or(8);
+    (cores, Some(memory))
 }
 
 /// Identity Grafting Manager
@@ -122,12 +782,15 @@ pub struct IdentityGrafting {
     /// Profile rotation index
     rotation_index: usize,
     
-    /// Redis connection URL (optional - for swarm profile sharing)
-    redis_url: Option<String>,
+    /// Redis store (optional - for swarm profile sharing)
+    redis: Option<RedisProfileStore>,
 }
 
 impl IdentityGrafting {
-    /// Create a new Identity Grafting manager
+    /// Create a new Identity Grafting manager from the filesystem store
+    /// 
+    /// Does no Redis I/O: call `load_profiles_async` afterwards, or use `open`,
+    /// to pull the swarm's profiles.
     /// 
     /// Args:
     ///   - profiles_dir: Directory for filesystem storage (fallback)
@@ -137,20 +800,20 @@ impl IdentityGrafting {
         
         // Create directory if it doesn't exist
         std::fs::create_dir_all(&profiles_dir)
-            .context("Failed to create profiles directory")?;
+            .map_err(|e| ChimeraError::Profile(format!("Failed to create profiles directory: {}", e)))?;
         
+        let redis = redis_url.as_deref().map(RedisProfileStore::open).transpose()?;
         let mut manager = Self {
             profiles_dir,
             profiles: HashMap::new(),
             rotation_index: 0,
-            redis_url,
+            redis,
         };
         
-        // Load existing profiles (from Redis if available, otherwise filesystem)
         manager.load_profiles()?;
         
         info!("Identity Grafting initialized with {} profiles", manager.profiles.len());
-        if manager.redis_url.is_some() {
+        if manager.redis.is_some() {
             info!("Profile storage: Redis (swarm sharing enabled)");
         } else {
             info!("Profile storage: Filesystem (local only)");
@@ -159,38 +822,31 @@ impl IdentityGrafting {
         Ok(manager)
     }
     
-    /// Load profiles from storage
-    /// 
-    /// Priority:
-    /// 1. Redis (if redis_url is set) - for swarm profile sharing
-    /// 2. Filesystem (fallback) - for local development
-    fn load_profiles(&mut self) -> Result<()> {
-        // Try Redis first if configured
-        if let Some(ref redis_url) = self.redis_url {
-            match self.load_profiles_from_redis(redis_url) {
-                Ok(count) => {
-                    if count > 0 {
-                        info!("Loaded {} profiles from Redis", count);
-                        return Ok(());
-                    }
-                    // Fall through to filesystem if Redis is empty
-                    debug!("Redis has no profiles, falling back to filesystem");
-                }
-                Err(e) => {
-                    warn!("Failed to load profiles from Redis: {}, falling back to filesystem", e);
-                    // Fall through to filesystem
-                }
+    /// Create a manager and pull the swarm's profiles from Redis
+    pub async fn open(profiles_dir: impl AsRef<Path>, redis_url: Option<String>) -> Result<Self> {
+        let mut manager = Self::new(profiles_dir, redis_url)?;
+        if manager.redis.is_some() {
+            if let Err(e) = manager.load_profiles_async().await {
+                warn!("Failed to load profiles from Redis: {}, using filesystem profiles", e);
             }
         }
-        
-        // Load from filesystem (fallback or if Redis not configured)
+        Ok(manager)
+    }
+    
+    /// Handle to the Redis store, for awaiting saves without holding a lock on `self`
+    pub fn redis_store(&self) -> Option<RedisProfileStore> {
+        self.redis.clone()
+    }
+    
+    /// Load profiles from the filesystem, creating defaults on first run
+    fn load_profiles(&mut self) -> Result<()> {
         let profiles_file = self.profiles_dir.join("profiles.json");
         if profiles_file.exists() {
             let content = std::fs::read_to_string(&profiles_file)
-                .context("Failed to read profiles file")?;
+                .map_err(|e| ChimeraError::Profile(format!("Failed to read profiles file: {}", e)))?;
             
             let profiles: Vec<SyntheticProfile> = serde_json::from_str(&content)
-                .context("Failed to parse profiles file")?;
+                .map_err(|e| ChimeraError::Profile(format!("Failed to parse profiles file: {}", e)))?;
             
             for profile in profiles {
                 self.profiles.insert(profile.id.clone(), profile);
@@ -205,98 +861,35 @@ impl IdentityGrafting {
         Ok(())
     }
     
-    /// Load profiles from Redis
+    /// Load profiles from Redis, replacing local copies with the same id
     /// 
     /// This implements "Lived-In" Identity Grafting by pulling synthetic profiles
     /// (cookies, history, cache) from Redis so every worker arrives with a "history."
-    /// 
-    /// Profile Swapping: Workers pull a persistent Browser Context (cookies,
-    /// localStorage, and session cache) from Redis on startup.
-    fn load_profiles_from_redis(&mut self, redis_url: &str) -> Result<usize> {
-        use redis::AsyncCommands;
-        
-        // Create Redis client
-        let client = redis::Client::open(redis_url)
-            .context("Failed to create Redis client")?;
-        
-        // Get async connection (requires tokio runtime)
-        let rt = tokio::runtime::Handle::try_current()
-            .context("Redis requires tokio runtime")?;
+    pub async fn load_profiles_async(&mut self) -> Result<usize> {
+        let Some(redis) = self.redis.clone() else {
+            return Ok(0);
+        };
         
-        let count = rt.block_on(async {
-            let mut conn = client.get_async_connection().await
-                .context("Failed to connect to Redis")?;
-            
-            // Get all profile keys
-            let keys: Vec<String> = conn.keys("profile:*").await
-                .context("Failed to get profile keys from Redis")?;
-            
-            let mut loaded = 0;
-            for key in keys {
-                match conn.get::<_, String>(&key).await {
-                    Ok(profile_json) => {
-                        match serde_json::from_str::<SyntheticProfile>(&profile_json) {
-                            Ok(profile) => {
-                                self.profiles.insert(profile.id.clone(), profile);
-                                loaded += 1;
-                                debug!("Loaded profile from Redis: {}", key);
-                            }
-                            Err(e) => {
-                                warn!("Failed to parse profile from Redis key {}: {}", key, e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        warn!("Failed to get profile from Redis key {}: {}", key, e);
-                    }
-                }
-            }
-            
-            Ok::<usize, anyhow::Error>(loaded)
-        })?;
+        let profiles = redis.load_profiles().await?;
+        let count = profiles.len();
+        for profile in profiles {
+            self.profiles.insert(profile.id.clone(), profile);
+        }
         
+        info!("Loaded {} profiles from Redis", count);
         Ok(count)
     }
     
-    /// Save profile to Redis (for swarm sharing)
+    /// Save a profile to Redis (for swarm sharing); a no-op without Redis
     /// 
     /// Pre-Warming: If a worker encounters a "New User" flag, it must push
     /// its current state to Redis so that subsequent workers can inherit
     /// that "warmed" session.
-    /// 
-    /// When a profile is used, update it in Redis so other workers can benefit
-    /// from the "lived-in" history (cookies, cache, visit history).
-    fn save_profile_to_redis(&self, profile: &SyntheticProfile) -> Result<()> {
-        use redis::AsyncCommands;
-        
-        let redis_url = self.redis_url.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Redis URL not configured"))?;
-        
-        // Create Redis client
-        let client = redis::Client::open(redis_url)
-            .context("Failed to create Redis client")?;
-        
-        // Get async connection
-        let rt = tokio::runtime::Handle::try_current()
-            .context("Redis requires tokio runtime")?;
-        
-        rt.block_on(async {
-            let mut conn = client.get_async_connection().await
-                .context("Failed to connect to Redis")?;
-            
-            let key = format!("profile:{}", profile.id);
-            let profile_json = serde_json::to_string(profile)
-                .context("Failed to serialize profile")?;
-            
-            // Save profile to Redis with expiration (30 days)
-            conn.set_ex::<_, _, ()>(&key, &profile_json, 30 * 24 * 60 * 60).await
-                .context("Failed to save profile to Redis")?;
-            
-            debug!("Saved profile to Redis: {}", key);
-            Ok::<(), anyhow::Error>(())
-        })?;
-        
-        Ok(())
+    pub async fn save_profile_async(&self, profile: &SyntheticProfile) -> Result<()> {
+        match &self.redis {
+            Some(redis) => redis.save_profile(profile).await,
+            None => Ok(()),
+        }
     }
     
     /// Create default synthetic profiles
@@ -372,7 +965,7 @@ impl IdentityGrafting {
         
         let profile_dir = PathBuf::from(format!("/tmp/chimera-profiles/{}", id));
         std::fs::create_dir_all(&profile_dir)
-            .context("Failed to create profile directory")?;
+            .map_err(|e| ChimeraError::Profile(format!("Failed to create profile directory: {}", e)))?;
         
         Ok(SyntheticProfile {
             id: id.to_string(),
@@ -388,8 +981,12 @@ impl IdentityGrafting {
             visit_history,
             cache_size_mb: 500,
             cookie_count: 42,
+            cookies: Vec::new(),
             fingerprint: Self::generate_fingerprint(os, browser, viewport),
             profile_dir,
+            burned: false,
+            referrer_pool: default_referrer_pool(),
+            motor_profile: None,
         })
     }
     
@@ -399,6 +996,8 @@ impl IdentityGrafting {
         browser: &str,
         viewport: (u32, u32),
     ) -> BrowserFingerprint {
+        use rand::seq::SliceRandom;
+        
         let user_agent = match (os, browser) {
             ("Windows 11", "Chrome 124") => {
                 "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36"
@@ -414,34 +1013,156 @@ impl IdentityGrafting {
             }
         };
         
+        // Distinct GPUs across the fleet; one shared renderer string correlates every session
+        let (gpu_vendor, gpu_renderer) = gpus_for_os(os)
+            .choose(&mut rand::thread_rng())
+            .copied()
+            .unwrap_or(("Intel Inc.", "Intel(R) Iris(R) Xe Graphics"));
+        
+        // Identical core/RAM counts across the fleet are as correlating as a shared GPU
+        let (hardware_concurrency, device_memory) = hardware_for_browser(browser);
+        
         BrowserFingerprint {
             user_agent: user_agent.to_string(),
             screen_resolution: viewport,
             color_depth: 24,
             timezone_offset: -300, // EST
             platform: os.to_string(),
-            hardware_concurrency: 8,
-            device_memory: 8,
+            hardware_concurrency,
+            device_memory,
+            gpu_vendor: gpu_vendor.to_string(),
+            gpu_renderer: gpu_renderer.to_string(),
         }
     }
     
+    /// Check out a profile no other swarm worker is using
+    /// 
+    /// Takes `lock:profile:<id>` in Redis (`SET NX EX`). With a `profile_id`,
+    /// waits with backoff for that profile and errors if it stays locked (or
+    /// was burned - a detected identity is never grafted again).
+    /// Otherwise rotates through the non-burned profiles, retries with backoff
+    /// when all are taken, and finally synthesizes a fresh identity.
+    /// 
+    /// The lock is held until the lease is released or dropped. If Redis is
+    /// unreachable the profile is leased without a lock (local-only mode).
+    pub async fn acquire_profile(identity: &Arc<Mutex<Self>>, profile_id: Option<&str>) -> Result<ProfileLease> {
+        let (candidates, redis) = {
+            let mut manager = identity.lock().unwrap();
+            let candidates = match profile_id {
+                Some(id) => {
+                    let profile = manager.get_profile(Some(id))?;
+                    if profile.burned {
+                        return Err(ChimeraError::Profile(format!("Profile {} is burned", id)));
+                    }
+                    vec![profile.clone()]
+                }
+                None => manager.rotation_order(),
+            };
+            (candidates, manager.redis_store())
+        };
+        
+        let Some(redis) = redis else {
+            let profile = match candidates.into_iter().next() {
+                Some(profile) => profile,
+                None => identity.lock().unwrap().synthesize_profile()?,
+            };
+            return Ok(ProfileLease { profile, lock: None });
+        };
+        
+        let token = format!("{}:{:016x}", std::process::id(), rand::random::<u64>());
+        for backoff in std::iter::once(None).chain(LOCK_BACKOFF.iter().map(Some)) {
+            if let Some(delay) = backoff {
+                sleep_for_lock(*delay).await;
+            }
+            
+            for profile in &candidates {
+                match redis.try_lock_profile(&profile.id, &token).await {
+                    Ok(true) => {
+                        debug!("🔒 Leased profile {}", profile.id);
+                        return Ok(ProfileLease {
+                            profile: profile.clone(),
+                            lock: Some((redis, token)),
+                        });
+                    }
+                    Ok(false) => debug!("Profile {} is leased by another worker", profile.id),
+                    Err(e) => {
+                        warn!("Redis unavailable, grafting {} without a swarm lock: {}", profile.id, e);
+                        return Ok(ProfileLease { profile: profile.clone(), lock: None });
+                    }
+                }
+            }
+        }
+        
+        if let Some(id) = profile_id {
+            return Err(ChimeraError::Profile(format!("Profile {} is in use by another worker", id)));
+        }
+        
+        // Every identity is busy: mint a new one rather than share
+        let profile = identity.lock().unwrap().synthesize_profile()?;
+        if let Err(e) = redis.save_profile(&profile).await {
+            warn!("Failed to share synthesized profile {} (non-fatal): {}", profile.id, e);
+        }
+        let locked = redis.try_lock_profile(&profile.id, &token).await.unwrap_or(false);
+        Ok(ProfileLease {
+            profile,
+            lock: locked.then_some((redis, token)),
+        })
+    }
+    
+    /// Non-burned profiles, starting at the rotation index
+    fn rotation_order(&mut self) -> Vec<SyntheticProfile> {
+        let mut profiles: Vec<SyntheticProfile> = self.profiles
+            .values()
+            .filter(|p| !p.burned)
+            .cloned()
+            .collect();
+        if !profiles.is_empty() {
+            let start = self.rotation_index % profiles.len();
+            profiles.rotate_left(start);
+            self.rotation_index += 1;
+        }
+        profiles
+    }
+    
+    /// Create (and persist) a new identity modelled on an existing one
+    fn synthesize_profile(&mut self) -> Result<SyntheticProfile> {
+        let (os, browser, viewport) = self.profiles
+            .values()
+            .find(|p| !p.burned)
+            .or_else(|| self.profiles.values().next())
+            .map(|p| (p.metadata.os.clone(), p.metadata.browser.clone(), p.metadata.viewport))
+            .unwrap_or_else(|| ("Windows 11".to_string(), "Chrome 124".to_string(), (1920, 1080)));
+        
+        let id = format!("synth_{:012x}", rand::random::<u64>() & 0xffff_ffff_ffff);
+        let profile = Self::create_profile(&id, &os, &browser, viewport)?;
+        info!("🧬 Synthesized profile {} ({} / {}), all others were leased", id, os, browser);
+        
+        self.profiles.insert(id, profile.clone());
+        self.save_profiles()?;
+        Ok(profile)
+    }
+    
     /// Get a profile for use (with rotation)
     pub fn get_profile(&mut self, profile_id: Option<&str>) -> Result<&SyntheticProfile> {
         let profile = if let Some(id) = profile_id {
             self.profiles.get(id)
-                .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", id))?
+                .ok_or_else(|| ChimeraError::Profile(format!("Profile not found: {}", id)))?
         } else {
-            // Rotate through available profiles
-            let profile_ids: Vec<&String> = self.profiles.keys().collect();
+            // Rotate through available (non-burned) profiles
+            let profile_ids: Vec<&String> = self.profiles
+                .iter()
+                .filter(|(_, p)| !p.burned)
+                .map(|(id, _)| id)
+                .collect();
             if profile_ids.is_empty() {
-                anyhow::bail!("No profiles available");
+                return Err(ChimeraError::Profile("No profiles available".to_string()));
             }
             
             let index = self.rotation_index % profile_ids.len();
             self.rotation_index += 1;
             
             self.profiles.get(profile_ids[index])
-                .ok_or_else(|| anyhow::anyhow!("Profile rotation failed"))?
+                .ok_or_else(|| ChimeraError::Profile("Profile rotation failed".to_string()))?
         };
         
         // Update last used timestamp
@@ -453,7 +1174,7 @@ impl IdentityGrafting {
     /// Get profile directory for browser launch
     pub fn get_profile_dir(&self, profile_id: &str) -> Result<PathBuf> {
         let profile = self.profiles.get(profile_id)
-            .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", profile_id))?;
+            .ok_or_else(|| ChimeraError::Profile(format!("Profile not found: {}", profile_id)))?;
         
         Ok(profile.profile_dir.clone())
     }
@@ -462,42 +1183,317 @@ impl IdentityGrafting {
     fn save_profiles(&self) -> Result<()> {
         let profiles: Vec<&SyntheticProfile> = self.profiles.values().collect();
         let content = serde_json::to_string_pretty(&profiles)
-            .context("Failed to serialize profiles")?;
+            .map_err(|e| ChimeraError::Profile(format!("Failed to serialize profiles: {}", e)))?;
         
         let profiles_file = self.profiles_dir.join("profiles.json");
         std::fs::write(&profiles_file, content)
-            .context("Failed to write profiles file")?;
+            .map_err(|e| ChimeraError::Profile(format!("Failed to write profiles file: {}", e)))?;
         
         Ok(())
     }
     
-    /// Update profile after use
+    /// Mark a profile as burned (detected) so it is never grafted again
     /// 
-    /// Updates the profile's last_used timestamp and increments usage metrics.
-    /// If Redis is configured, also saves the updated profile to Redis for swarm sharing.
-    pub fn update_profile(&mut self, profile_id: &str) -> Result<()> {
-        if let Some(profile) = self.profiles.get_mut(profile_id) {
-            let now = std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            
-            profile.metadata.last_used = now;
-            // Increment cache size, cookie count, etc.
-            profile.cache_size_mb = profile.cache_size_mb.saturating_add(1);
-            profile.cookie_count = profile.cookie_count.saturating_add(1);
-            
-            // Save to Redis if configured (for swarm sharing)
-            if self.redis_url.is_some() {
-                if let Err(e) = self.save_profile_to_redis(profile) {
-                    warn!("Failed to save profile to Redis (non-fatal): {}", e);
+    /// The burned flag is persisted to the filesystem. Push the returned
+    /// profile with `save_profile_async` so the rest of the swarm stops using
+    /// the identity.
+    pub fn mark_burned(&mut self, profile_id: &str) -> Result<SyntheticProfile> {
+        let profile = self.profiles.get_mut(profile_id)
+            .ok_or_else(|| ChimeraError::Profile(format!("Profile not found: {}", profile_id)))?;
+        
+        profile.burned = true;
+        warn!("🔥 Profile {} marked as burned", profile_id);
+        let profile = profile.clone();
+        
+        self.save_profiles()?;
+        Ok(profile)
+    }
+    
+    /// Replace a profile's cookie jar with what the browser held at session close
+    /// 
+    /// Persists to the filesystem and returns the updated profile for
+    /// `save_profile_async`.
+    pub fn update_cookies(&mut self, profile_id: &str, cookies: Vec<CookieRecord>) -> Result<SyntheticProfile> {
+        let Some(profile) = self.profiles.get_mut(profile_id) else {
+            return Err(ChimeraError::Profile(format!("Profile not found: {}", profile_id)));
+        };
+        
+        debug!("Persisting {} cookies to profile {}", cookies.len(), profile_id);
+        profile.cookie_count = cookies.len();
+        profile.cookies = cookies;
+        profile.metadata.last_used = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        let profile = profile.clone();
+        
+        self.save_profiles()?;
+        Ok(profile)
+    }
+    
+    /// Simulate `days` of ordinary browsing ending now
+    /// 
+    /// Each day has zero to a few sessions at waking hours (in the profile's
+    /// timezone, days aligned to its midnight); each session visits
+    /// a handful of sites picked with Zipf (power-law) popularity, so a few
+    /// sites dominate the history and the tail is visited rarely. Durations are
+    /// log-normal. The cache grows toward `CACHE_ASYMPTOTE_MB`, cookies past
+    /// their expiry are dropped, and `created_at` is pushed back if the
+    /// simulated history predates it. `seed` makes the simulation reproducible.
+    pub fn age_profile(profile: &mut SyntheticProfile, days: u32, seed: Option<u64>) {
+        use rand::rngs::StdRng;
+        use rand::{Rng, SeedableRng};
+        use rand_distr::{Distribution, LogNormal, WeightedIndex};
+        
+        let mut rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // Days run midnight to midnight in the profile's timezone (offset in minutes east of UTC)
+        let offset = profile.fingerprint.timezone_offset as i64 * 60;
+        let local_midnight = |t: u64| ((t as i64 + offset).div_euclid(86400) * 86400 - offset).max(0) as u64;
+        let start = local_midnight(now.saturating_sub(days as u64 * 86400));
+        
+        let popularity = WeightedIndex::new(
+            (1..=BROWSING_SITES.len()).map(|rank| 1.0 / (rank as f64).powf(VISIT_POWER_LAW)),
+        )
+        .expect("non-empty site pool");
+        // Median ~2.5 minutes on a page, long tail for videos and articles
+        let durations = LogNormal::new(150f64.ln(), 1.0).expect("finite log-normal parameters");
+        
+        let mut visits = 0u32;
+        let mut new_sites = 0usize;
+        for day in 0..days as u64 {
+            // Some days the browser isn't opened at all
+            let sessions = if rng.gen_bool(0.2) { 0 } else { rng.gen_range(1..=3) };
+            for _ in 0..sessions {
+                // Waking hours: 08:00-23:00
+                let mut timestamp = start + day * 86400 + rng.gen_range(8 * 3600..23 * 3600);
+                for _ in 0..rng.gen_range(1..=6) {
+                    // A late session can run into the present; history stops at now
+                    if timestamp > now {
+                        break;
+                    }
+                    let (url, title) = BROWSING_SITES[popularity.sample(&mut rng)];
+                    let duration = durations.sample(&mut rng).min(3.0 * 3600.0) as u32;
+                    
+                    match profile.visit_history.iter_mut().find(|v| v.url == url) {
+                        Some(record) => {
+                            record.visit_count += 1;
+                            record.last_visit = record.last_visit.max(timestamp);
+                            record.duration_seconds = record.duration_seconds.saturating_add(duration);
+                        }
+                        None => {
+                            new_sites += 1;
+                            profile.visit_history.push(VisitRecord {
+                                url: url.to_string(),
+                                title: title.to_string(),
+                                visit_count: 1,
+                                last_visit: timestamp,
+                                duration_seconds: duration,
+                            });
+                        }
+                    }
+                    visits += 1;
+                    timestamp += duration as u64 + rng.gen_range(5..60);
                 }
             }
-            
-            // Also save to filesystem (backup)
-            self.save_profiles()?;
         }
         
-        Ok(())
+        // Cache fills quickly at first, then levels off as old entries are evicted
+        let remaining = (CACHE_ASYMPTOTE_MB - profile.cache_size_mb as f64).max(0.0);
+        let grown = remaining * (1.0 - (1.0 - CACHE_GROWTH_PER_VISIT).powi(visits as i32));
+        profile.cache_size_mb += grown.round() as u64;
+        
+        // Expired cookies are gone; newly visited sites leave a few behind
+        let before = profile.cookies.len();
+        profile.cookies.retain(|c| c.expires.map_or(true, |e| e > now as f64));
+        let expired = before - profile.cookies.len();
+        profile.cookie_count = (profile.cookie_count + new_sites * 3).saturating_sub(expired);
+        
+        if let Some(last) = profile.visit_history.iter().map(|v| v.last_visit).max() {
+            profile.metadata.last_used = profile.metadata.last_used.max(last.min(now));
+        }
+        profile.metadata.created_at = profile.metadata.created_at.min(start);
+        
+        debug!(
+            "Aged profile {} by {} days: {} visits, {} new sites, cache {}MB, {} cookies expired",
+            profile.id, days, visits, new_sites, profile.cache_size_mb, expired
+        );
+    }
+    
+    /// Update profile after use
+    /// 
+    /// Ages the profile by the whole days since it was last used (see
+    /// `age_profile`) and stamps last_used, saving to the filesystem. Push the
+    /// returned profile with `save_profile_async` for swarm sharing.
+    pub fn update_profile(&mut self, profile_id: &str) -> Result<Option<SyntheticProfile>> {
+        let Some(profile) = self.profiles.get_mut(profile_id) else {
+            return Ok(None);
+        };
+        
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        
+        // Life between runs: the identity kept browsing while we weren't using it
+        let idle_days = now.saturating_sub(profile.metadata.last_used) / 86400;
+        if idle_days > 0 {
+            Self::age_profile(profile, idle_days.min(365) as u32, None);
+        }
+        profile.metadata.last_used = now;
+        let profile = profile.clone();
+        
+        self.save_profiles()?;
+        Ok(Some(profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn metadata(os: &str) -> ProfileMetadata {
+        ProfileMetadata {
+            os: os.to_string(),
+            browser: "Chrome 124".to_string(),
+            viewport: (1920, 1080),
+            timezone: "America/New_York".to_string(),
+            language: "en-US".to_string(),
+            created_at: 0,
+            last_used: 0,
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_burned_profile_is_never_leased() {
+        let dir = std::env::temp_dir().join(format!("chimera-profiles-test-{}", std::process::id()));
+        let identity = Arc::new(Mutex::new(IdentityGrafting::new(&dir, None).unwrap()));
+        identity.lock().unwrap().mark_burned("windows_chrome_124").unwrap();
+        
+        let burned = IdentityGrafting::acquire_profile(&identity, Some("windows_chrome_124")).await;
+        let other = IdentityGrafting::acquire_profile(&identity, Some("mac_safari_17")).await;
+        std::fs::remove_dir_all(&dir).unwrap();
+        
+        assert!(matches!(burned, Err(ChimeraError::Profile(_))));
+        assert_eq!(other.unwrap().profile.id, "mac_safari_17");
+    }
+    
+    #[test]
+    fn test_client_headers_match_claimed_browser() {
+        let headers = metadata("Windows 11").client_headers();
+        assert_eq!(headers, vec![
+            ("Accept-Language", "en-US,en;q=0.9".to_string()),
+            ("Sec-CH-UA", r#""Chromium";v="124", "Google Chrome";v="124", "Not-A.Brand";v="99""#.to_string()),
+            ("Sec-CH-UA-Mobile", "?0".to_string()),
+            ("Sec-CH-UA-Platform", r#""Windows""#.to_string()),
+        ]);
+        
+        let firefox = ProfileMetadata { browser: "Firefox 120".to_string(), ..metadata("Linux") };
+        assert_eq!(firefox.client_headers().len(), 1, "Firefox must not send Client Hints");
+        assert!(firefox.user_agent_metadata().is_none());
+    }
+    
+    #[test]
+    fn test_user_agent_metadata_matches_client_headers() {
+        let ua_metadata = metadata("macOS 14").user_agent_metadata().unwrap();
+        assert_eq!(ua_metadata["platform"], "macOS");
+        assert_eq!(ua_metadata["mobile"], false);
+        assert_eq!(ua_metadata["brands"][1], serde_json::json!({ "brand": "Google Chrome", "version": "124" }));
+        assert_eq!(ua_metadata["brands"].as_array().unwrap().len(), 3);
+    }
+    
+    #[test]
+    fn test_plugins_follow_claimed_browser() {
+        let chrome = metadata("Windows 11");
+        assert_eq!(chrome.plugins().len(), 5);
+        assert_eq!(chrome.plugins()[0], "PDF Viewer");
+        assert_eq!(plugins_for_browser("chrome124"), chrome.plugins());
+        
+        let firefox = ProfileMetadata { browser: "Firefox 120".to_string(), ..metadata("Linux") };
+        assert!(firefox.plugins().is_empty());
+        assert!(plugins_for_browser("firefox120").is_empty());
+    }
+    
+    #[test]
+    fn test_age_profile_is_seeded_and_power_law() {
+        let fresh = || SyntheticProfile {
+            id: "aging".to_string(),
+            metadata: metadata("Windows 11"),
+            visit_history: Vec::new(),
+            cache_size_mb: 0,
+            cookie_count: 0,
+            cookies: vec![CookieRecord {
+                name: "old".to_string(),
+                value: "1".to_string(),
+                domain: ".example.com".to_string(),
+                path: "/".to_string(),
+                expires: Some(1.0),
+                http_only: false,
+                secure: true,
+                same_site: None,
+            }],
+            fingerprint: IdentityGrafting::generate_fingerprint("Windows 11", "Chrome 124", (1920, 1080)),
+            profile_dir: PathBuf::from("/tmp/chimera-profiles/aging"),
+            burned: false,
+            referrer_pool: default_referrer_pool(),
+            motor_profile: None,
+        };
+        
+        let mut a = fresh();
+        let mut b = fresh();
+        IdentityGrafting::age_profile(&mut a, 90, Some(7));
+        IdentityGrafting::age_profile(&mut b, 90, Some(7));
+        
+        let summary = |p: &SyntheticProfile| p.visit_history.iter()
+            .map(|v| (v.url.clone(), v.visit_count, v.duration_seconds))
+            .collect::<Vec<_>>();
+        assert_eq!(summary(&a), summary(&b));
+        
+        // The most popular site dominates the least popular one
+        let count = |url: &str| a.visit_history.iter().find(|v| v.url == url).map_or(0, |v| v.visit_count);
+        assert!(count(BROWSING_SITES[0].0) > 3 * count(BROWSING_SITES[BROWSING_SITES.len() - 1].0));
+        
+        assert!(a.cache_size_mb > 0 && (a.cache_size_mb as f64) < CACHE_ASYMPTOTE_MB);
+        assert!(a.cookies.is_empty(), "expired cookie survived aging");
+        
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+        assert!(a.visit_history.iter().all(|v| v.last_visit <= now), "visit in the future");
+    }
+    
+    #[test]
+    fn test_macos_fingerprint_reports_apple_gpu() {
+        let fingerprint = IdentityGrafting::generate_fingerprint("macOS 14", "Safari 17", (1440, 900));
+        assert_eq!(fingerprint.gpu_vendor, "Google Inc. (Apple)");
+        assert!(fingerprint.gpu_renderer.contains("Apple M"));
+        
+        let fingerprint = IdentityGrafting::generate_fingerprint("Windows 11", "Chrome 124", (1920, 1080));
+        assert!(WINDOWS_GPUS.contains(&(fingerprint.gpu_vendor.as_str(), fingerprint.gpu_renderer.as_str())));
+    }
+
+    #[test]
+    fn test_hardware_is_consumer_grade_and_browser_aware() {
+        for _ in 0..50 {
+            let (cores, memory) = hardware_for_browser("Chrome 124");
+            let memory = memory.expect("Chrome exposes deviceMemory");
+            assert!(CPU_CORES.contains(&cores));
+            assert!(DEVICE_MEMORY_GB.contains(&memory));
+            assert!(memory >= cores / 2, "{} cores with {} GB", cores, memory);
+        }
+        
+        let fingerprint = IdentityGrafting::generate_fingerprint("macOS 14", "Safari 17", (1440, 900));
+        assert!(CPU_CORES.contains(&fingerprint.hardware_concurrency));
+        assert_eq!(fingerprint.device_memory, None);
+        
+        // Chrome on a Mac still exposes deviceMemory
+        let fingerprint = IdentityGrafting::generate_fingerprint("macOS 14", "Chrome 124", (1440, 900));
+        assert!(fingerprint.device_memory.is_some());
+        
+        assert_eq!(hardware_for_browser("Firefox 120").1, None);
     }
 }
@@ -1,10 +1,14 @@
 pub mod agent;
+pub mod config;
 pub mod browser;
 pub mod vision_client;
 pub mod error;
 pub mod mouse;
 pub mod ooda;
+pub mod pacing;
 pub mod stealth_transport;
+pub mod mitm;
+pub mod stealth_status;
 pub mod ghost_mouse;
 pub mod diffusion_mouse;
 pub mod cortex;
@@ -12,5 +16,11 @@ pub mod world_model;
 pub mod identity_grafting;
 pub mod binary_patch;
 pub mod dbi;
+pub mod metrics;
+pub mod circuit_breaker;
+pub mod trajectory;
+pub mod download;
+pub mod auth;
+pub mod seed;
 
 pub use error::{ChimeraError, Result};
@@ -1,111 +1,164 @@
 use chimera_core::agent::ChimeraAgentService;
-use chimera_core::browser::BrowserSession;
+use chimera_core::browser::{BrowserSession, LaunchMode};
+use chimera_core::config::{ChimeraConfig, LogFormat, SanitizationMode};
 use chimera_core::proto::chimera_agent_server::ChimeraAgentServer;
 use chimera_core::stealth_transport::StealthProxy;
-use std::env;
 use std::time::Duration;
 use tonic::transport::Server;
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
 use tracing::{error, info, warn, Level};
 
+/// How long the Phantom Sidecar gets to start serving before startup gives up on it
+const PROXY_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper bound on the JA4 self-test, which holds up serving (and readiness)
+const PROXY_SELF_TEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Phantom Sidecar restarts tolerated before the process exits
+const PROXY_MAX_RESTARTS: u32 = 3;
+
+/// Delay before the first restart; later restarts back off linearly
+const PROXY_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Report the agent (and the server as a whole) as SERVING or NOT_SERVING
+async fn set_readiness(health: &mut HealthReporter, ready: bool) {
+    let status = if ready { ServingStatus::Serving } else { ServingStatus::NotServing };
+    health.set_service_status("", status).await;
+    if ready {
+        health.set_serving::<ChimeraAgentServer<ChimeraAgentService>>().await;
+    } else {
+        health.set_not_serving::<ChimeraAgentServer<ChimeraAgentService>>().await;
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
-    tracing_subscriber::fmt()
-        .with_max_level(Level::INFO)
-        .init();
+    // All environment parsing happens here, once
+    let config = ChimeraConfig::from_env()?;
+
+    // Initialize tracing. JSON lines carry the enclosing spans (e.g. session_id)
+    // so a log shipper can group every line belonging to one session.
+    let subscriber = tracing_subscriber::fmt().with_max_level(Level::INFO);
+    match config.log_format {
+        LogFormat::Text => subscriber.init(),
+        LogFormat::Json => subscriber.json().init(),
+    }
+    
+    if let Some(path) = &config.trajectory_debug {
+        warn!("🐾 Trajectory debugging on: recording every mouse path to {}", path.display());
+        chimera_core::trajectory::enable(path.clone());
+    }
 
     // 1. IGNITE THE PHANTOM PROXY (Sidecar)
     // We spawn it in the background on port 8080.
     // This intercepts all Chrome traffic and launders it through our impersonation engine.
     info!("👻 Starting Phantom Sidecar (Stealth Proxy)...");
     
-    let proxy_port = env::var("CHIMERA_PROXY_PORT")
-        .unwrap_or_else(|_| "8080".to_string())
-        .parse()
-        .unwrap_or(8080);
+    // grpc.health.v1.Health: NOT_SERVING until the proxy is up and Chrome launches
+    let (mut health, health_service) = tonic_health::server::health_reporter();
+    set_readiness(&mut health, false).await;
     
+    let proxy_config = config.clone();
+    let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+    let proxy_task = tokio::spawn(supervise_proxy(proxy_config, ready_tx));
+
+    // Metrics endpoint (Prometheus scrape target)
+    let metrics_port = config.metrics_port;
     tokio::spawn(async move {
-        let proxy = match StealthProxy::new(proxy_port) {
-            Ok(p) => p,
-            Err(e) => {
-                eprintln!("FATAL: Failed to create Phantom Proxy: {}", e);
-                return;
-            }
-        };
-        
-        if let Err(e) = proxy.serve().await {
-            eprintln!("FATAL: Phantom Proxy died: {}", e);
+        if let Err(e) = chimera_core::metrics::serve(metrics_port).await {
+            error!("Metrics endpoint died: {}", e);
         }
     });
 
-    // Give it 500ms to warm up
-    tokio::time::sleep(Duration::from_millis(500)).await;
-    info!("✅ Phantom Sidecar ready on port {}", proxy_port);
-
-    // Get configuration from environment
-    let agent_addr = env::var("CHIMERA_AGENT_ADDR")
-        .unwrap_or_else(|_| "0.0.0.0:50051".to_string());
-    
-    // Update vision address to use brainscraper service
-    let vision_addr = env::var("CHIMERA_VISION_ADDR")
-        .unwrap_or_else(|_| "http://brainscraper.railway.internal:50052".to_string());
+    // Wait for the proxy to serve rather than guessing at a warm-up time
+    let proxy_ready = matches!(tokio::time::timeout(PROXY_STARTUP_TIMEOUT, ready_rx).await, Ok(Ok(())));
+    if proxy_ready {
+        info!("✅ Phantom Sidecar ready on port {}", config.proxy_port);
+    } else {
+        error!("❌ Phantom Sidecar is not listening on port {}", config.proxy_port);
+    }
 
     info!("🚀 Launching Chimera with Phantom Sidecar active...");
-    info!("Starting Chimera Agent Service on {}", agent_addr);
-    info!("Vision service address: {}", vision_addr);
+    info!("Starting Chimera Agent Service on {}", config.agent_addr);
+    info!("Vision service address: {}", config.vision_addr);
 
     // 2. BINARY SANITIZATION VERIFICATION
     // Verify that the binary patching was successful before starting the service
     info!("🔍 Verifying binary sanitization...");
     
     // Initialize binary patching (if not already done in Dockerfile)
-    if let Err(e) = chimera_core::binary_patch::initialize_binary_patching() {
-        warn!("Binary patching initialization failed (may already be sanitized): {}", e);
+    if config.sanitization != SanitizationMode::Off {
+        if let Err(e) = chimera_core::binary_patch::initialize_binary_patching(&config) {
+            warn!("Binary patching initialization failed (may already be sanitized): {}", e);
+        }
     }
     
     // Create a temporary browser session to verify sanitization
-    // This ensures the engine is "Sanitized and Ready" before accepting missions
-    let sanitization_verified = {
-        info!("🧪 Creating test browser session to verify sanitization...");
-        match BrowserSession::new("sanitization_test".to_string(), true) {
-            Ok(test_session) => {
-                match test_session.get_tab() {
-                    Ok(tab) => {
-                        let cortex = chimera_core::cortex::Cortex::new(tab);
-                        match cortex.verify_engine_health() {
-                            Ok(true) => {
-                                info!("✅ Binary sanitization verified: Engine is Sanitized and Ready");
-                                true
-                            }
-                            Ok(false) => {
-                                error!("❌ Binary sanitization verification FAILED: navigator.webdriver is still present!");
-                                error!("   The engine is NOT sanitized. Missions will fail.");
+    // This ensures the engine is "Sanitized and Ready" before accepting missions.
+    // Readiness needs it even with sanitization off: no Chrome, no sessions.
+    info!("🧪 Creating test browser session...");
+    let test_session = match BrowserSession::new("sanitization_test".to_string(), LaunchMode::Headless, &config, None, None) {
+        Ok(session) => Some(session),
+        Err(e) => {
+            error!("❌ Failed to create test browser session: {}", e);
+            None
+        }
+    };
+    let browser_ready = test_session.is_some();
+    let sanitization_verified = config.sanitization == SanitizationMode::Off || match &test_session {
+        Some(test_session) => {
+            info!("🛡️  Stealth measures: {}", test_session.stealth_status());
+            match test_session.get_tab() {
+                Ok(tab) => {
+                    let cortex = chimera_core::cortex::Cortex::new(tab)
+                        .with_redis_url(config.redis_url.clone());
+                    match cortex.verify_engine_health() {
+                        // webdriver is clean; the engine's own UA must not say "HeadlessChrome"
+                        // either (navigator.userAgent is already overridden per session by now)
+                        Ok(true) => match test_session.engine_user_agent() {
+                            Ok(ua) if ua.contains("Headless") => {
+                                error!("❌ Binary sanitization verification FAILED: engine User-Agent still reports {}", ua);
                                 false
                             }
+                            Ok(ua) => {
+                                info!("✅ Binary sanitization verified: Engine is Sanitized and Ready ({})", ua);
+                                true
+                            }
                             Err(e) => {
-                                error!("❌ Failed to verify sanitization: {}", e);
+                                error!("❌ Failed to read engine User-Agent: {}", e);
                                 false
                             }
+                        },
+                        Ok(false) => {
+                            error!("❌ Binary sanitization verification FAILED: navigator.webdriver is still present!");
+                            error!("   The engine is NOT sanitized. Missions will fail.");
+                            false
+                        }
+                        Err(e) => {
+                            error!("❌ Failed to verify sanitization: {}", e);
+                            false
                         }
-                    }
-                    Err(e) => {
-                        error!("❌ Failed to get test tab: {}", e);
-                        false
                     }
                 }
-            }
-            Err(e) => {
-                error!("❌ Failed to create test browser session: {}", e);
-                false
+                Err(e) => {
+                    error!("❌ Failed to get test tab: {}", e);
+                    false
+                }
             }
         }
+        None => false,
     };
+    drop(test_session);
     
     if !sanitization_verified {
-        error!("🚨 FATAL: Binary sanitization verification failed!");
-        error!("   The Body cannot start missions until the engine is sanitized.");
-        error!("   Check that sanitize_binary.py ran successfully in the Dockerfile.");
-        std::process::exit(1);
+        if config.sanitization == SanitizationMode::Enforce {
+            error!("🚨 FATAL: Binary sanitization verification failed!");
+            error!("   The Body cannot start missions until the engine is sanitized.");
+            error!("   Check that sanitize_binary.py ran successfully in the Dockerfile.");
+            std::process::exit(1);
+        }
+        warn!("⚠️  Sanitization failed but CHIMERA_SANITIZATION=warn - continuing with a dirty engine");
     }
     
     info!("✅ Body Status: Sanitized and Ready");
@@ -114,13 +167,106 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("   - Automation markers: ✅ Erased");
     info!("   - Ready for missions");
 
-    let service = ChimeraAgentService::new(vision_addr);
-    let addr = agent_addr.parse()?;
+    let addr = config.agent_addr.parse()?;
+    let service = ChimeraAgentService::from_config(config);
+
+    set_readiness(&mut health, proxy_ready && browser_ready).await;
+    if !(proxy_ready && browser_ready) {
+        warn!("⚠️  Health: NOT_SERVING (proxy listening: {}, test session: {})", proxy_ready, browser_ready);
+    }
+    
+    // A dead sidecar leaves every session without a network (or worse, talking
+    // to targets without the stealth layer): stop advertising readiness and
+    // shut the whole process down so the orchestrator restarts it.
+    let mut proxy_health = health.clone();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+    let proxy_watch = tokio::spawn(async move {
+        let reason = match proxy_task.await {
+            Ok(reason) => reason,
+            Err(e) => format!("supervisor task failed: {}", e),
+        };
+        error!("🚨 Phantom Sidecar stopped for good ({}) - health is now NOT_SERVING", reason);
+        set_readiness(&mut proxy_health, false).await;
+        let _ = shutdown_tx.send(());
+        reason
+    });
 
     Server::builder()
+        .add_service(health_service)
         .add_service(ChimeraAgentServer::new(service))
-        .serve(addr)
+        .serve_with_shutdown(addr, async {
+            let _ = shutdown_rx.await;
+        })
         .await?;
 
-    Ok(())
+    // The server only stops cleanly when the sidecar is gone
+    let reason = proxy_watch.await?;
+    error!("🚨 FATAL: Chimera Agent shut down, Phantom Sidecar is gone");
+    Err(format!("Phantom Proxy died: {}", reason).into())
+}
+
+/// Run the Phantom Sidecar, restarting it up to `PROXY_MAX_RESTARTS` times.
+///
+/// `ready_tx` fires the first time the proxy starts serving. Returns (only) once the
+/// proxy has died more often than the restart budget allows, with the last error.
+async fn supervise_proxy(config: ChimeraConfig, ready_tx: tokio::sync::oneshot::Sender<()>) -> String {
+    let mut ready_tx = Some(ready_tx);
+    let mut restarts = 0;
+    loop {
+        let attempt_config = config.clone();
+        let first_run = ready_tx.is_some();
+        let (attempt_ready_tx, attempt_ready_rx) = tokio::sync::oneshot::channel();
+        // Each run gets its own task so a panic counts as a death rather than
+        // taking the supervisor with it
+        let run = tokio::spawn(async move {
+            let proxy = StealthProxy::from_config(&attempt_config)
+                .map_err(|e| format!("failed to create Phantom Proxy: {}", e))?;
+            let listener = proxy
+                .bind()
+                .await
+                .map_err(|e| format!("Phantom Proxy could not listen: {}", e))?;
+            
+            // Confirm what targets actually see before trusting the stealth stack
+            if first_run {
+                match tokio::time::timeout(PROXY_SELF_TEST_TIMEOUT, proxy.self_test()).await {
+                    Ok(Ok(report)) if !report.matches => {
+                        warn!("JA4 self-test observed {:?}, expected {}", report.observed_ja4, report.expected_ja4);
+                    }
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => warn!("JA4 self-test could not run: {}", e),
+                    Err(_) => warn!("JA4 self-test gave no answer within {:?}, serving without it", PROXY_SELF_TEST_TIMEOUT),
+                }
+            }
+            
+            // Ready once the accept loop runs (the listener queues connections until then)
+            let _ = attempt_ready_tx.send(());
+            proxy.serve_on(listener).await.map_err(|e| e.to_string())
+        });
+        
+        // Forward the first run that starts serving to startup
+        let watch_bind = async {
+            if attempt_ready_rx.await.is_ok() {
+                if let Some(tx) = ready_tx.take() {
+                    let _ = tx.send(());
+                }
+            }
+        };
+        let (result, _) = tokio::join!(run, watch_bind);
+        let reason = match result {
+            Ok(Err(reason)) => reason,
+            Ok(Ok(())) => "serve loop returned".to_string(),
+            Err(e) => format!("proxy task panicked: {}", e),
+        };
+        
+        if restarts >= PROXY_MAX_RESTARTS {
+            return reason;
+        }
+        restarts += 1;
+        let backoff = PROXY_RESTART_BACKOFF * restarts;
+        error!(
+            "❌ Phantom Sidecar died ({}); restart {}/{} in {:?}",
+            reason, restarts, PROXY_MAX_RESTARTS, backoff
+        );
+        tokio::time::sleep(backoff).await;
+    }
 }
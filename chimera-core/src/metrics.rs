@@ -0,0 +1,496 @@
+/// Metrics - Prometheus-Style Observability
+///
+/// `tracing` tells us what happened to one session. In a swarm, operators need
+/// the aggregate view: how many sessions are live, how often the OODA loop has
+/// to retry, how slow the vision service is, how many bytes the Phantom Proxy
+/// is shoveling.
+///
+/// This module provides a tiny, dependency-free registry of counters, gauges
+/// and histograms rendered in the Prometheus text exposition format, plus a
+/// minimal `/metrics` HTTP endpoint. Embedders that already run their own
+/// HTTP stack can call `registry().render()` and serve it however they like.
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::net::TcpListener;
+use tracing::{debug, error, info};
+
+/// Default latency buckets (seconds) - tuned for vision/CDP round trips
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Monotonically increasing counter
+#[derive(Debug, Default)]
+pub struct Counter {
+    value: AtomicU64,
+}
+
+impl Counter {
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    pub fn inc_by(&self, n: u64) {
+        self.value.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+/// Gauge - a value that can go up and down (e.g. active sessions)
+#[derive(Debug, Default)]
+pub struct Gauge {
+    value: AtomicI64,
+}
+
+impl Gauge {
+    pub fn inc(&self) {
+        self.value.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn dec(&self) {
+        self.value.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, value: i64) {
+        self.value.store(value, Ordering::Relaxed);
+    }
+
+    pub fn get(&self) -> i64 {
+        self.value.load(Ordering::Relaxed)
+    }
+}
+
+/// Histogram with fixed, cumulative buckets
+#[derive(Debug)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    buckets: Vec<AtomicU64>,
+    count: AtomicU64,
+    /// Sum of observations, stored as f64 bits so it can live in an atomic
+    sum_bits: AtomicU64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            buckets: bounds.iter().map(|_| AtomicU64::new(0)).collect(),
+            count: AtomicU64::new(0),
+            sum_bits: AtomicU64::new(0f64.to_bits()),
+        }
+    }
+
+    pub fn observe(&self, value: f64) {
+        for (bound, bucket) in self.bounds.iter().zip(&self.buckets) {
+            if value <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+
+        // CAS loop - there is no atomic f64 add
+        let mut current = self.sum_bits.load(Ordering::Relaxed);
+        loop {
+            let next = (f64::from_bits(current) + value).to_bits();
+            match self.sum_bits.compare_exchange_weak(current, next, Ordering::Relaxed, Ordering::Relaxed) {
+                Ok(_) => break,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// Observe an elapsed duration in seconds
+    pub fn observe_duration(&self, elapsed: std::time::Duration) {
+        self.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    pub fn sum(&self) -> f64 {
+        f64::from_bits(self.sum_bits.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Metric {
+    Counter(Arc<Counter>),
+    Gauge(Arc<Gauge>),
+    Histogram(Arc<Histogram>),
+}
+
+impl Metric {
+    fn type_name(&self) -> &'static str {
+        match self {
+            Metric::Counter(_) => "counter",
+            Metric::Gauge(_) => "gauge",
+            Metric::Histogram(_) => "histogram",
+        }
+    }
+}
+
+/// A metric family: one name + help text, many label sets
+#[derive(Debug)]
+struct Family {
+    help: String,
+    series: BTreeMap<String, Metric>,
+}
+
+/// Metrics registry
+///
+/// Metrics are created lazily on first use and shared afterwards, so any
+/// module can grab `registry().counter(...)` at its instrumentation point
+/// without coordinating registration order.
+#[derive(Debug, Default)]
+pub struct MetricsRegistry {
+    families: RwLock<BTreeMap<String, Family>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get or create an unlabeled counter
+    pub fn counter(&self, name: &str, help: &str) -> Arc<Counter> {
+        self.counter_with_labels(name, help, &[])
+    }
+
+    /// Get or create a counter for a specific label set
+    pub fn counter_with_labels(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Arc<Counter> {
+        match self.get_or_insert(name, help, labels, || Metric::Counter(Arc::new(Counter::default()))) {
+            Metric::Counter(c) => c,
+            other => panic!("metric '{}' already registered as {}", name, other.type_name()),
+        }
+    }
+
+    /// Get or create an unlabeled gauge
+    pub fn gauge(&self, name: &str, help: &str) -> Arc<Gauge> {
+        match self.get_or_insert(name, help, &[], || Metric::Gauge(Arc::new(Gauge::default()))) {
+            Metric::Gauge(g) => g,
+            other => panic!("metric '{}' already registered as {}", name, other.type_name()),
+        }
+    }
+
+    /// Get or create an unlabeled histogram with the given bucket bounds
+    pub fn histogram(&self, name: &str, help: &str, bounds: &[f64]) -> Arc<Histogram> {
+        match self.get_or_insert(name, help, &[], || Metric::Histogram(Arc::new(Histogram::new(bounds)))) {
+            Metric::Histogram(h) => h,
+            other => panic!("metric '{}' already registered as {}", name, other.type_name()),
+        }
+    }
+
+    fn get_or_insert(
+        &self,
+        name: &str,
+        help: &str,
+        labels: &[(&str, &str)],
+        create: impl FnOnce() -> Metric,
+    ) -> Metric {
+        let label_key = render_labels(labels);
+
+        // Fast path: already registered
+        if let Some(metric) = self.families.read().unwrap()
+            .get(name)
+            .and_then(|f| f.series.get(&label_key))
+        {
+            return metric.clone();
+        }
+
+        let mut families = self.families.write().unwrap();
+        let family = families.entry(name.to_string()).or_insert_with(|| Family {
+            help: help.to_string(),
+            series: BTreeMap::new(),
+        });
+        family.series.entry(label_key).or_insert_with(create).clone()
+    }
+
+    /// Render all metrics in the Prometheus text exposition format (v0.0.4)
+    pub fn render(&self) -> String {
+        let families = self.families.read().unwrap();
+        let mut out = String::new();
+
+        for (name, family) in families.iter() {
+            let Some(first) = family.series.values().next() else {
+                continue;
+            };
+            let _ = writeln!(out, "# HELP {} {}", name, family.help);
+            let _ = writeln!(out, "# TYPE {} {}", name, first.type_name());
+
+            for (labels, metric) in &family.series {
+                match metric {
+                    Metric::Counter(c) => {
+                        let _ = writeln!(out, "{}{} {}", name, labels, c.get());
+                    }
+                    Metric::Gauge(g) => {
+                        let _ = writeln!(out, "{}{} {}", name, labels, g.get());
+                    }
+                    Metric::Histogram(h) => {
+                        for (bound, bucket) in h.bounds.iter().zip(&h.buckets) {
+                            let _ = writeln!(
+                                out,
+                                "{}_bucket{{le=\"{}\"}} {}",
+                                name, bound, bucket.load(Ordering::Relaxed)
+                            );
+                        }
+                        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, h.count());
+                        let _ = writeln!(out, "{}_sum {}", name, h.sum());
+                        let _ = writeln!(out, "{}_count {}", name, h.count());
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Render a label set as `{k="v",...}` (empty string for no labels)
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+
+    let inner: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, v.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+    format!("{{{}}}", inner.join(","))
+}
+
+/// Process-wide registry
+pub fn registry() -> &'static MetricsRegistry {
+    static REGISTRY: OnceLock<MetricsRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(MetricsRegistry::new)
+}
+
+/// Agent-side metrics (sessions, actions, OODA, vision)
+pub struct AgentMetrics {
+    pub sessions_started: Arc<Counter>,
+    pub sessions_closed: Arc<Counter>,
+    pub sessions_active: Arc<Gauge>,
+    pub ooda_retries: Arc<Counter>,
+    pub vision_latency: Arc<Histogram>,
+    pub captcha_detections: Arc<Counter>,
+}
+
+impl AgentMetrics {
+    fn register(registry: &MetricsRegistry) -> Self {
+        Self {
+            sessions_started: registry.counter(
+                "chimera_sessions_started_total",
+                "Browser sessions started",
+            ),
+            sessions_closed: registry.counter(
+                "chimera_sessions_closed_total",
+                "Browser sessions closed",
+            ),
+            sessions_active: registry.gauge(
+                "chimera_sessions_active",
+                "Browser sessions currently open",
+            ),
+            ooda_retries: registry.counter(
+                "chimera_ooda_retries_total",
+                "OODA loop attempts that did not verify and were retried",
+            ),
+            vision_latency: registry.histogram(
+                "chimera_vision_latency_seconds",
+                "Vision service GetCoordinates round-trip latency",
+                DEFAULT_LATENCY_BUCKETS,
+            ),
+            captcha_detections: registry.counter(
+                "chimera_captcha_detections_total",
+                "CAPTCHA challenges detected on target pages",
+            ),
+        }
+    }
+
+    /// Per-action-type counter (`click`, `type`, `scroll`, `wait`)
+    pub fn actions(&self, action_type: &str) -> Arc<Counter> {
+        registry().counter_with_labels(
+            "chimera_actions_total",
+            "Actions performed, by action type",
+            &[("type", action_type)],
+        )
+    }
+}
+
+/// Phantom Proxy metrics
+pub struct ProxyMetrics {
+    pub connections_accepted: Arc<Counter>,
+    pub blocked_connects: Arc<Counter>,
+    pub bytes_client_to_server: Arc<Counter>,
+    pub bytes_server_to_client: Arc<Counter>,
+    pub https_tunnels: Arc<Counter>,
+    pub websocket_tunnels: Arc<Counter>,
+    pub websocket_tunnels_active: Arc<Gauge>,
+    pub event_stream_tunnels: Arc<Counter>,
+    pub bypass_tunnels: Arc<Counter>,
+    pub mitm_tunnels: Arc<Counter>,
+}
+
+impl ProxyMetrics {
+    fn register(registry: &MetricsRegistry) -> Self {
+        Self {
+            connections_accepted: registry.counter(
+                "chimera_proxy_connections_accepted_total",
+                "TCP connections accepted by the proxy listener",
+            ),
+            blocked_connects: registry.counter(
+                "chimera_proxy_blocked_connects_total",
+                "CONNECT requests refused by the host filter",
+            ),
+            bytes_client_to_server: registry.counter_with_labels(
+                "chimera_proxy_bytes_total",
+                "Bytes shoveled through proxy tunnels, by direction",
+                &[("direction", "client_to_server")],
+            ),
+            bytes_server_to_client: registry.counter_with_labels(
+                "chimera_proxy_bytes_total",
+                "Bytes shoveled through proxy tunnels, by direction",
+                &[("direction", "server_to_client")],
+            ),
+            https_tunnels: registry.counter_with_labels(
+                "chimera_proxy_tunnels_total",
+                "CONNECT tunnels opened, by traffic kind",
+                &[("kind", "https")],
+            ),
+            websocket_tunnels: registry.counter_with_labels(
+                "chimera_proxy_tunnels_total",
+                "CONNECT tunnels opened, by traffic kind",
+                &[("kind", "websocket")],
+            ),
+            event_stream_tunnels: registry.counter_with_labels(
+                "chimera_proxy_tunnels_total",
+                "CONNECT tunnels opened, by traffic kind",
+                &[("kind", "sse")],
+            ),
+            bypass_tunnels: registry.counter_with_labels(
+                "chimera_proxy_tunnels_total",
+                "CONNECT tunnels opened, by traffic kind",
+                &[("kind", "bypass")],
+            ),
+            mitm_tunnels: registry.counter_with_labels(
+                "chimera_proxy_tunnels_total",
+                "CONNECT tunnels opened, by traffic kind",
+                &[("kind", "mitm")],
+            ),
+            websocket_tunnels_active: registry.gauge(
+                "chimera_proxy_websocket_tunnels_active",
+                "Long-lived WebSocket tunnels currently open",
+            ),
+        }
+    }
+}
+
+/// Agent metrics registered in the global registry
+pub fn agent() -> &'static AgentMetrics {
+    static AGENT: OnceLock<AgentMetrics> = OnceLock::new();
+    AGENT.get_or_init(|| AgentMetrics::register(registry()))
+}
+
+/// Proxy metrics registered in the global registry
+pub fn proxy() -> &'static ProxyMetrics {
+    static PROXY: OnceLock<ProxyMetrics> = OnceLock::new();
+    PROXY.get_or_init(|| ProxyMetrics::register(registry()))
+}
+
+/// Serve the global registry on `http://0.0.0.0:{port}/metrics`
+pub async fn serve(port: u16) -> Result<()> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = TcpListener::bind(addr)
+        .await
+        .context("Failed to bind metrics listener")?;
+
+    info!("📈 Metrics endpoint listening on http://{}/metrics", addr);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!("Failed to accept metrics connection: {}", e);
+                continue;
+            }
+        };
+
+        let io = TokioIo::new(stream);
+        tokio::task::spawn(async move {
+            if let Err(err) = http1::Builder::new()
+                .serve_connection(io, service_fn(handle_metrics_request))
+                .await
+            {
+                debug!("Metrics connection error: {:?}", err);
+            }
+        });
+    }
+}
+
+async fn handle_metrics_request(req: Request<Incoming>) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    if req.uri().path() != "/metrics" {
+        let mut resp = Response::new(Full::new(Bytes::new()));
+        *resp.status_mut() = StatusCode::NOT_FOUND;
+        return Ok(resp);
+    }
+
+    let mut resp = Response::new(Full::new(Bytes::from(registry().render())));
+    resp.headers_mut().insert(
+        hyper::header::CONTENT_TYPE,
+        hyper::header::HeaderValue::from_static("text/plain; version=0.0.4"),
+    );
+    Ok(resp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counter_render() {
+        let registry = MetricsRegistry::new();
+        registry.counter("test_total", "A test counter").inc_by(3);
+        registry.counter_with_labels("test_labeled_total", "Labeled", &[("type", "click")]).inc();
+
+        let out = registry.render();
+        assert!(out.contains("# TYPE test_total counter"));
+        assert!(out.contains("test_total 3"));
+        assert!(out.contains("test_labeled_total{type=\"click\"} 1"));
+    }
+
+    #[test]
+    fn test_histogram_buckets() {
+        let registry = MetricsRegistry::new();
+        let h = registry.histogram("latency_seconds", "Latency", &[0.1, 1.0]);
+        h.observe(0.05);
+        h.observe(0.5);
+        h.observe(2.0);
+
+        let out = registry.render();
+        assert!(out.contains("latency_seconds_bucket{le=\"0.1\"} 1"));
+        assert!(out.contains("latency_seconds_bucket{le=\"1\"} 2"));
+        assert!(out.contains("latency_seconds_bucket{le=\"+Inf\"} 3"));
+        assert!(out.contains("latency_seconds_count 3"));
+    }
+
+    #[test]
+    fn test_same_metric_is_shared() {
+        let registry = MetricsRegistry::new();
+        registry.counter("shared_total", "Shared").inc();
+        registry.counter("shared_total", "Shared").inc();
+        assert_eq!(registry.counter("shared_total", "Shared").get(), 2);
+    }
+}
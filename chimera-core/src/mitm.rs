@@ -0,0 +1,524 @@
+/// MITM Mode (V3) - TLS Termination & Re-encryption
+///
+/// Transparent tunneling forwards Chrome's own ClientHello, so the JA4 a site
+/// sees is Chrome's. In MITM mode the Phantom Proxy terminates Chrome's TLS
+/// with a per-host leaf certificate signed by a local root CA, then re-issues
+/// every request through the impersonation `Client` - the handshake the site
+/// sees is the one we chose.
+///
+/// Chrome must trust the root CA. `CertAuthority::chrome_trust_arg` produces an
+/// `--ignore-certificate-errors-spki-list` flag pinned to this CA's key, which
+/// avoids touching the system trust store.
+///
+/// Limitations: the Chrome-facing side speaks HTTP/1.1 only, and responses are
+/// buffered rather than streamed. WebSocket-looking hosts keep using the
+/// transparent tunnel; an upgrade that shows up inside a MITM tunnel anyway is
+/// refused, and later tunnels to that host stay transparent. Redirects are
+/// handed back to Chrome rather than followed, as Chrome would see them.
+///
+/// A `ProxyInterceptor` sees every decrypted request and response and can pass,
+/// block, or replace the body - e.g. to strip anti-bot JS before Chrome runs
+/// it, or to capture API JSON without scraping the DOM.
+
+use anyhow::{Context, Result};
+use base64::Engine;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa,
+    KeyPair, KeyUsagePurpose,
+};
+use reqwest_impersonate::client::Client;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+use futures::FutureExt;
+use tracing::{debug, error, info, warn};
+
+/// File names used by `CertAuthority::export` / `load_or_generate`
+const CA_CERT_FILE: &str = "chimera-ca.pem";
+const CA_KEY_FILE: &str = "chimera-ca.key";
+
+/// Request headers that describe the Chrome<->proxy hop, not the request
+const HOP_BY_HOP: &[&str] = &[
+    "connection", "proxy-connection", "keep-alive", "transfer-encoding", "te",
+    "trailer", "upgrade", "host", "content-length",
+];
+
+/// What a `ProxyInterceptor` wants done with a request or response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Forward unchanged
+    Pass,
+    /// Answer Chrome with 403 (a blocked request never reaches upstream)
+    Block,
+    /// Forward with this body instead
+    Replace(Bytes),
+}
+
+/// A decrypted request from Chrome, before it is replayed upstream
+#[derive(Debug, Clone)]
+pub struct InterceptedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Bytes,
+}
+
+/// An upstream response, before it is returned to Chrome
+///
+/// `body` is as the impersonation client delivered it; when the client did
+/// not decode it, check `content-encoding` before parsing.
+#[derive(Debug, Clone)]
+pub struct InterceptedResponse {
+    pub url: String,
+    pub status: u16,
+    pub headers: Vec<(String, Vec<u8>)>,
+    pub body: Bytes,
+}
+
+impl InterceptedResponse {
+    /// Value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers.iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_slice())
+    }
+}
+
+/// Body inspection hook for MITM mode
+///
+/// Only decrypted traffic can be inspected, so interceptors are not called
+/// for transparent tunnels, bypassed hosts or WebSockets.
+#[tonic::async_trait]
+pub trait ProxyInterceptor: Send + Sync {
+    async fn on_request(&self, _req: &InterceptedRequest) -> Action {
+        Action::Pass
+    }
+
+    async fn on_response(&self, _resp: &InterceptedResponse) -> Action {
+        Action::Pass
+    }
+}
+
+/// Local root CA that signs per-host leaf certificates
+pub struct CertAuthority {
+    ca: Certificate,
+    ca_der: Vec<u8>,
+    cert_pem: String,
+    key_pem: String,
+
+    /// Leaf TLS configs, one per host (signing is too slow to do per connection)
+    leaves: Mutex<HashMap<String, Arc<ServerConfig>>>,
+
+    /// Hosts that tried to upgrade to WebSocket inside a MITM tunnel
+    websocket_hosts: Mutex<HashSet<String>>,
+}
+
+impl CertAuthority {
+    /// Generate a fresh root CA
+    pub fn generate() -> Result<Self> {
+        let mut params = CertificateParams::new(Vec::<String>::new());
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params.distinguished_name.push(DnType::CommonName, "Chimera Phantom Root CA");
+        params.distinguished_name.push(DnType::OrganizationName, "Chimera");
+        params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+
+        let ca = Certificate::from_params(params).context("Failed to generate root CA")?;
+        let cert_pem = ca.serialize_pem().context("Failed to encode root CA")?;
+        let key_pem = ca.serialize_private_key_pem();
+
+        info!("🔑 Generated Phantom root CA");
+        Self::from_pem(&cert_pem, &key_pem)
+    }
+
+    /// Load a root CA from PEM-encoded certificate and PKCS#8 key
+    pub fn from_pem(cert_pem: &str, key_pem: &str) -> Result<Self> {
+        let key = KeyPair::from_pem(key_pem).context("Invalid CA private key")?;
+        let params = CertificateParams::from_ca_cert_pem(cert_pem, key)
+            .context("Invalid CA certificate")?;
+        let ca = Certificate::from_params(params).context("Failed to load root CA")?;
+        let ca_der = ca.serialize_der().context("Failed to encode root CA")?;
+
+        Ok(Self {
+            ca,
+            ca_der,
+            cert_pem: cert_pem.to_string(),
+            key_pem: key_pem.to_string(),
+            leaves: Mutex::new(HashMap::new()),
+            websocket_hosts: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Load the CA from `dir`, generating and exporting one on first use
+    pub fn load_or_generate(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let cert_path = dir.join(CA_CERT_FILE);
+        let key_path = dir.join(CA_KEY_FILE);
+
+        if cert_path.exists() && key_path.exists() {
+            let cert_pem = std::fs::read_to_string(&cert_path)
+                .with_context(|| format!("Failed to read {}", cert_path.display()))?;
+            let key_pem = std::fs::read_to_string(&key_path)
+                .with_context(|| format!("Failed to read {}", key_path.display()))?;
+            return Self::from_pem(&cert_pem, &key_pem);
+        }
+
+        let ca = Self::generate()?;
+        ca.export(dir)?;
+        Ok(ca)
+    }
+
+    /// Write the CA certificate and key to `dir`, returning the certificate path
+    ///
+    /// The certificate can be imported into an NSS database (`certutil`) or an
+    /// OS trust store if flag-based trust isn't an option. The key can sign a
+    /// certificate for any host, so only the owner may read it.
+    pub fn export(&self, dir: impl AsRef<Path>) -> Result<PathBuf> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let cert_path = dir.join(CA_CERT_FILE);
+        std::fs::write(&cert_path, &self.cert_pem)
+            .with_context(|| format!("Failed to write {}", cert_path.display()))?;
+        write_owner_only(&dir.join(CA_KEY_FILE), &self.key_pem)
+            .context("Failed to write CA key")?;
+
+        info!("📜 Exported Phantom root CA to {}", cert_path.display());
+        Ok(cert_path)
+    }
+
+    pub fn cert_pem(&self) -> &str {
+        &self.cert_pem
+    }
+
+    /// Base64 SHA-256 of the CA's SubjectPublicKeyInfo
+    pub fn spki_fingerprint(&self) -> String {
+        let spki = self.ca.get_key_pair().public_key_der();
+        base64::engine::general_purpose::STANDARD.encode(Sha256::digest(spki))
+    }
+
+    /// Chrome flag that trusts chains containing this CA
+    pub fn chrome_trust_arg(&self) -> String {
+        format!("--ignore-certificate-errors-spki-list={}", self.spki_fingerprint())
+    }
+
+    /// Whether tunnels to `addr` (`host:port`) must stay transparent
+    /// 
+    /// True once the host tried a WebSocket upgrade inside a MITM tunnel.
+    pub fn is_websocket_host(&self, addr: &str) -> bool {
+        self.websocket_hosts.lock().unwrap().contains(&addr_host(addr))
+    }
+
+    /// TLS server config presenting a leaf certificate for `host`
+    fn server_config(&self, host: &str) -> Result<Arc<ServerConfig>> {
+        if let Some(config) = self.leaves.lock().unwrap().get(host) {
+            return Ok(Arc::clone(config));
+        }
+
+        let mut params = CertificateParams::new(vec![host.to_string()]);
+        params.distinguished_name.push(DnType::CommonName, host);
+        params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        let leaf = Certificate::from_params(params).context("Failed to generate leaf certificate")?;
+        let leaf_der = leaf.serialize_der_with_signer(&self.ca)
+            .with_context(|| format!("Failed to sign leaf certificate for {}", host))?;
+
+        let chain = vec![CertificateDer::from(leaf_der), CertificateDer::from(self.ca_der.clone())];
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(leaf.serialize_private_key_der()));
+        let mut config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(chain, key)
+            .context("Failed to build TLS server config")?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        let config = Arc::new(config);
+        self.leaves.lock().unwrap().insert(host.to_string(), Arc::clone(&config));
+        debug!("Issued leaf certificate for {}", host);
+        Ok(config)
+    }
+}
+
+/// Terminate Chrome's TLS for `addr` and replay its requests through `client`
+pub(crate) async fn mitm_tunnel(
+    upgraded: hyper::upgrade::Upgraded,
+    addr: String,
+    ca: Arc<CertAuthority>,
+    client: Arc<Client>,
+    interceptor: Option<Arc<dyn ProxyInterceptor>>,
+    accept_language: Option<Arc<str>>,
+) -> Result<()> {
+    let host = addr_host(&addr);
+    debug!("Opening MITM tunnel to: {}", addr);
+    crate::metrics::proxy().mitm_tunnels.inc();
+
+    let acceptor = TlsAcceptor::from(ca.server_config(&host)?);
+    let tls = acceptor.accept(TokioIo::new(upgraded)).await
+        .with_context(|| format!("TLS handshake with Chrome failed for {}", addr))?;
+
+    http1::Builder::new()
+        .serve_connection(TokioIo::new(tls), service_fn(move |req| {
+            // Upgrades can't be replayed request-by-request
+            if req.headers().contains_key(hyper::header::UPGRADE) {
+                return std::future::ready(Ok(refuse_upgrade(&ca, &host))).boxed();
+            }
+            forward_request(req, Arc::clone(&client), addr.clone(), interceptor.clone(), accept_language.clone()).boxed()
+        }))
+        .await
+        .context("MITM connection error")?;
+
+    Ok(())
+}
+
+/// Re-issue one decrypted request through the impersonation client
+async fn forward_request(
+    req: Request<Incoming>,
+    client: Arc<Client>,
+    authority: String,
+    interceptor: Option<Arc<dyn ProxyInterceptor>>,
+    accept_language: Option<Arc<str>>,
+) -> Result<Response<Full<Bytes>>, hyper::Error> {
+    let path = req.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let url = match authority.strip_suffix(":443") {
+        Some(host) => format!("https://{}{}", host, path),
+        None => format!("https://{}{}", authority, path),
+    };
+
+    match replay(req, &client, &url, interceptor.as_deref(), accept_language.as_deref()).await {
+        Ok(response) => Ok(response),
+        Err(e) => {
+            error!("MITM forward to {} failed: {:#}", url, e);
+            let mut resp = Response::new(Full::new(Bytes::new()));
+            *resp.status_mut() = StatusCode::BAD_GATEWAY;
+            Ok(resp)
+        }
+    }
+}
+
+/// Host part of `host:port` / `[v6]:port`
+fn addr_host(addr: &str) -> String {
+    let host = addr.rsplit_once(':').map(|(h, _)| h).unwrap_or(addr);
+    host.trim_start_matches('[').trim_end_matches(']').to_ascii_lowercase()
+}
+
+/// Empty 501 for an upgrade inside a MITM tunnel; later tunnels to `host` stay transparent
+fn refuse_upgrade(ca: &CertAuthority, host: &str) -> Response<Full<Bytes>> {
+    warn!("🔌 {} tried to upgrade inside a MITM tunnel; its next tunnels will be transparent", host);
+    ca.websocket_hosts.lock().unwrap().insert(host.to_string());
+    let mut resp = Response::new(Full::new(Bytes::new()));
+    *resp.status_mut() = StatusCode::NOT_IMPLEMENTED;
+    resp
+}
+
+/// Write `contents` to `path` readable and writable by the owner only
+fn write_owner_only(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // The mode only applies on creation; tighten a key written by an older version
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    std::io::Write::write_all(&mut options.open(path)?, contents.as_bytes())
+}
+
+/// Empty 403 returned to Chrome when an interceptor blocks
+fn blocked(url: &str) -> Response<Full<Bytes>> {
+    info!("🚫 Interceptor blocked {}", url);
+    let mut resp = Response::new(Full::new(Bytes::new()));
+    *resp.status_mut() = StatusCode::FORBIDDEN;
+    resp
+}
+
+/// Fill in `Accept-Language` for requests the browser sent without one
+/// 
+/// The browser's own value is the session's profile language (set by
+/// `BrowserSession::emulate_locale`) and always wins; the proxy-wide
+/// `fallback` must not overwrite one identity's language with another's.
+fn default_accept_language(headers: &mut Vec<(String, Vec<u8>)>, fallback: Option<&str>) {
+    let Some(lang) = fallback else {
+        return;
+    };
+    if !headers.iter().any(|(name, _)| name == "accept-language") {
+        headers.push(("accept-language".to_string(), lang.as_bytes().to_vec()));
+    }
+}
+
+async fn replay(
+    req: Request<Incoming>,
+    client: &Client,
+    url: &str,
+    interceptor: Option<&dyn ProxyInterceptor>,
+    accept_language: Option<&str>,
+) -> Result<Response<Full<Bytes>>> {
+    // hyper 1 and reqwest-impersonate use different `http` versions; go through strings
+    let method = reqwest_impersonate::Method::from_bytes(req.method().as_str().as_bytes())
+        .context("Unsupported method")?;
+    let mut headers: Vec<(String, Vec<u8>)> = req.headers()
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP.contains(&name.as_str()))
+        .map(|(name, value)| (name.as_str().to_string(), value.as_bytes().to_vec()))
+        .collect();
+    default_accept_language(&mut headers, accept_language);
+    let body = req.into_body().collect().await
+        .context("Failed to read request body")?
+        .to_bytes();
+
+    let (headers, body) = match interceptor {
+        Some(interceptor) => {
+            let intercepted = InterceptedRequest {
+                method: method.as_str().to_string(),
+                url: url.to_string(),
+                headers,
+                body,
+            };
+            match interceptor.on_request(&intercepted).await {
+                Action::Pass => (intercepted.headers, intercepted.body),
+                Action::Block => return Ok(blocked(url)),
+                Action::Replace(replacement) => {
+                    debug!("Interceptor replaced request body for {}", url);
+                    (intercepted.headers, replacement)
+                }
+            }
+        }
+        None => (headers, body),
+    };
+
+    send_upstream(client, method, url, headers, body, interceptor).await
+}
+
+async fn send_upstream(
+    client: &Client,
+    method: reqwest_impersonate::Method,
+    url: &str,
+    headers: Vec<(String, Vec<u8>)>,
+    body: Bytes,
+    interceptor: Option<&dyn ProxyInterceptor>,
+) -> Result<Response<Full<Bytes>>> {
+    let mut outbound = client.request(method, url);
+    for (name, value) in headers {
+        outbound = outbound.header(name, value);
+    }
+    if !body.is_empty() {
+        outbound = outbound.body(body);
+    }
+
+    let upstream = outbound.send().await.context("Upstream request failed")?;
+    let status = StatusCode::from_u16(upstream.status().as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    let mut upstream_headers: Vec<(String, Vec<u8>)> = upstream.headers()
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP.contains(&name.as_str()))
+        .map(|(name, value)| (name.as_str().to_string(), value.as_bytes().to_vec()))
+        .collect();
+    let mut body = upstream.bytes().await.context("Failed to read upstream body")?;
+
+    if let Some(interceptor) = interceptor {
+        let intercepted = InterceptedResponse {
+            url: url.to_string(),
+            status: status.as_u16(),
+            headers: upstream_headers,
+            body,
+        };
+        let action = interceptor.on_response(&intercepted).await;
+        upstream_headers = intercepted.headers;
+        body = intercepted.body;
+        match action {
+            Action::Pass => {}
+            Action::Block => return Ok(blocked(url)),
+            Action::Replace(replacement) => {
+                debug!("Interceptor replaced response body for {}", url);
+                // The replacement is plain bytes; the old encoding no longer applies
+                upstream_headers.retain(|(name, _)| !name.eq_ignore_ascii_case("content-encoding"));
+                body = replacement;
+            }
+        }
+    }
+
+    let mut response = Response::builder().status(status);
+    for (name, value) in upstream_headers {
+        response = response.header(name, value);
+    }
+    response.body(Full::new(body)).map_err(|e| {
+        warn!("Dropping malformed upstream response for {}: {}", url, e);
+        anyhow::anyhow!(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaf_certificates_are_issued_once_per_host() {
+        let ca = CertAuthority::generate().unwrap();
+        let first = ca.server_config("example.com").unwrap();
+        let again = ca.server_config("example.com").unwrap();
+        let other = ca.server_config("shop.example.com").unwrap();
+
+        assert!(Arc::ptr_eq(&first, &again));
+        assert!(!Arc::ptr_eq(&first, &other));
+        assert_eq!(first.alpn_protocols, vec![b"http/1.1".to_vec()]);
+    }
+
+    #[test]
+    fn test_ca_round_trips_through_pem() {
+        let ca = CertAuthority::generate().unwrap();
+        let loaded = CertAuthority::from_pem(ca.cert_pem(), &ca.key_pem).unwrap();
+
+        assert_eq!(loaded.spki_fingerprint(), ca.spki_fingerprint());
+        assert!(loaded.chrome_trust_arg().ends_with(&ca.spki_fingerprint()));
+        assert!(loaded.server_config("example.com").is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_exported_key_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("chimera-ca-test-{}", std::process::id()));
+        let ca = CertAuthority::generate().unwrap();
+        ca.export(&dir).unwrap();
+        let mode = std::fs::metadata(dir.join(CA_KEY_FILE)).unwrap().permissions().mode();
+        let reloaded = CertAuthority::load_or_generate(&dir).map(|loaded| loaded.spki_fingerprint());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(reloaded.unwrap(), ca.spki_fingerprint());
+    }
+
+    #[test]
+    fn test_session_language_survives_replay() {
+        let mut from_profile = vec![("accept-language".to_string(), b"de-DE,de;q=0.9".to_vec())];
+        default_accept_language(&mut from_profile, Some("en-US,en;q=0.9"));
+        assert_eq!(from_profile, vec![("accept-language".to_string(), b"de-DE,de;q=0.9".to_vec())]);
+
+        let mut without = vec![("accept".to_string(), b"*/*".to_vec())];
+        default_accept_language(&mut without, Some("en-US,en;q=0.9"));
+        assert_eq!(without[1], ("accept-language".to_string(), b"en-US,en;q=0.9".to_vec()));
+    }
+
+    #[test]
+    fn test_refused_upgrade_makes_host_transparent() {
+        let ca = CertAuthority::generate().unwrap();
+        assert!(!ca.is_websocket_host("chat.example.com:443"));
+
+        let resp = refuse_upgrade(&ca, &addr_host("Chat.Example.com:443"));
+        assert_eq!(resp.status(), StatusCode::NOT_IMPLEMENTED);
+        assert!(ca.is_websocket_host("chat.example.com:443"));
+        assert!(!ca.is_websocket_host("example.com:443"));
+        assert_eq!(addr_host("[::1]:8443"), "::1");
+    }
+}
@@ -4,12 +4,17 @@
 /// indistinguishable from human behavior by using curved paths with
 /// random variations.
 
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+use std::ops::Range;
 use std::time::Duration;
 use tokio::time::sleep;
 use headless_chrome::Tab;
 use anyhow::Context;
 use tracing::debug;
+use crate::ghost_mouse::MotorProfile;
+use crate::trajectory::Trajectory;
 
 /// Generate a human-like curved path between two points using Bezier curves
 /// 
@@ -18,22 +23,50 @@ use tracing::debug;
 /// - Decelerates at the end  
 /// - Curves slightly (not a straight line)
 /// - Has random variations (no two paths are identical)
+/// - Shakes, overshoots and takes its time the way `profile` does
 fn generate_human_path(
     start_x: f64,
     start_y: f64,
     end_x: f64,
     end_y: f64,
+    profile: &MotorProfile,
+    rng: &mut StdRng,
 ) -> Vec<(f64, f64)> {
-    let mut rng = rand::thread_rng();
+    let distance = ((end_x - start_x).powi(2) + (end_y - start_y).powi(2)).sqrt();
+    if distance > 200.0 && rng.gen_bool(profile.overshoot_prob.clamp(0.0, 1.0)) {
+        // Sail past the target along the line of travel, then correct back
+        let (min, max) = profile.overshoot_range;
+        let overshoot = if max > min { rng.gen_range(min..max) } else { min };
+        let past_x = end_x + (end_x - start_x) / distance * overshoot;
+        let past_y = end_y + (end_y - start_y) / distance * overshoot;
+        
+        let mut path = bezier_path(start_x, start_y, past_x, past_y, profile, rng);
+        path.extend(bezier_path(past_x, past_y, end_x, end_y, profile, rng).into_iter().skip(1));
+        return path;
+    }
     
+    bezier_path(start_x, start_y, end_x, end_y, profile, rng)
+}
+
+/// One curved stroke, with tremor that fades out at both ends
+fn bezier_path(
+    start_x: f64,
+    start_y: f64,
+    end_x: f64,
+    end_y: f64,
+    profile: &MotorProfile,
+    rng: &mut StdRng,
+) -> Vec<(f64, f64)> {
     // Create control points for the Bezier curve
     // The randomness adds "imperfection" that makes it human-like
     let mid_x = (start_x + end_x) / 2.0;
     let mid_y = (start_y + end_y) / 2.0;
     
     // Random offset for the control point (creates the curve)
+    // 10% of distance, clamped; short correction strokes curve less
     let distance = ((end_x - start_x).powi(2) + (end_y - start_y).powi(2)).sqrt();
-    let offset_range = (distance * 0.1).min(100.0).max(20.0); // 10% of distance, clamped
+    let offset_range = (distance * 0.1).clamp((distance / 2.0).clamp(1.0, 20.0), 100.0);
+    let tremor = Normal::new(0.0, profile.tremor_std.abs()).ok();
     
     let control_x = mid_x + rng.gen_range(-offset_range..offset_range);
     let control_y = mid_y + rng.gen_range(-offset_range..offset_range);
@@ -56,6 +89,14 @@ fn generate_human_path(
             + 2.0 * (1.0 - t) * t * control_y 
             + t.powi(2) * end_y;
         
+        // Hand jitter, none at the start and landing points
+        let (x, y) = match &tremor {
+            Some(tremor) => {
+                let envelope = (std::f64::consts::PI * t).sin();
+                (x + tremor.sample(rng) * envelope, y + tremor.sample(rng) * envelope)
+            }
+            None => (x, y),
+        };
         path.push((x, y));
     }
     
@@ -69,21 +110,29 @@ async fn move_mouse_human_like(
     start_y: f64,
     end_x: f64,
     end_y: f64,
+    profile: &MotorProfile,
+    rng: &mut StdRng,
 ) -> anyhow::Result<()> {
-    let path = generate_human_path(start_x, start_y, end_x, end_y);
-    let mut rng = rand::thread_rng();
+    let path = generate_human_path(start_x, start_y, end_x, end_y, profile, rng);
     
     debug!("Moving mouse along {} point path from ({:.0}, {:.0}) to ({:.0}, {:.0})", 
            path.len(), start_x, start_y, end_x, end_y);
     
+    let mut recorded = crate::trajectory::enabled().then(Vec::new);
     for (x, y) in path {
         tab.move_mouse(x, y)
             .context("Failed to move mouse")?;
         
         // Micro-sleeps between movements simulate human hand drag
         // Humans don't move at constant speed - we accelerate and decelerate
-        let delay_ms = rng.gen_range(5..15);
-        sleep(Duration::from_millis(delay_ms)).await;
+        let delay = Duration::from_millis(rng.gen_range(5..15)).div_f64(profile.speed_scale.max(0.1));
+        if let Some(steps) = recorded.as_mut() {
+            steps.push((x, y, delay));
+        }
+        sleep(delay).await;
+    }
+    if let Some(steps) = recorded {
+        crate::trajectory::record(&Trajectory::from_steps("bezier", (end_x, end_y), steps));
     }
     
     Ok(())
@@ -102,19 +151,40 @@ pub async fn human_click(
     current_x: Option<f64>,
     current_y: Option<f64>,
 ) -> anyhow::Result<()> {
-    let mut rng = rand::thread_rng();
-    
+    human_click_with_rng(
+        tab,
+        target_x,
+        target_y,
+        current_x,
+        current_y,
+        &MotorProfile::default(),
+        &mut StdRng::from_entropy(),
+    )
+    .await
+}
+
+/// `human_click` moving like `profile`, drawing its path and timing from
+/// `rng` (e.g. `BrowserSession::motor_profile` and `mouse_rng`)
+pub async fn human_click_with_rng(
+    tab: &Tab,
+    target_x: f64,
+    target_y: f64,
+    current_x: Option<f64>,
+    current_y: Option<f64>,
+    profile: &MotorProfile,
+    rng: &mut StdRng,
+) -> anyhow::Result<()> {
     // Get current mouse position (or use provided)
     let (start_x, start_y) = if let (Some(x), Some(y)) = (current_x, current_y) {
         (x, y)
     } else {
         // Default to center of screen if position unknown
-        // In production, you'd track mouse position in session state
+        // (BrowserSession tracks the real position and always passes it)
         (960.0, 540.0)
     };
     
     // Move to target with human-like curve
-    move_mouse_human_like(tab, start_x, start_y, target_x, target_y).await?;
+    move_mouse_human_like(tab, start_x, start_y, target_x, target_y, profile, rng).await?;
     
     // Small random delay before clicking (humans pause slightly)
     let pre_click_delay = rng.gen_range(50..150);
@@ -133,18 +203,122 @@ pub async fn human_click(
     Ok(())
 }
 
+/// How `human_type_with` handles text that isn't plain keyboard input
+#[derive(Debug, Clone)]
+pub struct TypeOptions {
+    /// Press Enter for '\n' (may submit single-line forms); otherwise insert a literal newline
+    pub press_enter_on_newline: bool,
+    
+    /// Insert the whole string in one `Input.insertText` call (fast, but no keystrokes -
+    /// only use where stealth isn't required)
+    pub bulk_insert: bool,
+}
+
+impl Default for TypeOptions {
+    fn default() -> Self {
+        Self {
+            press_enter_on_newline: true,
+            bulk_insert: false,
+        }
+    }
+}
+
+/// Typing mistakes: how often a neighbouring key is hit, and how long the
+/// typist takes to notice before backspacing
+#[derive(Debug, Clone)]
+pub struct TypingProfile {
+    /// Probability (0.0-1.0) that a letter or digit is mistyped
+    pub typo_rate: f64,
+    
+    /// Pause between the wrong key and the Backspace
+    pub correction_delay_ms: Range<u64>,
+}
+
+impl Default for TypingProfile {
+    fn default() -> Self {
+        Self {
+            typo_rate: 0.0,
+            correction_delay_ms: 150..450,
+        }
+    }
+}
+
 /// Type text with human-like timing
 pub async fn human_type(
     tab: &Tab,
     text: &str,
 ) -> anyhow::Result<()> {
-    let mut rng = rand::thread_rng();
-    
+    human_type_with(tab, text, &TypeOptions::default()).await
+}
+
+/// Type text with human-like timing and explicit handling of special characters
+/// 
+/// ASCII goes through real key events. Anything the US keyboard layout can't
+/// produce (accents, CJK, emoji) is inserted via `Input.insertText` one
+/// grapheme-ish cluster at a time, so emoji with modifiers/ZWJ sequences and
+/// combining accents arrive intact, like an IME commit.
+pub async fn human_type_with(
+    tab: &Tab,
+    text: &str,
+    options: &TypeOptions,
+) -> anyhow::Result<()> {
+    human_type_with_profile(tab, text, options, &TypingProfile::default()).await
+}
+
+/// `human_type_with` plus occasional typos that are noticed and corrected
+/// 
+/// A typo types a QWERTY-adjacent key, pauses, sends Backspace, then types
+/// the intended character.
+pub async fn human_type_with_profile(
+    tab: &Tab,
+    text: &str,
+    options: &TypeOptions,
+    profile: &TypingProfile,
+) -> anyhow::Result<()> {
+    human_type_with_rng(tab, text, options, profile, &mut StdRng::from_entropy()).await
+}
+
+/// `human_type_with_profile` drawing keystroke timing and typos from `rng`
+/// (e.g. `BrowserSession::typing_rng`)
+pub async fn human_type_with_rng(
+    tab: &Tab,
+    text: &str,
+    options: &TypeOptions,
+    profile: &TypingProfile,
+    rng: &mut StdRng,
+) -> anyhow::Result<()> {
     debug!("Typing text with human-like timing: {}", text);
     
-    for ch in text.chars() {
-        tab.type_str(&ch.to_string())
-            .context("Failed to type character")?;
+    if options.bulk_insert {
+        insert_text(tab, text)?;
+        return Ok(());
+    }
+    
+    for cluster in char_clusters(text) {
+        match cluster.as_str() {
+            // Normalize CRLF: the '\n' half does the work
+            "\r" => continue,
+            "\n" if options.press_enter_on_newline => {
+                tab.press_key("Enter").context("Failed to press Enter")?;
+            }
+            "\n" => insert_text(tab, "\n")?,
+            "\t" => {
+                tab.press_key("Tab").context("Failed to press Tab")?;
+            }
+            c if c.is_ascii() => {
+                if let Some(wrong) = c.chars().next().and_then(|intended| typo_for(intended, profile, rng)) {
+                    tab.type_str(&wrong.to_string()).context("Failed to type character")?;
+                    
+                    let notice_ms = rng.gen_range(profile.correction_delay_ms.clone());
+                    sleep(Duration::from_millis(notice_ms)).await;
+                    tab.press_key("Backspace").context("Failed to press Backspace")?;
+                    sleep(Duration::from_millis(rng.gen_range(50..150))).await;
+                }
+                
+                tab.type_str(c).context("Failed to type character")?;
+            }
+            c => insert_text(tab, c)?,
+        }
         
         // Humans type at variable speeds (WPM varies)
         // Average is ~40 WPM, but we add randomness
@@ -155,30 +329,144 @@ pub async fn human_type(
     Ok(())
 }
 
+/// The wrong key hit instead of `intended`, if this keystroke is a typo
+/// 
+/// Drawn at `profile.typo_rate` from the QWERTY neighbours, matching the
+/// intended character's case. Keys without neighbours are never mistyped.
+fn typo_for(intended: char, profile: &TypingProfile, rng: &mut StdRng) -> Option<char> {
+    if profile.typo_rate <= 0.0 || !rng.gen_bool(profile.typo_rate.min(1.0)) {
+        return None;
+    }
+    let neighbors = qwerty_neighbors(intended);
+    if neighbors.is_empty() {
+        return None;
+    }
+    let wrong = neighbors[rng.gen_range(0..neighbors.len())];
+    Some(if intended.is_ascii_uppercase() { wrong.to_ascii_uppercase() } else { wrong })
+}
+
+/// Keys physically adjacent to `c` on a US QWERTY keyboard (letters and digits)
+fn qwerty_neighbors(c: char) -> &'static [char] {
+    match c.to_ascii_lowercase() {
+        '1' => &['2', 'q'],
+        '2' => &['1', '3', 'q', 'w'],
+        '3' => &['2', '4', 'w', 'e'],
+        '4' => &['3', '5', 'e', 'r'],
+        '5' => &['4', '6', 'r', 't'],
+        '6' => &['5', '7', 't', 'y'],
+        '7' => &['6', '8', 'y', 'u'],
+        '8' => &['7', '9', 'u', 'i'],
+        '9' => &['8', '0', 'i', 'o'],
+        '0' => &['9', 'o', 'p'],
+        'q' => &['w', 'a', '1', '2'],
+        'w' => &['q', 'e', 'a', 's', '2', '3'],
+        'e' => &['w', 'r', 's', 'd', '3', '4'],
+        'r' => &['e', 't', 'd', 'f', '4', '5'],
+        't' => &['r', 'y', 'f', 'g', '5', '6'],
+        'y' => &['t', 'u', 'g', 'h', '6', '7'],
+        'u' => &['y', 'i', 'h', 'j', '7', '8'],
+        'i' => &['u', 'o', 'j', 'k', '8', '9'],
+        'o' => &['i', 'p', 'k', 'l', '9', '0'],
+        'p' => &['o', 'l', '0'],
+        'a' => &['q', 'w', 's', 'z'],
+        's' => &['a', 'd', 'w', 'e', 'z', 'x'],
+        'd' => &['s', 'f', 'e', 'r', 'x', 'c'],
+        'f' => &['d', 'g', 'r', 't', 'c', 'v'],
+        'g' => &['f', 'h', 't', 'y', 'v', 'b'],
+        'h' => &['g', 'j', 'y', 'u', 'b', 'n'],
+        'j' => &['h', 'k', 'u', 'i', 'n', 'm'],
+        'k' => &['j', 'l', 'i', 'o', 'm'],
+        'l' => &['k', 'o', 'p'],
+        'z' => &['a', 's', 'x'],
+        'x' => &['z', 'c', 's', 'd'],
+        'c' => &['x', 'v', 'd', 'f'],
+        'v' => &['c', 'b', 'f', 'g'],
+        'b' => &['v', 'n', 'g', 'h'],
+        'n' => &['b', 'm', 'h', 'j'],
+        'm' => &['n', 'j', 'k'],
+        _ => &[],
+    }
+}
+
+/// Insert text at the caret without key events (IME-style commit)
+fn insert_text(tab: &Tab, text: &str) -> anyhow::Result<()> {
+    tab.call_method("Input.insertText", serde_json::json!({ "text": text }))
+        .context("Failed to insert text")?;
+    Ok(())
+}
+
+/// Split text into clusters that must be inserted together
+/// 
+/// A base character absorbs following combining marks, variation selectors,
+/// emoji skin-tone modifiers, and zero-width-joiner sequences; regional
+/// indicators pair up into flags.
+fn char_clusters(text: &str) -> Vec<String> {
+    fn is_regional_indicator(c: char) -> bool {
+        (0x1F1E6..=0x1F1FF).contains(&(c as u32))
+    }
+    
+    fn extends_cluster(c: char) -> bool {
+        matches!(c as u32,
+            0x0300..=0x036F     // combining diacritical marks
+            | 0x200D            // zero-width joiner
+            | 0xFE00..=0xFE0F   // variation selectors
+            | 0x1F3FB..=0x1F3FF // skin-tone modifiers
+            | 0xE0020..=0xE007F // tag sequences (flags)
+        )
+    }
+    
+    let mut clusters: Vec<String> = Vec::new();
+    let mut joined = false;
+    for c in text.chars() {
+        match clusters.last_mut() {
+            Some(last) if joined
+                || extends_cluster(c)
+                || (is_regional_indicator(c)
+                    && last.chars().count() == 1
+                    && last.chars().all(is_regional_indicator)) => {
+                last.push(c);
+                joined = c == '\u{200D}';
+            }
+            _ => {
+                clusters.push(c.to_string());
+                joined = false;
+            }
+        }
+    }
+    clusters
+}
+
 /// Perform micro-fidgeting - subtle mouse movements during wait/think states
 /// 
 /// The Problem: When waiting, the mouse is perfectly still (dead giveaway).
 /// Real humans fidget - hands drift, micro-movements, text highlighting.
 /// 
-/// The Fix: Perform tiny random movements (1-3 pixels) or drift towards center.
-pub async fn perform_micro_fidget(tab: &Tab) -> anyhow::Result<()> {
-    use rand::Rng;
+/// The Fix: Perform tiny movements (1-3 pixels) from the current position, in a
+/// random direction or, given an `attractor` (e.g. the element being read),
+/// roughly toward it. The result is clamped to `bounds` (viewport width, height).
+/// Returns the new cursor position.
+pub async fn perform_micro_fidget(
+    tab: &Tab,
+    current_x: f64,
+    current_y: f64,
+    bounds: (f64, f64),
+    attractor: Option<(f64, f64)>,
+    rng: &mut StdRng,
+) -> anyhow::Result<(f64, f64)> {
     use tokio::time::sleep;
     
-    let mut rng = rand::thread_rng();
-    
-    // Get current mouse position (or use center as default)
-    // In production, you'd track this in session state
-    let current_x = 960.0; // Center of 1920px screen
-    let current_y = 540.0; // Center of 1080px screen
-    
-    // Micro-movement: 1-3 pixels in random direction
-    // These are imperceptible to humans but prevent "dead mouse" detection
-    let drift_x = rng.gen_range(-3.0..3.0);
-    let drift_y = rng.gen_range(-3.0..3.0);
+    // Micro-movement: 1-3 pixels, imperceptible to humans but prevents "dead mouse" detection
+    // Toward an attractor the hand still wobbles: +/- ~30 degrees off the straight line
+    let distance = rng.gen_range(1.0..3.0);
+    let angle = match attractor {
+        Some((ax, ay)) if (ax - current_x).hypot(ay - current_y) > distance => {
+            (ay - current_y).atan2(ax - current_x) + rng.gen_range(-0.5..0.5)
+        }
+        _ => rng.gen_range(0.0..std::f64::consts::TAU),
+    };
     
-    let new_x = (current_x + drift_x).max(0.0).min(1920.0);
-    let new_y = (current_y + drift_y).max(0.0).min(1080.0);
+    let new_x = (current_x + distance * angle.cos()).clamp(0.0, bounds.0);
+    let new_y = (current_y + distance * angle.sin()).clamp(0.0, bounds.1);
     
     // Move mouse slightly (imperceptible to humans, but prevents "dead mouse" detection)
     tab.move_mouse(new_x, new_y)
@@ -187,5 +475,73 @@ pub async fn perform_micro_fidget(tab: &Tab) -> anyhow::Result<()> {
     // Small delay before next fidget
     sleep(Duration::from_millis(rng.gen_range(50..200))).await;
     
-    Ok(())
+    Ok((new_x, new_y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_qwerty_neighbors_are_symmetric_for_letters() {
+        for c in 'a'..='z' {
+            let neighbors = qwerty_neighbors(c);
+            assert!(!neighbors.is_empty(), "{} has no neighbours", c);
+            for &n in neighbors.iter().filter(|n| n.is_ascii_alphabetic()) {
+                assert!(qwerty_neighbors(n).contains(&c), "{} -> {} but not back", c, n);
+            }
+        }
+        assert_eq!(qwerty_neighbors('G'), qwerty_neighbors('g'));
+    }
+
+    #[test]
+    fn test_qwerty_neighbors_empty_for_non_letters() {
+        for c in [' ', '.', '-', '@', '\n', 'é', '中'] {
+            assert!(qwerty_neighbors(c).is_empty(), "{:?} should have no neighbours", c);
+        }
+    }
+
+    #[test]
+    fn test_typo_rate_zero_never_mistypes() {
+        let profile = TypingProfile { typo_rate: 0.0, ..Default::default() };
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..50 {
+            for c in "The quick brown fox 1234".chars() {
+                assert_eq!(typo_for(c, &profile, &mut rng), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_typo_rate_one_always_corrects() {
+        let profile = TypingProfile { typo_rate: 1.0, ..Default::default() };
+        let mut rng = StdRng::seed_from_u64(7);
+        for c in "TheQuickBrownFox1234".chars() {
+            let wrong = typo_for(c, &profile, &mut rng).expect("every letter and digit is mistyped");
+            assert!(qwerty_neighbors(c).contains(&wrong.to_ascii_lowercase()));
+            if wrong.is_ascii_alphabetic() {
+                assert_eq!(wrong.is_ascii_uppercase(), c.is_ascii_uppercase());
+            }
+        }
+        // Nothing to slip onto, nothing to correct
+        assert_eq!(typo_for(' ', &profile, &mut rng), None);
+    }
+
+    #[test]
+    fn test_char_clusters_keeps_zwj_emoji_together() {
+        // Family: man ZWJ woman ZWJ girl, then a skin-toned thumbs up
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        let thumbs = "\u{1F44D}\u{1F3FD}";
+        assert_eq!(char_clusters(&format!("a{}{}b", family, thumbs)), vec!["a", family, thumbs, "b"]);
+    }
+
+    #[test]
+    fn test_char_clusters_keeps_combining_accent_with_base() {
+        assert_eq!(char_clusters("cafe\u{0301}!"), vec!["c", "a", "f", "e\u{0301}", "!"]);
+    }
+
+    #[test]
+    fn test_char_clusters_splits_crlf_and_tabs() {
+        assert_eq!(char_clusters("a\r\nb\tc"), vec!["a", "\r", "\n", "b", "\t", "c"]);
+    }
 }
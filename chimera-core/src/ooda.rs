@@ -3,15 +3,506 @@
 /// Observe-Orient-Decide-Act loop with visual verification
 /// This is what makes Chimera self-healing and resilient.
 
-use crate::browser::BrowserSession;
-use crate::cortex::AxTree;
+use crate::browser::{visual_hash_distance, BrowserSession};
+use crate::cortex::{AxNode, AxTree, Cortex, FusionState, DEFAULT_FUZZY_THRESHOLD};
 use crate::error::{ChimeraError, Result};
 use crate::vision_client::VisionClient;
+use headless_chrome::Tab;
+use rand::rngs::StdRng;
 use rand::Rng;
-use std::time::Duration;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
 use tokio::time::sleep;
 use tracing::{debug, info, warn};
 
+/// AX roles a click is meant to land on (what `expected_role_at` looks for)
+pub(crate) const CLICKABLE_ROLES: &[&str] = &[
+    "button", "link", "checkbox", "radio", "textbox", "searchbox", "combobox", "listbox",
+    "option", "menuitem", "menuitemcheckbox", "menuitemradio", "tab", "switch", "slider",
+    "spinbutton", "PopUpButton", "MenuListOption",
+];
+
+/// Whether the focused element takes typed text: a textarea, a contenteditable
+/// host, or an `<input>` that isn't a button, box or picker
+const FOCUSED_TEXT_ENTRY_JS: &str = "(() => { \
+    const el = document.activeElement; \
+    if (!el) return false; \
+    if (el.isContentEditable || el.tagName === 'TEXTAREA') return true; \
+    const nonText = ['checkbox', 'radio', 'button', 'submit', 'reset', 'image', 'file', 'range', 'color', 'hidden']; \
+    return el.tagName === 'INPUT' && !nonText.includes(el.type); \
+})()";
+
+/// Characters left in the focused `<input>`/`<textarea>`/contenteditable host
+const FOCUSED_FIELD_LENGTH_JS: &str = "(() => { \
+    const el = document.activeElement; \
+    if (!el) return 0; \
+    if (typeof el.value === 'string') return el.value.length; \
+    return el.isContentEditable ? el.textContent.length : 0; \
+})()";
+
+/// Select everything in the focused field, for fields that ignore Ctrl+A
+const SELECT_FOCUSED_FIELD_JS: &str = "(() => { \
+    const el = document.activeElement; \
+    if (!el) return; \
+    if (typeof el.select === 'function') { el.select(); return; } \
+    if (el.isContentEditable) { \
+        const range = document.createRange(); \
+        range.selectNodeContents(el); \
+        const selection = window.getSelection(); \
+        selection.removeAllRanges(); \
+        selection.addRange(range); \
+    } \
+})()";
+
+/// Share of the Hick's Law delay still spent on a page seen recently (learning curve)
+const FAMILIAR_PAGE_SPEEDUP: f64 = 0.6;
+
+/// Roles Chrome's AX tree and the DOM name differently for the same control
+const ROLE_ALIASES: &[&[&str]] = &[
+    &["combobox", "PopUpButton", "listbox"],
+    &["textbox", "searchbox"],
+    &["option", "MenuListOption"],
+];
+
+/// How `execute_with_verification` decides a click worked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClickVerification {
+    /// The screen's perceptual hash moved by at least `change_threshold`
+    #[default]
+    VisualHash,
+    
+    /// The DOM click event reached an element with the role of the AX element
+    /// under the cursor. A different receiver (a transparent overlay, a
+    /// honeypot) fails with `ChimeraError::ClickIntercepted` rather than being
+    /// retried. Falls back to the visual hash where no clickable AX element
+    /// covers the point, or the event went into a child frame.
+    DomEvent,
+}
+
+/// OODA verification tuning
+#[derive(Debug, Clone)]
+pub struct OodaConfig {
+    /// Minimum Hamming distance (of 64 bits) between pre/post perceptual hashes
+    /// that counts as "the screen changed". A caret blink flips ~1 bit; a modal
+    /// opening or a navigation flips dozens.
+    pub change_threshold: u32,
+    
+    /// First retry waits up to this long; each further retry doubles the cap
+    pub retry_base_delay: Duration,
+    
+    /// Upper bound on any single retry wait
+    pub retry_max_delay: Duration,
+    
+    /// How long to wait for the screen to change after acting
+    pub settle_timeout: Duration,
+    
+    /// How often the visual hash is re-checked while settling
+    pub settle_poll_interval: Duration,
+    
+    /// Whether clicks are verified by screen change or by the DOM event's target
+    pub click_verification: ClickVerification,
+}
+
+impl Default for OodaConfig {
+    fn default() -> Self {
+        Self {
+            change_threshold: 5,
+            retry_base_delay: Duration::from_millis(400),
+            retry_max_delay: Duration::from_secs(8),
+            settle_timeout: Duration::from_secs(4),
+            settle_poll_interval: Duration::from_millis(150),
+            click_verification: ClickVerification::default(),
+        }
+    }
+}
+
+impl OodaConfig {
+    /// Wait before retry number `retry` (0-based): exponential backoff with full jitter
+    /// 
+    /// Uniform in `[0, min(max, base * 2^retry)]`, so retries never fall into
+    /// a fixed, machine-like cadence. Jitter comes from `rng` (the session's
+    /// pacing stream).
+    pub fn retry_delay(&self, retry: u32, rng: &mut StdRng) -> Duration {
+        let cap = self.retry_base_delay
+            .saturating_mul(1u32 << retry.min(16))
+            .min(self.retry_max_delay);
+        cap.mul_f64(rng.gen_range(0.0..=1.0))
+    }
+}
+
+/// Poll the visual hash until it moves away from `initial_hash` or `settle_timeout` passes
+/// 
+/// Returns the last hash seen, so fast pages are verified as soon as they
+/// react instead of after a flat sleep.
+async fn wait_for_change(session: &BrowserSession, initial_hash: &str, config: &OodaConfig) -> Result<String> {
+    let started = Instant::now();
+    loop {
+        sleep(config.settle_poll_interval).await;
+        let hash = session
+            .get_visual_hash()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get new visual hash: {}", e)))?;
+        
+        if screen_changed(initial_hash, &hash, config) || started.elapsed() >= config.settle_timeout {
+            debug!("Settled after {:?}", started.elapsed());
+            return Ok(hash);
+        }
+    }
+}
+
+/// One pass through the OODA loop
+#[derive(Debug, Clone)]
+pub struct OodaAttempt {
+    /// 1-based attempt number
+    pub attempt: u32,
+    
+    /// Vision service confidence for the chosen target
+    pub confidence: f32,
+    
+    /// Prefix of the visual hash before acting
+    pub initial_hash: String,
+    
+    /// Prefix of the visual hash after acting
+    pub new_hash: String,
+    
+    /// Whether the change cleared the threshold
+    pub changed: bool,
+    
+    /// Element the click was confirmed to land on (`ClickVerification::DomEvent`)
+    pub landed_on: Option<String>,
+    
+    /// `AxDiff::summary` when the screen barely moved but the AX tree did
+    pub ax_changes: Option<String>,
+}
+
+/// Per-attempt telemetry from a verified action
+#[derive(Debug, Clone, Default)]
+pub struct OodaReport {
+    pub attempts: Vec<OodaAttempt>,
+}
+
+impl OodaReport {
+    /// Whether the final attempt changed the screen (or AX tree) or landed on its target
+    pub fn succeeded(&self) -> bool {
+        self.attempts.last().is_some_and(|a| a.changed || a.landed_on.is_some() || a.ax_changes.is_some())
+    }
+    
+    /// One-line summary, e.g. `2 attempts: #1 conf 0.41 a1b2c3d4->a1b2c3d4 unchanged; #2 ...`
+    pub fn summary(&self) -> String {
+        let attempts: Vec<String> = self.attempts
+            .iter()
+            .map(|a| format!(
+                "#{} conf {:.2} {}->{} {}",
+                a.attempt,
+                a.confidence,
+                a.initial_hash,
+                a.new_hash,
+                match &a.landed_on {
+                    Some(target) => format!("landed on {}", target),
+                    None if a.changed => "changed".to_string(),
+                    None => match &a.ax_changes {
+                        Some(changes) => format!("ax {}", changes),
+                        None => "unchanged".to_string(),
+                    },
+                },
+            ))
+            .collect();
+        format!("{} attempt(s): {}", self.attempts.len(), attempts.join("; "))
+    }
+}
+
+/// What received a click, as recorded by the probe `arm_click_probe` installs
+#[derive(Debug, Clone, Deserialize)]
+pub struct ClickTarget {
+    /// Lowercase tag of the element the event was dispatched to
+    pub tag: String,
+    
+    /// Role (explicit or implicit) of its nearest interactive ancestor-or-self
+    pub role: Option<String>,
+    
+    /// The element's `id`, for logs
+    pub id: Option<String>,
+}
+
+impl ClickTarget {
+    /// e.g. `<div id=promo-overlay>` or `<span> in button`
+    pub fn describe(&self) -> String {
+        let id = self.id.as_deref().map(|id| format!(" id={}", id)).unwrap_or_default();
+        match &self.role {
+            Some(role) => format!("<{}{}> in {}", self.tag, id, role),
+            None => format!("<{}{}>", self.tag, id),
+        }
+    }
+}
+
+/// Role of the smallest clickable AX element whose bounds contain `(x, y)`
+/// 
+/// This is what the user sees under the cursor; invisible overlays have no
+/// accessible role and don't show up here, which is what makes them detectable.
+/// Live snapshots carry no bounds: resolve them for the clickable roles first
+/// (`Cortex::resolve_bounds`).
+pub fn expected_role_at(tree: &AxTree, x: f64, y: f64) -> Option<&str> {
+    tree.nodes
+        .iter()
+        .filter(|n| CLICKABLE_ROLES.contains(&n.role.as_str()))
+        .filter_map(|n| n.bounds.as_ref().map(|b| (n, b)))
+        .filter(|(_, b)| x >= b.x && x <= b.x + b.width && y >= b.y && y <= b.y + b.height)
+        .min_by(|(_, a), (_, b)| (a.width * a.height).total_cmp(&(b.width * b.height)))
+        .map(|(n, _)| n.role.as_str())
+}
+
+/// Whether a click aimed at an `expected` element landed on one like it
+/// 
+/// Fails with `ChimeraError::ClickIntercepted` naming the element that
+/// received the event instead.
+pub fn check_click_target(expected: &str, landed: &ClickTarget) -> Result<()> {
+    let matches = landed.role.as_deref().is_some_and(|role| {
+        role.eq_ignore_ascii_case(expected)
+            || ROLE_ALIASES.iter().any(|group| group.contains(&role) && group.contains(&expected))
+    });
+    if matches {
+        Ok(())
+    } else {
+        Err(ChimeraError::ClickIntercepted(format!(
+            "aimed at a {} but the click landed on {}",
+            expected,
+            landed.describe()
+        )))
+    }
+}
+
+/// Intent words that carry no part of the target's accessible name
+const INTENT_FILLER: &[&str] = &[
+    "click", "press", "tap", "select", "choose", "open", "enter", "fill", "find", "type",
+    "the", "a", "an", "on", "in", "into", "to",
+];
+
+/// Intent words naming the kind of element, with the AX role they mean
+const INTENT_ROLE_WORDS: &[(&str, &str)] = &[
+    ("button", "button"), ("link", "link"), ("field", "textbox"), ("input", "textbox"),
+    ("box", "textbox"), ("checkbox", "checkbox"), ("tab", "tab"), ("dropdown", "combobox"),
+    ("option", "option"), ("menu", "menuitem"),
+];
+
+/// Split an intent into the AX roles it could mean and the name to look for
+/// 
+/// "Click the Sign in button" is `(["button"], "Sign in")`. A role word
+/// narrows the roles to it and its aliases; without one every clickable role
+/// is a candidate.
+fn parse_intent(instruction: &str) -> (Vec<&'static str>, String) {
+    let mut roles: Vec<&str> = Vec::new();
+    let mut name_words: Vec<&str> = Vec::new();
+    for word in instruction.split_whitespace() {
+        let lower = word.to_lowercase();
+        if let Some((_, role)) = INTENT_ROLE_WORDS.iter().find(|(w, _)| *w == lower) {
+            roles.push(*role);
+        } else if !INTENT_FILLER.contains(&lower.as_str()) {
+            name_words.push(word);
+        }
+    }
+    let query = if name_words.is_empty() { instruction.to_string() } else { name_words.join(" ") };
+    
+    let candidates = if roles.is_empty() {
+        CLICKABLE_ROLES.to_vec()
+    } else {
+        CLICKABLE_ROLES
+            .iter()
+            .copied()
+            .filter(|c| {
+                roles.iter().any(|r| {
+                    c.eq_ignore_ascii_case(r) || ROLE_ALIASES.iter().any(|g| g.contains(r) && g.contains(c))
+                })
+            })
+            .collect()
+    };
+    (candidates, query)
+}
+
+/// Resolve an intent from the accessibility tree alone (the vision outage path)
+/// 
+/// "Click the Sign in button" becomes a fuzzy search (`find_node_fuzzy`
+/// scoring) for "sign in" among buttons (see `parse_intent`). The best match
+/// that is actually rendered wins; its geometry comes from the DOM
+/// (`Cortex::node_bounds`), since AX snapshots carry none. Returns CSS-pixel
+/// click coordinates and the match score as confidence, or None if nothing
+/// on screen scores `DEFAULT_FUZZY_THRESHOLD`.
+pub fn resolve_semantically(session: &BrowserSession, instruction: &str) -> Option<(i32, i32, f32)> {
+    let fusion = FusionState::from_session(session)
+        .map_err(|e| debug!("No AX tree for semantic fallback: {}", e))
+        .ok()?;
+    let (candidates, query) = parse_intent(instruction);
+    
+    let mut matches: Vec<(&AxNode, f64)> = candidates
+        .iter()
+        .filter_map(|role| fusion.find_node_fuzzy(role, &query, DEFAULT_FUZZY_THRESHOLD))
+        .collect();
+    matches.sort_by(|a, b| b.1.total_cmp(&a.1));
+    
+    let cortex = Cortex::new(session.get_tab().ok()?);
+    matches.into_iter().find_map(|(node, score)| {
+        let bounds = cortex.node_bounds(node)
+            .map_err(|e| debug!("No box for AX match {:?}: {}", node.name, e))
+            .ok()??;
+        debug!("Semantic AX match {:?} for '{}' (score {:.2})", node.name, query, score);
+        let (x, y) = (bounds.x + bounds.width / 2.0, bounds.y + bounds.height / 2.0);
+        Some((x.round() as i32, y.round() as i32, score as f32))
+    })
+}
+
+/// Find `instruction` on screen: vision first, the AX tree if vision fails
+/// 
+/// With no client (the service couldn't be reached at all) only the AX tree
+/// is tried. Errors only when both come up empty. The client reports the
+/// call to its breaker (`VisionClient::with_breaker`).
+async fn locate(
+    session: &BrowserSession,
+    vision_client: Option<&mut VisionClient>,
+    screenshot: Vec<u8>,
+    instruction: &str,
+) -> Result<(i32, i32, f32)> {
+    let vision_error = match vision_client {
+        Some(vision) => match vision.get_coordinates(screenshot, instruction.to_string()).await {
+            Ok((x, y, confidence)) => {
+                // Vision answers in screenshot pixels
+                let (x, y) = session.viewport().to_css(x, y);
+                return Ok((x, y, confidence));
+            }
+            Err(e) => e.to_string(),
+        },
+        None => "vision service unreachable".to_string(),
+    };
+    
+    match resolve_semantically(session, instruction) {
+        Some(found) => {
+            warn!("👁️  Vision failed ({}), resolved '{}' from the AX tree", vision_error, instruction);
+            Ok(found)
+        }
+        None => Err(ChimeraError::Vision(format!(
+            "Vision service error: {} (no AX tree match either)",
+            vision_error
+        ))),
+    }
+}
+
+/// A click probe armed by `arm_click_probe`
+struct ClickProbe {
+    /// Isolated world holding the listener and what it recorded
+    context_id: i64,
+    
+    /// Tag of the topmost element at the click point when the probe was armed
+    topmost: Option<String>,
+}
+
+/// Evaluate `expression` in the isolated world `context_id`, returning its value
+fn evaluate_in_world(tab: &Tab, context_id: i64, expression: &str) -> anyhow::Result<serde_json::Value> {
+    let result = tab.call_method("Runtime.evaluate", serde_json::json!({
+        "expression": expression,
+        "contextId": context_id,
+        "returnByValue": true,
+    }))?;
+    if let Some(exception) = result.get("exceptionDetails") {
+        anyhow::bail!("{}", exception);
+    }
+    Ok(result.pointer("/result/value").cloned().unwrap_or(serde_json::Value::Null))
+}
+
+/// Install a one-shot capture listener recording what the next click lands on
+/// 
+/// The listener lives in an isolated world (`Page.createIsolatedWorld`): it
+/// sees the page's DOM events, but the page can't see its globals. Also
+/// records the tag of the topmost element at `(x, y)` right now, so a click
+/// that vanishes into a child frame can be told apart from one that never fired.
+fn arm_click_probe(tab: &Tab, x: i32, y: i32) -> Result<ClickProbe> {
+    let arm_failed = |e: anyhow::Error| ChimeraError::ActionFailed(format!("Failed to arm click probe: {}", e));
+    
+    let frame_tree = tab.call_method("Page.getFrameTree", serde_json::json!({})).map_err(arm_failed)?;
+    let frame_id = frame_tree
+        .pointer("/frameTree/frame/id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| ChimeraError::ActionFailed("Failed to arm click probe: no main frame".to_string()))?;
+    let world = tab
+        .call_method("Page.createIsolatedWorld", serde_json::json!({ "frameId": frame_id }))
+        .map_err(arm_failed)?;
+    let context_id = world
+        .get("executionContextId")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| ChimeraError::ActionFailed("Failed to arm click probe: no execution context".to_string()))?;
+    
+    let script = format!(r#"
+        (() => {{
+            const implicit = {{ BUTTON: 'button', SELECT: 'combobox', TEXTAREA: 'textbox', SUMMARY: 'button', OPTION: 'option' }};
+            const inputs = {{ checkbox: 'checkbox', radio: 'radio', button: 'button', submit: 'button', reset: 'button',
+                              image: 'button', range: 'slider', number: 'spinbutton', search: 'searchbox' }};
+            const roleOf = (el) => el.getAttribute('role')
+                || (el.tagName === 'A' && el.hasAttribute('href') ? 'link' : null)
+                || (el.tagName === 'INPUT' ? (inputs[el.type] || 'textbox') : null)
+                || implicit[el.tagName]
+                || null;
+            const probe = {{ landed: null }};
+            probe.listener = (e) => {{
+                const hit = e.target instanceof Element ? e.target : e.target.parentElement;
+                let el = hit;
+                while (el && !roleOf(el)) el = el.parentElement;
+                probe.landed = JSON.stringify({{
+                    tag: hit ? hit.tagName.toLowerCase() : '#text',
+                    role: el ? roleOf(el) : null,
+                    id: hit && hit.id ? hit.id : null,
+                }});
+            }};
+            window.addEventListener('click', probe.listener, {{ capture: true, once: true }});
+            globalThis.probe = probe;
+            const top = document.elementFromPoint({x}, {y});
+            return top ? top.tagName.toLowerCase() : null;
+        }})()
+    "#);
+    
+    let topmost = evaluate_in_world(tab, context_id, &script).map_err(arm_failed)?;
+    Ok(ClickProbe {
+        context_id,
+        topmost: topmost.as_str().map(str::to_string),
+    })
+}
+
+/// What the armed probe recorded, removing its listener; None if no click
+/// event reached this document
+fn read_click_probe(tab: &Tab, probe: &ClickProbe) -> Result<Option<ClickTarget>> {
+    const READ_JS: &str = "(() => { \
+        const probe = globalThis.probe; \
+        if (!probe) return null; \
+        window.removeEventListener('click', probe.listener, { capture: true }); \
+        return probe.landed; \
+    })()";
+    
+    // A click that navigated took the isolated world (and any record) with the old document
+    let landed = match evaluate_in_world(tab, probe.context_id, READ_JS) {
+        Ok(landed) => landed,
+        Err(e) => {
+            debug!("Click probe world is gone ({}), treating the click as unobserved", e);
+            return Ok(None);
+        }
+    };
+    match landed.as_str() {
+        Some(json) => Ok(Some(serde_json::from_str(json)?)),
+        None => Ok(None),
+    }
+}
+
+/// Leading characters of a visual hash, enough to tell states apart in logs
+fn hash_prefix(hash: &str) -> String {
+    hash.chars().take(8).collect()
+}
+
+/// Whether two visual hashes differ by at least the configured threshold
+pub fn screen_changed(before: &str, after: &str, config: &OodaConfig) -> bool {
+    match visual_hash_distance(before, after) {
+        Some(distance) => {
+            debug!("Visual hash distance: {} (threshold {})", distance, config.change_threshold);
+            distance >= config.change_threshold
+        }
+        // Not perceptual hashes - fall back to exact comparison
+        None => before != after,
+    }
+}
+
 /// Execute an action with visual verification (OODA Loop)
 /// 
 /// This implements the "Nervous System" - it doesn't just hope the action worked,
@@ -25,11 +516,23 @@ use tracing::{debug, info, warn};
 /// 5. **Loop**: Verify screen changed, retry if not
 pub async fn execute_with_verification(
     session: &BrowserSession,
-    vision_client: &mut VisionClient,
+    vision_client: Option<&mut VisionClient>,
     instruction: &str,
     max_retries: u32,
-) -> Result<()> {
+) -> Result<OodaReport> {
+    execute_with_verification_config(session, vision_client, instruction, max_retries, &OodaConfig::default()).await
+}
+
+/// `execute_with_verification` with explicit verification tuning
+pub async fn execute_with_verification_config(
+    session: &BrowserSession,
+    mut vision_client: Option<&mut VisionClient>,
+    instruction: &str,
+    max_retries: u32,
+    config: &OodaConfig,
+) -> Result<OodaReport> {
     info!("Starting OODA loop for instruction: {}", instruction);
+    let mut report = OodaReport::default();
     
     for attempt in 0..max_retries {
         debug!("OODA Loop iteration {} of {}", attempt + 1, max_retries);
@@ -41,7 +544,7 @@ pub async fn execute_with_verification(
         
         debug!("Initial visual hash: {}", &initial_hash[..16]);
         
-        // ORIENT: Get coordinates from vision service
+        // ORIENT: Get coordinates from vision service (the AX tree if it fails)
         let screenshot = session
             .capture_screenshot()
             .map_err(|e| ChimeraError::ActionFailed(format!("Screenshot failed: {}", e)))?;
@@ -49,17 +552,15 @@ pub async fn execute_with_verification(
         // Get AX tree for cognitive delay calculation (Hick's Law)
         let tab = session.get_tab()
             .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get tab: {}", e)))?;
-        let cortex = crate::cortex::Cortex::new(tab);
-        let ax_tree = cortex.snapshot_accessibility_tree()
+        let cortex = crate::cortex::Cortex::new(tab.clone());
+        let mut ax_tree = cortex.snapshot_accessibility_tree()
             .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get AX tree: {}", e)))?;
         
-        // Apply cognitive delay based on visual complexity (Hick's Law)
-        apply_cognitive_delay(&ax_tree).await;
+        // Apply cognitive delay based on visual complexity (Hick's Law), less on familiar pages
+        let familiar = session.recall_page(&initial_hash, config.change_threshold);
+        apply_cognitive_delay(&ax_tree, familiar, &mut session.pacer().rng()).await;
         
-        let (x, y, confidence) = vision_client
-            .get_coordinates(screenshot, instruction.to_string())
-            .await
-            .map_err(|e| ChimeraError::Vision(format!("Vision service error: {}", e)))?;
+        let (x, y, confidence) = locate(session, vision_client.as_deref_mut(), screenshot, instruction).await?;
         
         debug!("Target identified at ({}, {}) with confidence: {:.2}", x, y, confidence);
         
@@ -68,32 +569,106 @@ pub async fn execute_with_verification(
             warn!("Low confidence ({:.2}), but proceeding with action", confidence);
         }
         
+        // DOM verification: note what should receive the click before making it
+        let expected_role = match config.click_verification {
+            ClickVerification::DomEvent => {
+                cortex.resolve_bounds(&mut ax_tree, |n| CLICKABLE_ROLES.contains(&n.role.as_str()))
+                    .map_err(|e| ChimeraError::ActionFailed(format!("Failed to resolve AX bounds: {}", e)))?;
+                expected_role_at(&ax_tree, x as f64, y as f64).map(str::to_string)
+            }
+            ClickVerification::VisualHash => None,
+        };
+        let probe = match &expected_role {
+            Some(_) => Some(arm_click_probe(&tab, x, y)?),
+            None => None,
+        };
+        
+        // Whatever moved between observing and now did so on its own (timers, carousels)
+        let pre_click = cortex.snapshot_accessibility_tree()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get AX tree: {}", e)))?;
+        let ambient = ax_tree.diff(&pre_click);
+        
         // ACT: Execute human-like click
         session
             .click_human_like(x, y, None)
             .await
             .map_err(|e| ChimeraError::ActionFailed(format!("Click failed: {}", e)))?;
         
-        // Wait for page to react (animations, navigation, etc.)
-        sleep(Duration::from_secs(2)).await;
+        if let (Some(expected), Some(probe)) = (&expected_role, &probe) {
+            match read_click_probe(&tab, probe)? {
+                Some(landed) => {
+                    // An intercepted click already did something; retrying would only repeat it
+                    if let Err(e) = check_click_target(expected, &landed) {
+                        warn!("🪤 {} (attempt {})", e, attempt + 1);
+                        return Err(e);
+                    }
+                    info!("✅ Action verified: click landed on {} (attempt {})", landed.describe(), attempt + 1);
+                    let new_hash = session
+                        .get_visual_hash()
+                        .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get new visual hash: {}", e)))?;
+                    report.attempts.push(OodaAttempt {
+                        attempt: attempt + 1,
+                        confidence,
+                        initial_hash: hash_prefix(&initial_hash),
+                        new_hash: hash_prefix(&new_hash),
+                        changed: screen_changed(&initial_hash, &new_hash, config),
+                        landed_on: Some(landed.describe()),
+                        ax_changes: None,
+                    });
+                    return Ok(report);
+                }
+                None => debug!(
+                    "No click event reached the page (topmost element {:?}), falling back to visual verification",
+                    probe.topmost
+                ),
+            }
+        }
         
-        // LOOP: Verify the screen changed
-        let new_hash = session
-            .get_visual_hash()
-            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get new visual hash: {}", e)))?;
+        // Wait for page to react (animations, navigation, etc.), returning as soon as it does
+        let new_hash = wait_for_change(session, &initial_hash, config).await?;
         
         debug!("New visual hash: {}", &new_hash[..16]);
         
-        if initial_hash != new_hash {
+        let changed = screen_changed(&initial_hash, &new_hash, config);
+        
+        // A checkbox ticking or a cart count going up barely moves pixels; the AX tree sees
+        // it. Focus moving to the target and ambient churn aren't the page reacting.
+        let ax_changes = if changed {
+            None
+        } else {
+            cortex.snapshot_accessibility_tree()
+                .map(|after| pre_click.diff(&after).reaction(&ambient))
+                .map_err(|e| debug!("AX re-snapshot failed, relying on the visual hash: {}", e))
+                .ok()
+                .filter(|diff| !diff.is_empty())
+                .map(|diff| diff.summary())
+        };
+        report.attempts.push(OodaAttempt {
+            attempt: attempt + 1,
+            confidence,
+            initial_hash: hash_prefix(&initial_hash),
+            new_hash: hash_prefix(&new_hash),
+            changed,
+            landed_on: None,
+            ax_changes: ax_changes.clone(),
+        });
+        
+        if changed {
             info!("✅ Action verified: Screen state changed (attempt {})", attempt + 1);
-            return Ok(()); // Success! The screen changed.
+            return Ok(report); // Success! The screen changed.
+        } else if let Some(changes) = ax_changes {
+            info!("✅ Action verified: AX tree changed {} (attempt {})", changes, attempt + 1);
+            return Ok(report);
         } else {
             warn!("⚠️  Screen didn't change after click (attempt {}/{})", attempt + 1, max_retries);
             
             if attempt < max_retries - 1 {
-                // Wait a bit longer and try again
+                crate::metrics::agent().ooda_retries.inc();
+                // Back off and try again
                 // Maybe the page is slow to load, or a popup appeared
-                sleep(Duration::from_secs(1)).await;
+                sleep(config.retry_delay(attempt, &mut session.pacer().rng())).await;
+                // The retry is a fresh action: keep the session's rhythm
+                session.pacer().wait_turn().await;
                 
                 // Optional: Check for popups or error messages
                 // In production, you'd ask the vision service: "Is there a popup blocking the action?"
@@ -102,18 +677,38 @@ pub async fn execute_with_verification(
     }
     
     Err(ChimeraError::ActionFailed(format!(
-        "Action failed after {} retries - screen state did not change",
-        max_retries
+        "Action failed after {} retries - screen state did not change ({})",
+        max_retries,
+        report.summary()
     )))
 }
 
 /// Execute a typing action with verification
+/// 
+/// With `overwrite` the field's current content (a pre-filled search box, a
+/// previous attempt) is selected and deleted before typing instead of being
+/// appended to. `None` overwrites when the click focused a text field (see
+/// `focused_text_entry`) and appends otherwise.
 pub async fn type_with_verification(
     session: &BrowserSession,
-    vision_client: &mut VisionClient,
+    vision_client: Option<&mut VisionClient>,
+    field_instruction: &str,
+    text: &str,
+    overwrite: Option<bool>,
+    max_retries: u32,
+) -> Result<()> {
+    type_with_verification_config(session, vision_client, field_instruction, text, overwrite, max_retries, &OodaConfig::default()).await
+}
+
+/// `type_with_verification` with explicit verification tuning
+pub async fn type_with_verification_config(
+    session: &BrowserSession,
+    mut vision_client: Option<&mut VisionClient>,
     field_instruction: &str,
     text: &str,
+    overwrite: Option<bool>,
     max_retries: u32,
+    config: &OodaConfig,
 ) -> Result<()> {
     info!("Typing action: '{}' in field matching '{}'", text, field_instruction);
     
@@ -128,10 +723,7 @@ pub async fn type_with_verification(
             .capture_screenshot()
             .map_err(|e| ChimeraError::ActionFailed(format!("Screenshot failed: {}", e)))?;
         
-        let (x, y, confidence) = vision_client
-            .get_coordinates(screenshot, field_instruction.to_string())
-            .await
-            .map_err(|e| ChimeraError::Vision(format!("Vision service error: {}", e)))?;
+        let (x, y, confidence) = locate(session, vision_client.as_deref_mut(), screenshot, field_instruction).await?;
         
         // DECIDE & ACT: Click field and type
         session
@@ -142,27 +734,28 @@ pub async fn type_with_verification(
         // Small delay before typing
         sleep(Duration::from_millis(100)).await;
         
-        // Type with human-like timing
         let tab = session.get_tab()
             .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get tab: {}", e)))?;
-        crate::mouse::human_type(&tab, text).await
-            .map_err(|e| ChimeraError::ActionFailed(format!("Type failed: {}", e)))?;
+        let overwrite = overwrite.unwrap_or_else(|| focused_text_entry(&tab));
+        if overwrite {
+            clear_focused_field(session, &tab).await?;
+        }
         
-        // Wait for any updates
-        sleep(Duration::from_secs(1)).await;
+        // Type with human-like timing
+        session.human_type(text).await
+            .map_err(|e| ChimeraError::ActionFailed(format!("Type failed: {}", e)))?;
         
         // VERIFY: Check if field was filled (visual change)
-        let new_hash = session
-            .get_visual_hash()
-            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get new visual hash: {}", e)))?;
+        let new_hash = wait_for_change(session, &initial_hash, config).await?;
         
-        if initial_hash != new_hash {
+        if screen_changed(&initial_hash, &new_hash, config) {
             info!("✅ Typing verified: Screen state changed");
             return Ok(());
         } else {
             warn!("⚠️  Screen didn't change after typing (attempt {}/{})", attempt + 1, max_retries);
             if attempt < max_retries - 1 {
-                sleep(Duration::from_secs(1)).await;
+                sleep(config.retry_delay(attempt, &mut session.pacer().rng())).await;
+                session.pacer().wait_turn().await;
             }
         }
     }
@@ -173,6 +766,223 @@ pub async fn type_with_verification(
     )))
 }
 
+/// Whether the element that has focus (after clicking a field) takes typed text
+pub fn focused_text_entry(tab: &Tab) -> bool {
+    tab.evaluate(FOCUSED_TEXT_ENTRY_JS, false)
+        .map_err(|e| debug!("Couldn't inspect the focused element: {}", e))
+        .ok()
+        .and_then(|result| result.value)
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false)
+}
+
+/// Empty the focused field the way a user would: select-all, then Backspace
+/// 
+/// Select-all is Cmd+A on macOS profiles and Ctrl+A elsewhere. Works for
+/// `<input>`, `<textarea>` and contenteditable hosts alike (select-all inside
+/// an editing host selects only its content). Fields that swallow the
+/// shortcut get their content selected through the DOM and a second Backspace.
+async fn clear_focused_field(session: &BrowserSession, tab: &Tab) -> Result<()> {
+    let remaining = || -> Result<u64> {
+        let result = tab.evaluate(FOCUSED_FIELD_LENGTH_JS, false)
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to read field content: {}", e)))?;
+        Ok(result.value.as_ref().and_then(|v| v.as_u64()).unwrap_or(0))
+    };
+    if remaining()? == 0 {
+        return Ok(());
+    }
+    
+    session
+        .press_key("a", &[session.shortcut_modifier()])
+        .map_err(|e| ChimeraError::ActionFailed(format!("Select-all failed: {}", e)))?;
+    sleep(Duration::from_millis(session.typing_rng().gen_range(60..160))).await;
+    session
+        .press_key("Backspace", &[])
+        .map_err(|e| ChimeraError::ActionFailed(format!("Backspace failed: {}", e)))?;
+    
+    if remaining()? > 0 {
+        debug!("Select-all didn't clear the field, selecting its content through the DOM");
+        tab.evaluate(SELECT_FOCUSED_FIELD_JS, false)
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to select field text: {}", e)))?;
+        session
+            .press_key("Backspace", &[])
+            .map_err(|e| ChimeraError::ActionFailed(format!("Backspace failed: {}", e)))?;
+        if remaining()? > 0 {
+            warn!("⚠️  Field still has content after clearing; typing will append to it");
+        }
+    }
+    Ok(())
+}
+
+/// Type into a specific AX node, focused directly rather than by clicking
+/// 
+/// Keystrokes can't land in whatever happens to cover the field. If the node
+/// can't be focused (gone, no DOM backing, not focusable) this falls back to
+/// the click-then-type path of `type_with_verification`.
+pub async fn type_into_node(
+    session: &BrowserSession,
+    vision_client: &mut VisionClient,
+    node_id: &str,
+    field_instruction: &str,
+    text: &str,
+    max_retries: u32,
+) -> Result<()> {
+    let tab = session.get_tab()
+        .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get tab: {}", e)))?;
+    
+    let initial_hash = session
+        .get_visual_hash()
+        .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get visual hash: {}", e)))?;
+    
+    if let Err(e) = Cortex::new(tab.clone()).focus_node(node_id) {
+        warn!("⚠️  Could not focus AX node {} ({}), falling back to click-then-type", node_id, e);
+        return type_with_verification(session, Some(vision_client), field_instruction, text, None, max_retries).await;
+    }
+    
+    info!("Typing action: '{}' into focused AX node {}", text, node_id);
+    sleep(Duration::from_millis(100)).await;
+    session.human_type(text).await
+        .map_err(|e| ChimeraError::ActionFailed(format!("Type failed: {}", e)))?;
+    
+    let config = OodaConfig::default();
+    let new_hash = wait_for_change(session, &initial_hash, &config).await?;
+    
+    if screen_changed(&initial_hash, &new_hash, &config) {
+        info!("✅ Typing verified: Screen state changed");
+        Ok(())
+    } else {
+        Err(ChimeraError::ActionFailed(format!(
+            "Typed into AX node {} but the screen didn't change",
+            node_id
+        )))
+    }
+}
+
+/// Type into a field and submit with Enter, verifying the submission
+/// 
+/// How humans use search boxes: click the field, type, hit Enter - no hunt for
+/// a submit button. Submission is verified by a URL change (navigation) or a
+/// change in the accessibility tree (in-page results); on failure the whole
+/// sequence is retried, replacing whatever the previous attempt typed.
+pub async fn type_and_submit(
+    session: &BrowserSession,
+    vision_client: Option<&mut VisionClient>,
+    field_instruction: &str,
+    text: &str,
+    max_retries: u32,
+) -> Result<()> {
+    type_and_submit_config(session, vision_client, field_instruction, text, max_retries, &OodaConfig::default()).await
+}
+
+/// `type_and_submit` with explicit verification tuning
+pub async fn type_and_submit_config(
+    session: &BrowserSession,
+    mut vision_client: Option<&mut VisionClient>,
+    field_instruction: &str,
+    text: &str,
+    max_retries: u32,
+    config: &OodaConfig,
+) -> Result<()> {
+    info!("Type-and-submit: '{}' in field matching '{}'", text, field_instruction);
+    
+    for attempt in 0..max_retries {
+        // OBSERVE
+        let initial_url = session
+            .get_url()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get URL: {}", e)))?;
+        
+        // ORIENT: Find the input field
+        let screenshot = session
+            .capture_screenshot()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Screenshot failed: {}", e)))?;
+        
+        let (x, y, _confidence) = locate(session, vision_client.as_deref_mut(), screenshot, field_instruction).await?;
+        
+        // ACT: Focus the field, clear whatever a previous attempt (or the page) left in it
+        session
+            .click_human_like(x, y, None)
+            .await
+            .map_err(|e| ChimeraError::ActionFailed(format!("Click failed: {}", e)))?;
+        
+        sleep(Duration::from_millis(100)).await;
+        
+        let tab = session.get_tab()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get tab: {}", e)))?;
+        if focused_text_entry(&tab) {
+            clear_focused_field(session, &tab).await?;
+        }
+        
+        session.human_type(text).await
+            .map_err(|e| ChimeraError::ActionFailed(format!("Type failed: {}", e)))?;
+        
+        // Baseline after typing, so the typed text and any suggestion list don't count as a reaction
+        let typed_hash = session
+            .get_visual_hash()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get visual hash: {}", e)))?;
+        let typed_ax = ax_signature(session);
+        
+        // Humans glance at what they typed before hitting Enter
+        let pause_ms = session.typing_rng().gen_range(150..450);
+        sleep(Duration::from_millis(pause_ms)).await;
+        session
+            .press_key("Enter", &[])
+            .map_err(|e| ChimeraError::ActionFailed(format!("Enter failed: {}", e)))?;
+        
+        // Wait for navigation / results to render, returning as soon as the page reacts
+        wait_for_change(session, &typed_hash, config).await?;
+        
+        // VERIFY: navigation or structural change
+        let new_url = session
+            .get_url()
+            .map_err(|e| ChimeraError::ActionFailed(format!("Failed to get URL: {}", e)))?;
+        if new_url != initial_url {
+            info!("✅ Submission verified: navigated to {}", new_url);
+            return Ok(());
+        }
+        
+        let new_ax = ax_signature(session);
+        if typed_ax.is_some() && new_ax.is_some() && typed_ax != new_ax {
+            info!("✅ Submission verified: accessibility tree changed");
+            return Ok(());
+        }
+        
+        warn!("⚠️  Submission not detected (attempt {}/{})", attempt + 1, max_retries);
+        if attempt < max_retries - 1 {
+            crate::metrics::agent().ooda_retries.inc();
+            sleep(config.retry_delay(attempt, &mut session.pacer().rng())).await;
+            session.pacer().wait_turn().await;
+        }
+    }
+    
+    Err(ChimeraError::ActionFailed(format!(
+        "Submit failed after {} retries - no navigation or page change",
+        max_retries
+    )))
+}
+
+/// Order-independent fingerprint of the page structure (role + name of every node)
+/// 
+/// None if the tree couldn't be captured, so callers don't mistake a CDP hiccup
+/// for a page change.
+fn ax_signature(session: &BrowserSession) -> Option<u64> {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    
+    let tab = session.get_tab().ok()?;
+    let tree = crate::cortex::Cortex::new(tab).snapshot_accessibility_tree().ok()?;
+    
+    let mut entries: Vec<(&str, &str)> = tree
+        .nodes
+        .iter()
+        .map(|n| (n.role.as_str(), n.name.as_deref().unwrap_or("")))
+        .collect();
+    entries.sort_unstable();
+    
+    let mut hasher = DefaultHasher::new();
+    entries.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
 /// Apply cognitive delay based on Hick's Law
 /// 
 /// Hick's Law: Reaction time = b * log2(n + 1)
@@ -180,7 +990,12 @@ pub async fn type_with_verification(
 /// 
 /// The Problem: Bots click too fast. Real humans take longer on complex pages.
 /// The Fix: Calculate visual complexity and add proportional delay.
-pub async fn apply_cognitive_delay(ax_tree: &AxTree) {
+/// 
+/// Humans also get faster on pages they've just seen, so with `visited_before`
+/// only `FAMILIAR_PAGE_SPEEDUP` of the delay is spent. A flat delay on every
+/// revisit is itself a timing signature. Jitter comes from `rng` (the
+/// session's pacing stream, see `PacingGovernor::rng`).
+pub async fn apply_cognitive_delay(ax_tree: &AxTree, visited_before: bool, rng: &mut StdRng) {
     // Count clickable elements (buttons, links, inputs)
     let n = ax_tree.nodes.iter()
         .filter(|node| {
@@ -195,15 +1010,99 @@ pub async fn apply_cognitive_delay(ax_tree: &AxTree) {
     // Base reaction time (200ms) + Processing time per element
     let base_delay_ms = 200u64;
     let processing_per_element = 100.0;
-    let delay_ms = base_delay_ms + (processing_per_element * (n as f64 + 1.0).log2()) as u64;
+    let mut delay_ms = base_delay_ms + (processing_per_element * (n as f64 + 1.0).log2()) as u64;
+    if visited_before {
+        delay_ms = (delay_ms as f64 * FAMILIAR_PAGE_SPEEDUP) as u64;
+    }
     
     // Add randomness (Human Jitter) - humans are not perfectly consistent
-    let mut rng = rand::thread_rng();
     let jitter = rng.gen_range(0..150);
     
     let total_delay = delay_ms + jitter;
     
-    info!("🧠 Thinking... (Cognitive Load: {}ms for {} clickable elements)", total_delay, n);
+    info!(
+        "🧠 Thinking... (Cognitive Load: {}ms for {} clickable elements{})",
+        total_delay,
+        n,
+        if visited_before { ", familiar page" } else { "" }
+    );
     
     tokio::time::sleep(Duration::from_millis(total_delay)).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cortex::AxBounds;
+
+    fn node(role: &str, bounds: (f64, f64, f64, f64)) -> AxNode {
+        let (x, y, width, height) = bounds;
+        AxNode {
+            node_id: role.to_string(),
+            role: role.to_string(),
+            name: None,
+            value: None,
+            parent_id: None,
+            bounds: Some(AxBounds { x, y, width, height }),
+            state: Vec::new(),
+            frame_id: None,
+            backend_node_id: None,
+        }
+    }
+
+    fn target(tag: &str, role: Option<&str>, id: Option<&str>) -> ClickTarget {
+        ClickTarget {
+            tag: tag.to_string(),
+            role: role.map(str::to_string),
+            id: id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_expected_role_is_smallest_clickable_under_point() {
+        let tree = AxTree {
+            nodes: vec![
+                node("main", (0.0, 0.0, 1280.0, 720.0)),
+                node("listbox", (100.0, 100.0, 300.0, 200.0)),
+                node("option", (110.0, 110.0, 280.0, 30.0)),
+            ],
+        };
+        assert_eq!(expected_role_at(&tree, 120.0, 120.0), Some("option"));
+        assert_eq!(expected_role_at(&tree, 120.0, 250.0), Some("listbox"));
+        assert_eq!(expected_role_at(&tree, 600.0, 600.0), None);
+    }
+
+    #[test]
+    fn test_parse_intent() {
+        assert_eq!(parse_intent("Click the Sign in button"), (vec!["button"], "Sign in".to_string()));
+        assert_eq!(
+            parse_intent("choose the Country dropdown"),
+            (vec!["combobox", "listbox", "PopUpButton"], "Country".to_string())
+        );
+        
+        // No role word: any clickable role; nothing but filler: the whole instruction
+        let (roles, query) = parse_intent("Accept all cookies");
+        assert_eq!(roles, CLICKABLE_ROLES.to_vec());
+        assert_eq!(query, "Accept all cookies");
+        assert_eq!(parse_intent("click the link").1, "click the link");
+    }
+
+    #[test]
+    fn test_check_click_target() {
+        // A span inside the button, or an alias of the expected role, is a hit
+        assert!(check_click_target("button", &target("span", Some("button"), None)).is_ok());
+        assert!(check_click_target("combobox", &target("select", Some("PopUpButton"), None)).is_ok());
+        
+        // A transparent overlay has no role; another control is a different role
+        let overlay = target("div", None, Some("promo-overlay"));
+        assert_eq!(overlay.describe(), "<div id=promo-overlay>");
+        match check_click_target("button", &overlay) {
+            Err(ChimeraError::ClickIntercepted(message)) => {
+                assert_eq!(message, "aimed at a button but the click landed on <div id=promo-overlay>");
+            }
+            other => panic!("expected ClickIntercepted, got {:?}", other),
+        }
+        assert!(check_click_target("button", &target("a", Some("link"), None)).is_err());
+        assert_eq!(target("span", Some("button"), None).describe(), "<span> in button");
+    }
+}
@@ -0,0 +1,272 @@
+/// Pacing Governor - Session-Level Action Cadence
+///
+/// Per-action jitter (click dwell, keystroke delays) makes each action look
+/// human, but the *rhythm* of a whole mission still gives a bot away: nobody
+/// sustains one action every 300ms for twenty steps without pausing to read,
+/// getting distracted, or slowing down as they tire.
+///
+/// The governor sits on the session and is consulted before *any* action
+/// (click, type, scroll). Gaps are log-normal around the configured
+/// actions-per-minute (mostly short, with a long idle tail), come in
+/// occasional quick bursts, are sometimes replaced by a much longer "reading"
+/// pause, and stretch as the session ages (fatigue).
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::{Distribution, LogNormal};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::debug;
+
+/// Pacing parameters
+#[derive(Debug, Clone)]
+pub struct PacingConfig {
+    /// Mean cadence outside bursts and pauses; sets the log-normal gap mean
+    pub actions_per_minute: f64,
+
+    /// Log-normal shape: higher = more very short and very long gaps around the same mean
+    pub gap_sigma: f64,
+
+    /// Minimum gap between any two actions (bursts included)
+    pub min_gap: Duration,
+
+    /// Upper bound on a single gap before fatigue (caps the log-normal tail)
+    pub max_gap: Duration,
+
+    /// Probability that a gap becomes a longer "reading" pause
+    pub long_pause_probability: f64,
+
+    /// Range of a reading pause
+    pub long_pause: (Duration, Duration),
+
+    /// Probability that an action starts a burst of quick follow-ups
+    pub burst_probability: f64,
+
+    /// Number of quick actions in a burst (inclusive range)
+    pub burst_length: (u32, u32),
+
+    /// Gap divisor inside a burst
+    pub burst_speedup: f64,
+
+    /// Gap multiplier growth per minute of session time
+    pub fatigue_per_minute: f64,
+
+    /// Cap on the fatigue multiplier
+    pub max_fatigue: f64,
+}
+
+impl Default for PacingConfig {
+    fn default() -> Self {
+        Self {
+            actions_per_minute: 40.0,
+            gap_sigma: 0.5,
+            min_gap: Duration::from_millis(250),
+            max_gap: Duration::from_secs(20),
+            long_pause_probability: 0.08,
+            long_pause: (Duration::from_secs(3), Duration::from_secs(9)),
+            burst_probability: 0.1,
+            burst_length: (2, 4),
+            burst_speedup: 3.0,
+            fatigue_per_minute: 0.03,
+            max_fatigue: 1.8,
+        }
+    }
+}
+
+/// Mutable pacing state, under one lock so slot reservation stays atomic
+#[derive(Debug, Default)]
+struct PacingState {
+    /// When the most recent action was (or is scheduled to be) released
+    last_action: Option<Instant>,
+
+    /// Quick actions left in the current burst
+    burst_remaining: u32,
+}
+
+/// Shared per-session pacing state
+#[derive(Debug)]
+pub struct PacingGovernor {
+    config: PacingConfig,
+    started: Instant,
+    gaps: LogNormal<f64>,
+    state: Mutex<PacingState>,
+
+    /// Source of gap and think-time entropy; seeded for reproducible pacing
+    rng: Mutex<StdRng>,
+}
+
+impl PacingGovernor {
+    pub fn new(config: PacingConfig) -> Self {
+        // mean = exp(mu + sigma^2 / 2), so pick mu for the configured cadence
+        let mean_secs = 60.0 / config.actions_per_minute.max(0.1);
+        let sigma = config.gap_sigma.max(0.0);
+        let mu = mean_secs.ln() - sigma * sigma / 2.0;
+        Self {
+            gaps: LogNormal::new(mu, sigma).expect("finite log-normal parameters"),
+            config,
+            started: Instant::now(),
+            state: Mutex::new(PacingState::default()),
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// Seed the governor so gaps, bursts and pauses replay identically
+    ///
+    /// `None` keeps the default OS-seeded entropy.
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        if let Some(seed) = seed {
+            self.rng = Mutex::new(StdRng::seed_from_u64(seed));
+        }
+        self
+    }
+
+    /// Per-call RNG from the pacing stream, for other think-time jitter
+    /// (e.g. `apply_cognitive_delay`); deterministic when the governor is seeded
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.rng.lock().unwrap().gen())
+    }
+
+    /// Current fatigue multiplier (1.0 at session start)
+    pub fn fatigue(&self) -> f64 {
+        let minutes = self.started.elapsed().as_secs_f64() / 60.0;
+        (1.0 + self.config.fatigue_per_minute * minutes).min(self.config.max_fatigue)
+    }
+
+    /// Reserve the next action slot and return how long to wait for it
+    ///
+    /// The slot is claimed immediately, so concurrent callers queue up behind
+    /// each other instead of firing together.
+    pub fn reserve(&self) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        let gap = self.next_gap(&mut state).mul_f64(self.fatigue());
+
+        let now = Instant::now();
+        let release = match state.last_action {
+            Some(last) => (last + gap).max(now),
+            None => now,
+        };
+        state.last_action = Some(release);
+
+        release - now
+    }
+
+    /// Draw the gap before the next action, advancing burst state
+    fn next_gap(&self, state: &mut PacingState) -> Duration {
+        let mut rng = self.rng.lock().unwrap();
+        let config = &self.config;
+
+        if state.burst_remaining > 0 {
+            state.burst_remaining -= 1;
+            return self.sample_gap_with(&mut rng).div_f64(config.burst_speedup.max(1.0)).max(config.min_gap);
+        }
+        if rng.gen_bool(config.long_pause_probability.clamp(0.0, 1.0)) {
+            return rng.gen_range(config.long_pause.0..=config.long_pause.1);
+        }
+        if rng.gen_bool(config.burst_probability.clamp(0.0, 1.0)) {
+            // This gap is normal; the next few come quickly
+            state.burst_remaining = rng.gen_range(config.burst_length.0..=config.burst_length.1.max(config.burst_length.0));
+        }
+        self.sample_gap_with(&mut rng)
+    }
+
+    /// Log-normal gap around the configured cadence, within [min_gap, max_gap]
+    fn sample_gap(&self) -> Duration {
+        self.sample_gap_with(&mut self.rng.lock().unwrap())
+    }
+
+    fn sample_gap_with(&self, rng: &mut StdRng) -> Duration {
+        let secs = self.gaps.sample(rng);
+        Duration::from_secs_f64(secs).clamp(self.config.min_gap, self.config.max_gap.max(self.config.min_gap))
+    }
+
+    /// Look at the result of an action before moving on
+    /// 
+    /// A log-normal glance (about a third of the mean gap) that doesn't claim
+    /// an action slot; replaces flat post-action sleeps.
+    pub async fn dwell(&self) {
+        let dwell = self.sample_gap().div_f64(3.0).mul_f64(self.fatigue());
+        debug!("Pacing: dwelling {:?} on the result", dwell);
+        sleep(dwell).await;
+    }
+
+    /// Wait until this session may perform its next action
+    pub async fn wait_turn(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            debug!("Pacing: waiting {:?} before next action (fatigue {:.2})", wait, self.fatigue());
+            sleep(wait).await;
+        }
+    }
+}
+
+impl Default for PacingGovernor {
+    fn default() -> Self {
+        Self::new(PacingConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_action_is_immediate_then_gapped() {
+        let governor = PacingGovernor::new(PacingConfig {
+            long_pause_probability: 0.0,
+            burst_probability: 0.0,
+            ..PacingConfig::default()
+        });
+
+        assert!(governor.reserve().is_zero());
+        let wait = governor.reserve();
+        assert!(wait >= Duration::from_millis(200), "gap too short: {:?}", wait);
+        assert!(wait <= Duration::from_secs(20));
+    }
+
+    #[test]
+    fn test_gaps_follow_actions_per_minute() {
+        let governor = PacingGovernor::new(PacingConfig {
+            actions_per_minute: 30.0,
+            ..PacingConfig::default()
+        });
+
+        let samples = 5000;
+        let mean = (0..samples).map(|_| governor.sample_gap().as_secs_f64()).sum::<f64>() / samples as f64;
+        assert!((mean - 2.0).abs() < 0.2, "mean gap {:.2}s, expected ~2s", mean);
+    }
+
+    #[test]
+    fn test_burst_gaps_are_shorter() {
+        let governor = PacingGovernor::new(PacingConfig {
+            long_pause_probability: 0.0,
+            burst_probability: 1.0,
+            burst_length: (3, 3),
+            gap_sigma: 0.0,
+            ..PacingConfig::default()
+        });
+
+        let mut state = PacingState::default();
+        let normal = governor.next_gap(&mut state);
+        assert_eq!(state.burst_remaining, 3);
+        let quick = governor.next_gap(&mut state);
+        assert!(quick < normal, "burst gap {:?} not shorter than {:?}", quick, normal);
+        assert_eq!(state.burst_remaining, 2);
+    }
+
+    #[test]
+    fn test_seeded_governors_draw_the_same_gaps() {
+        let a = PacingGovernor::default().with_seed(Some(7));
+        let b = PacingGovernor::default().with_seed(Some(7));
+        let (mut state_a, mut state_b) = (PacingState::default(), PacingState::default());
+        for _ in 0..20 {
+            assert_eq!(a.next_gap(&mut state_a), b.next_gap(&mut state_b));
+        }
+    }
+
+    #[test]
+    fn test_fatigue_starts_at_one() {
+        let governor = PacingGovernor::default();
+        assert!((governor.fatigue() - 1.0).abs() < 0.01);
+    }
+}
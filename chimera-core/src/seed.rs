@@ -0,0 +1,156 @@
+/// Session Seed - One Source of Randomness per Session
+///
+/// Canvas noise, mouse paths, keystroke timing and action pacing each need
+/// their own random stream, but drawn from independent OS entropy they don't
+/// add up to one consistent person, and a session can't be replayed. A
+/// session owns one master seed instead; every subsystem gets a sub-seed
+/// derived from it, so the same master seed reproduces the same behaviour and
+/// can be pinned to a grafted profile as a stable behavioural identity.
+///
+/// Sub-seeds are independent of each other: knowing the canvas seed (which
+/// pages can partly observe) says nothing about the mouse or typing streams.
+///
+/// A profile pins only who it is: the same person renders the same canvas
+/// and has the same motor habits every visit, but never moves the mouse
+/// along the same path twice.
+/// Behavioural streams mix in a per-session nonce on top of the master seed.
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+/// Subsystem a sub-seed is derived for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeedStream {
+    /// Canvas/WebGL entropy (`DbiConfig::session_seed`)
+    Dbi,
+
+    /// Mouse trajectories, fidgets and scrolls
+    Mouse,
+
+    /// Keystroke timing and typos
+    Typing,
+
+    /// Action cadence and think time (`PacingGovernor`)
+    Pacing,
+
+    /// Motor personality (`MotorProfile::for_profile`)
+    Motor,
+}
+
+impl SeedStream {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Dbi => "dbi",
+            Self::Mouse => "mouse",
+            Self::Typing => "typing",
+            Self::Pacing => "pacing",
+            Self::Motor => "motor",
+        }
+    }
+
+    /// Whether the stream identifies the profile rather than the session
+    fn pinned(self) -> bool {
+        matches!(self, Self::Dbi | Self::Motor)
+    }
+}
+
+/// A session's master seed and behavioural nonce
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SessionSeed {
+    master: u64,
+    nonce: u64,
+}
+
+impl SessionSeed {
+    /// Fully reproducible seed: every stream follows from `master`
+    pub fn new(master: u64) -> Self {
+        Self { master, nonce: 0 }
+    }
+
+    /// Fresh OS-entropy seed (sessions launched without one)
+    pub fn random() -> Self {
+        Self::new(rand::random())
+    }
+
+    /// Seed for a grafted profile: the fingerprint stream is stable across
+    /// restarts, the behavioural streams get a fresh nonce every session
+    pub fn for_profile(profile_id: &str) -> Self {
+        Self::new(hash_u64(&[b"profile", profile_id.as_bytes()])).with_nonce(rand::random())
+    }
+
+    /// Replace the behavioural nonce (replaying a recorded profile session)
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.nonce = nonce;
+        self
+    }
+
+    pub fn master(&self) -> u64 {
+        self.master
+    }
+
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Sub-seed for `stream`
+    pub fn derive(&self, stream: SeedStream) -> u64 {
+        if stream.pinned() {
+            hash_u64(&[&self.master.to_le_bytes(), stream.label().as_bytes()])
+        } else {
+            hash_u64(&[
+                &self.master.to_le_bytes(),
+                &self.nonce.to_le_bytes(),
+                stream.label().as_bytes(),
+            ])
+        }
+    }
+
+    /// Generator for `stream`
+    pub fn rng(&self, stream: SeedStream) -> StdRng {
+        StdRng::seed_from_u64(self.derive(stream))
+    }
+}
+
+/// First 8 bytes of SHA-256 over length-prefixed parts
+fn hash_u64(parts: &[&[u8]]) -> u64 {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    let digest = hasher.finalize();
+    u64::from_le_bytes(digest[..8].try_into().expect("SHA-256 is 32 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_sub_seeds_are_stable_and_distinct() {
+        let seed = SessionSeed::new(42);
+        assert_eq!(seed.derive(SeedStream::Mouse), SessionSeed::new(42).derive(SeedStream::Mouse));
+        assert_ne!(seed.derive(SeedStream::Mouse), seed.derive(SeedStream::Typing));
+        assert_ne!(seed.derive(SeedStream::Dbi), SessionSeed::new(43).derive(SeedStream::Dbi));
+
+        let (mut a, mut b) = (seed.rng(SeedStream::Pacing), seed.rng(SeedStream::Pacing));
+        for _ in 0..8 {
+            assert_eq!(a.gen::<u32>(), b.gen::<u32>());
+        }
+
+        let (first, second) = (SessionSeed::for_profile("synth_1"), SessionSeed::for_profile("synth_1"));
+        assert_eq!(first.derive(SeedStream::Dbi), second.derive(SeedStream::Dbi));
+        assert_ne!(first.derive(SeedStream::Mouse), second.derive(SeedStream::Mouse));
+        assert_ne!(first.derive(SeedStream::Pacing), second.derive(SeedStream::Pacing));
+        assert_ne!(
+            first.derive(SeedStream::Dbi),
+            SessionSeed::for_profile("synth_2").derive(SeedStream::Dbi)
+        );
+        assert_eq!(
+            first.with_nonce(7).derive(SeedStream::Mouse),
+            second.with_nonce(7).derive(SeedStream::Mouse)
+        );
+    }
+}
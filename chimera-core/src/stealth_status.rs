@@ -0,0 +1,130 @@
+/// Stealth Status - Which Protections Actually Applied to a Session
+///
+/// Binary patching, DBI hooks, bio-BIOS, identity grafting, proxy routing and
+/// locale emulation each live in their own module and used to fail with a scattered warning.
+/// `StealthStatus` is assembled while a `BrowserSession` launches and records,
+/// per measure, whether it applied - and if not, why - so one struct tells the
+/// truth about a session's protection level.
+
+use serde::Serialize;
+use std::fmt;
+
+/// Outcome of a single stealth measure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "reason", rename_all = "snake_case")]
+pub enum MeasureState {
+    /// The measure is active
+    Applied,
+
+    /// Deliberately not applied (disabled by config, not requested)
+    Skipped(String),
+
+    /// Tried and failed - the session is less protected than intended
+    Failed(String),
+}
+
+impl MeasureState {
+    pub fn is_applied(&self) -> bool {
+        matches!(self, Self::Applied)
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+
+    /// State name as reported over RPC ("applied", "skipped", "failed")
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Skipped(_) => "skipped",
+            Self::Failed(_) => "failed",
+        }
+    }
+
+    /// Why the measure was skipped or failed (empty when applied)
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::Applied => "",
+            Self::Skipped(reason) | Self::Failed(reason) => reason,
+        }
+    }
+
+    /// Record an injection result, keeping the error text on failure
+    pub fn from_result<T>(result: &anyhow::Result<T>) -> Self {
+        match result {
+            Ok(_) => Self::Applied,
+            Err(e) => Self::Failed(format!("{:#}", e)),
+        }
+    }
+}
+
+impl fmt::Display for MeasureState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Applied => write!(f, "applied"),
+            Self::Skipped(reason) => write!(f, "skipped ({})", reason),
+            Self::Failed(reason) => write!(f, "FAILED ({})", reason),
+        }
+    }
+}
+
+/// Per-session record of every stealth measure
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StealthStatus {
+    /// Chromium binary automation-marker patching (process-wide)
+    pub binary_patch: MeasureState,
+
+    /// Canvas/WebGL entropy hooks
+    pub dbi_hooks: MeasureState,
+
+    /// Hardware fingerprint masking (cores, memory, GPU)
+    pub bio_bios: MeasureState,
+
+    /// Lived-in identity (cookies/history from a SyntheticProfile)
+    pub profile_grafting: MeasureState,
+
+    /// Traffic routed through the Phantom Proxy
+    pub proxy_routing: MeasureState,
+
+    /// Timezone, locale, Accept-Language and Client Hints matching the grafted profile
+    pub locale_emulation: MeasureState,
+}
+
+impl StealthStatus {
+    /// Whether every measure applied
+    pub fn fully_protected(&self) -> bool {
+        self.measures().iter().all(|(_, state)| state.is_applied())
+    }
+
+    /// Measures that were attempted and failed
+    pub fn failures(&self) -> Vec<&'static str> {
+        self.measures()
+            .iter()
+            .filter(|(_, state)| state.is_failed())
+            .map(|(name, _)| *name)
+            .collect()
+    }
+
+    /// Every measure by name, in a fixed order
+    pub fn measures(&self) -> [(&'static str, &MeasureState); 6] {
+        [
+            ("binary_patch", &self.binary_patch),
+            ("dbi_hooks", &self.dbi_hooks),
+            ("bio_bios", &self.bio_bios),
+            ("profile_grafting", &self.profile_grafting),
+            ("proxy_routing", &self.proxy_routing),
+            ("locale_emulation", &self.locale_emulation),
+        ]
+    }
+}
+
+impl fmt::Display for StealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .measures()
+            .iter()
+            .map(|(name, state)| format!("{}={}", name, state))
+            .collect();
+        write!(f, "{}", parts.join(", "))
+    }
+}
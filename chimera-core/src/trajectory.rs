@@ -0,0 +1,241 @@
+/// Trajectory Debugging - See What the Mouse Actually Did
+///
+/// When a site flags a session, "the humanization is probably fine" isn't an
+/// answer. With `CHIMERA_TRAJECTORY_DEBUG` pointing at a file, every executed
+/// mouse movement (Bezier, WindMouse, neuromotor and diffusion alike) is
+/// appended to it as one JSON line: the generator, the target, and each point
+/// the cursor was moved to with its offset in ms from the start of the move.
+///
+/// `render_trajectory_svg` turns a recorded path into an SVG for eyeballing:
+/// the sample dots bunch up where the hand slows down, so acceleration,
+/// overshoot and correction are visible at a glance.
+///
+/// Unset (the default), nothing is collected and the movement loops skip the
+/// bookkeeping entirely.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Margin (px) around the path in `render_trajectory_svg`
+const SVG_PADDING: f64 = 20.0;
+
+/// JSONL file trajectories are appended to, set once at startup
+static DEBUG_LOG: OnceLock<PathBuf> = OnceLock::new();
+
+/// Serializes appends so concurrent sessions don't interleave lines
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// One cursor position of an executed movement
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TrajectoryPoint {
+    pub x: f64,
+    pub y: f64,
+
+    /// Milliseconds since the first point of the movement
+    pub t_ms: f64,
+}
+
+/// An executed mouse movement, as written to the debug log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trajectory {
+    /// Which generator produced it ("bezier", "windmouse", "neuromotor", "diffusion")
+    pub generator: String,
+
+    /// Where the movement was aimed (the path may overshoot it)
+    pub target: (f64, f64),
+
+    /// Unix time (ms) the movement was recorded
+    pub recorded_at_ms: u64,
+
+    pub points: Vec<TrajectoryPoint>,
+}
+
+impl Trajectory {
+    /// Build from movement steps: move to `(x, y)`, then wait `delay`
+    pub fn from_steps(
+        generator: &str,
+        target: (f64, f64),
+        steps: impl IntoIterator<Item = (f64, f64, Duration)>,
+    ) -> Self {
+        let mut elapsed = Duration::ZERO;
+        let points = steps
+            .into_iter()
+            .map(|(x, y, delay)| {
+                let point = TrajectoryPoint { x, y, t_ms: elapsed.as_secs_f64() * 1000.0 };
+                elapsed += delay;
+                point
+            })
+            .collect();
+        Self {
+            generator: generator.to_string(),
+            target,
+            recorded_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_millis() as u64),
+            points,
+        }
+    }
+
+    /// Total movement time (ms)
+    pub fn duration_ms(&self) -> f64 {
+        self.points.last().map_or(0.0, |p| p.t_ms)
+    }
+
+    /// `render_trajectory_svg` of this movement
+    pub fn to_svg(&self) -> String {
+        render_trajectory_svg(&self.points)
+    }
+}
+
+/// Append every executed movement to `path` from now on (`CHIMERA_TRAJECTORY_DEBUG`)
+///
+/// Only the first call takes effect.
+pub fn enable(path: PathBuf) {
+    let _ = DEBUG_LOG.set(path);
+}
+
+/// Whether movements should be collected at all
+pub fn enabled() -> bool {
+    DEBUG_LOG.get().is_some()
+}
+
+/// Append `trajectory` to the debug log, if enabled
+///
+/// A failing write is logged, never surfaced: debugging must not break clicks.
+pub fn record(trajectory: &Trajectory) {
+    let Some(path) = DEBUG_LOG.get() else {
+        return;
+    };
+    if let Err(e) = append(path, trajectory) {
+        warn!("Failed to record {} trajectory to {}: {}", trajectory.generator, path.display(), e);
+    }
+}
+
+fn append(path: &Path, trajectory: &Trajectory) -> Result<()> {
+    let mut line = serde_json::to_string(trajectory)?;
+    line.push('\n');
+    let _guard = WRITE_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?
+        .write_all(line.as_bytes())
+        .context("Failed to append trajectory")
+}
+
+/// Read back a debug log written by `record`
+pub fn load(path: &Path) -> Result<Vec<Trajectory>> {
+    let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    BufReader::new(file)
+        .lines()
+        .filter(|line| line.as_ref().map_or(true, |l| !l.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?))
+        .collect()
+}
+
+/// Draw a movement as a standalone SVG document
+///
+/// The path is a polyline over one dot per sample, from a green start marker
+/// to a red end marker. Coordinates are page pixels, shifted so the path's
+/// bounding box (plus padding) is the canvas.
+pub fn render_trajectory_svg(points: &[TrajectoryPoint]) -> String {
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for p in points {
+        min_x = min_x.min(p.x);
+        min_y = min_y.min(p.y);
+        max_x = max_x.max(p.x);
+        max_y = max_y.max(p.y);
+    }
+    if points.is_empty() {
+        (min_x, min_y, max_x, max_y) = (0.0, 0.0, 0.0, 0.0);
+    }
+    let width = max_x - min_x + 2.0 * SVG_PADDING;
+    let height = max_y - min_y + 2.0 * SVG_PADDING;
+    let at = |p: &TrajectoryPoint| (p.x - min_x + SVG_PADDING, p.y - min_y + SVG_PADDING);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{:.0}" height="{:.0}" viewBox="0 0 {:.1} {:.1}">"#,
+        width.ceil(), height.ceil(), width, height
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    let polyline: Vec<String> = points
+        .iter()
+        .map(|p| {
+            let (x, y) = at(p);
+            format!("{:.1},{:.1}", x, y)
+        })
+        .collect();
+    let _ = writeln!(
+        svg,
+        r##"<polyline points="{}" fill="none" stroke="#3b82f6" stroke-width="1.5"/>"##,
+        polyline.join(" ")
+    );
+    for p in points {
+        let (x, y) = at(p);
+        let _ = writeln!(
+            svg,
+            r##"<circle cx="{:.1}" cy="{:.1}" r="1.5" fill="#1e3a8a"><title>{:.0} ms</title></circle>"##,
+            x, y, p.t_ms
+        );
+    }
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let (x, y) = at(first);
+        let _ = writeln!(svg, r##"<circle cx="{:.1}" cy="{:.1}" r="4" fill="#16a34a"/>"##, x, y);
+        let (x, y) = at(last);
+        let _ = writeln!(svg, r##"<circle cx="{:.1}" cy="{:.1}" r="4" fill="#dc2626"/>"##, x, y);
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_steps_accumulates_delays() {
+        let trajectory = Trajectory::from_steps(
+            "bezier",
+            (30.0, 40.0),
+            vec![
+                (10.0, 20.0, Duration::from_millis(8)),
+                (20.0, 30.0, Duration::from_millis(12)),
+                (30.0, 40.0, Duration::ZERO),
+            ],
+        );
+        let times: Vec<f64> = trajectory.points.iter().map(|p| p.t_ms).collect();
+        assert_eq!(times, vec![0.0, 8.0, 20.0]);
+        assert_eq!(trajectory.duration_ms(), 20.0);
+
+        let line = serde_json::to_string(&trajectory).unwrap();
+        assert_eq!(serde_json::from_str::<Trajectory>(&line).unwrap(), trajectory);
+    }
+
+    #[test]
+    fn test_render_trajectory_svg() {
+        let points = [
+            TrajectoryPoint { x: 100.0, y: 200.0, t_ms: 0.0 },
+            TrajectoryPoint { x: 150.0, y: 260.0, t_ms: 10.0 },
+        ];
+        let svg = render_trajectory_svg(&points);
+
+        // Canvas is the bounding box plus padding, points shifted into it
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="90" height="100""#));
+        assert!(svg.contains(r#"points="20.0,20.0 70.0,80.0""#));
+        assert_eq!(svg.matches("<circle").count(), 4);
+        assert!(svg.trim_end().ends_with("</svg>"));
+
+        assert!(render_trajectory_svg(&[]).contains("<polyline points=\"\""));
+    }
+}
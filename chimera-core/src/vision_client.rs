@@ -1,4 +1,6 @@
+use crate::circuit_breaker::CircuitBreaker;
 use crate::error::{ChimeraError, Result};
+use std::sync::Arc;
 use tonic::transport::Channel;
 use tracing::{debug, error};
 
@@ -7,10 +9,34 @@ pub mod vision {
 }
 
 use vision::vision_service_client::VisionServiceClient;
-use vision::{CoordinateRequest, CoordinateResponse};
+use vision::{BatchCoordinateRequest, CoordinateRequest, CoordinateResponse, VerifyRequest};
 
+/// Cloning is cheap: clones share one multiplexed HTTP/2 `Channel`
+#[derive(Clone)]
 pub struct VisionClient {
     client: VisionServiceClient<Channel>,
+    
+    /// Breaker the calls go through, if any (see `with_breaker`)
+    breaker: Option<Arc<CircuitBreaker>>,
+}
+
+/// Error for vision calls skipped because the circuit is open
+pub(crate) fn circuit_open() -> ChimeraError {
+    ChimeraError::VisionUnavailable("circuit open after repeated failures, cooling down".to_string())
+}
+
+/// Map an RPC failure, separating a dead channel from an application error
+/// 
+/// A timed-out call counts as a dead channel: a service too slow to answer
+/// is as unusable as one that's down.
+fn rpc_error(status: tonic::Status) -> ChimeraError {
+    let transport = matches!(status.code(), tonic::Code::Unavailable | tonic::Code::DeadlineExceeded)
+        || std::error::Error::source(&status).is_some_and(|s| s.is::<tonic::transport::Error>());
+    if transport {
+        ChimeraError::VisionUnavailable(format!("gRPC error: {}", status))
+    } else {
+        ChimeraError::Vision(format!("gRPC error: {}", status))
+    }
 }
 
 impl VisionClient {
@@ -18,9 +44,35 @@ impl VisionClient {
         debug!("Connecting to vision service at: {}", addr);
         let client = VisionServiceClient::connect(addr)
             .await
-            .map_err(|e| ChimeraError::Vision(format!("Failed to connect: {}", e)))?;
+            .map_err(|e| ChimeraError::VisionUnavailable(format!("Failed to connect: {}", e)))?;
         
-        Ok(Self { client })
+        Ok(Self { client, breaker: None })
+    }
+
+    /// Route every call through `breaker`: skipped while it's open, and
+    /// channel failures (not application errors) count against it
+    pub fn with_breaker(mut self, breaker: Arc<CircuitBreaker>) -> Self {
+        self.breaker = Some(breaker);
+        self
+    }
+
+    /// Fail fast if the breaker is open
+    fn admit(&self) -> Result<()> {
+        match &self.breaker {
+            Some(breaker) if !breaker.allow() => Err(circuit_open()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Report a call's outcome to the breaker
+    fn settle<T>(&self, result: Result<T>) -> Result<T> {
+        if let Some(breaker) = &self.breaker {
+            match &result {
+                Err(ChimeraError::VisionUnavailable(_)) => breaker.record_failure(),
+                _ => breaker.record_success(),
+            }
+        }
+        result
     }
 
     pub async fn get_coordinates(
@@ -29,18 +81,17 @@ impl VisionClient {
         text_command: String,
     ) -> Result<(i32, i32, f32)> {
         debug!("Requesting coordinates for: {}", text_command);
+        self.admit()?;
         
         let request = tonic::Request::new(CoordinateRequest {
             image,
             text_command,
         });
 
-        let response = self
-            .client
-            .get_coordinates(request)
-            .await
-            .map_err(|e| ChimeraError::Vision(format!("gRPC error: {}", e)))?
-            .into_inner();
+        let started = std::time::Instant::now();
+        let response = self.client.get_coordinates(request).await.map_err(rpc_error);
+        let response = self.settle(response)?.into_inner();
+        crate::metrics::agent().vision_latency.observe_duration(started.elapsed());
 
         if !response.found {
             return Err(ChimeraError::Vision("Element not found".to_string()));
@@ -49,6 +100,62 @@ impl VisionClient {
         Ok((response.x, response.y, response.confidence))
     }
 
+    /// Locate several intents on one screenshot with a single upload
+    /// 
+    /// Returns one entry per command, in order; `None` where the element
+    /// wasn't found. Cheaper than N `get_coordinates` calls when filling a form.
+    pub async fn get_coordinates_batch(
+        &mut self,
+        image: Vec<u8>,
+        text_commands: Vec<String>,
+    ) -> Result<Vec<Option<(i32, i32, f32)>>> {
+        debug!("Requesting coordinates for {} intents: {:?}", text_commands.len(), text_commands);
+        let expected = text_commands.len();
+        self.admit()?;
+        
+        let request = tonic::Request::new(BatchCoordinateRequest { image, text_commands });
+
+        let started = std::time::Instant::now();
+        let response = self.client.get_coordinates_batch(request).await.map_err(rpc_error);
+        let response = self.settle(response)?.into_inner();
+        crate::metrics::agent().vision_latency.observe_duration(started.elapsed());
+
+        if response.results.len() != expected {
+            return Err(ChimeraError::Vision(format!(
+                "Batch returned {} results for {} intents",
+                response.results.len(),
+                expected
+            )));
+        }
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|r| r.found.then_some((r.x, r.y, r.confidence)))
+            .collect())
+    }
+
+    /// Ask the vision service whether `condition` holds on `image`
+    /// 
+    /// Returns `(satisfied, confidence)`.
+    pub async fn verify_condition(
+        &mut self,
+        image: Vec<u8>,
+        condition: String,
+    ) -> Result<(bool, f32)> {
+        debug!("Verifying condition: {}", condition);
+        self.admit()?;
+        
+        let request = tonic::Request::new(VerifyRequest { image, condition });
+
+        let started = std::time::Instant::now();
+        let response = self.client.verify_condition(request).await.map_err(rpc_error);
+        let response = self.settle(response)?.into_inner();
+        crate::metrics::agent().vision_latency.observe_duration(started.elapsed());
+
+        Ok((response.satisfied, response.confidence))
+    }
+
     /// Get coordinates with Region of Interest (ROI) cropping
     /// 
     /// This implements "Attention-Masked Parsing" - uses fast AX tree scan
@@ -99,3 +206,35 @@ impl VisionClient {
         self.get_coordinates(processed_screenshot, instruction).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::BreakerState;
+    use std::time::Duration;
+
+    #[test]
+    fn test_timeouts_count_as_unavailable() {
+        let timed_out = rpc_error(tonic::Status::deadline_exceeded("too slow"));
+        assert!(matches!(timed_out, ChimeraError::VisionUnavailable(_)));
+        assert!(matches!(rpc_error(tonic::Status::invalid_argument("bad image")), ChimeraError::Vision(_)));
+    }
+
+    #[tokio::test]
+    async fn test_channel_failures_trip_the_breaker() {
+        let breaker = Arc::new(CircuitBreaker::new("vision", 2, Duration::from_secs(60)));
+        // Nothing listens on port 1: every call fails at the transport
+        let channel = Channel::from_static("http://127.0.0.1:1").connect_lazy();
+        let mut client = VisionClient { client: VisionServiceClient::new(channel), breaker: None }
+            .with_breaker(Arc::clone(&breaker));
+
+        for _ in 0..2 {
+            let err = client.get_coordinates(Vec::new(), "sign in".to_string()).await.unwrap_err();
+            assert!(matches!(err, ChimeraError::VisionUnavailable(_)));
+        }
+        assert_eq!(breaker.state(), BreakerState::Open);
+
+        let err = client.get_coordinates(Vec::new(), "sign in".to_string()).await.unwrap_err();
+        assert!(matches!(err, ChimeraError::VisionUnavailable(msg) if msg.contains("circuit open")));
+    }
+}
@@ -11,10 +11,12 @@
 /// 
 /// The World Model predicts the future state and assesses risk.
 
-use crate::browser::BrowserSession;
-use anyhow::Result;
+use crate::browser::{visual_hash_distance, BrowserSession};
+use crate::cortex::{AxBounds, AxNode, AxTree, FusionState};
+use crate::error::{ChimeraError, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use tracing::{debug, info, warn};
 
 /// Action candidate proposed by the action generator
@@ -63,6 +65,126 @@ pub enum RiskIndicator {
     InfiniteLoop,
 }
 
+/// Response policy when a detection (captcha, ban page, honeypot) fires mid-mission
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DetectionPolicy {
+    /// Stop the mission and report
+    #[default]
+    Abort,
+    
+    /// Burn the profile, blank and close the session, and ask the orchestrator to respawn
+    Rotate,
+    
+    /// Leave the session open and hand off to a solver
+    Solve,
+}
+
+impl DetectionPolicy {
+    /// Parse a policy name ("abort", "rotate", "solve")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "" | "abort" => Some(Self::Abort),
+            "rotate" => Some(Self::Rotate),
+            "solve" | "solver" => Some(Self::Solve),
+            _ => None,
+        }
+    }
+}
+
+/// Classify the current page as a challenge or block page from its title and URL
+/// 
+/// Cheap heuristic that runs after every action - catches the interstitials that
+/// anti-bot vendors serve (Cloudflare "Just a moment", Akamai "Access Denied", etc.).
+pub fn detect_block_page(title: &str, url: &str) -> Option<RiskIndicator> {
+    let title = title.to_lowercase();
+    let url = url.to_lowercase();
+    
+    const CAPTCHA_TITLES: &[&str] = &[
+        "captcha",
+        "are you a robot",
+        "verify you are human",
+        "just a moment",
+        "human verification",
+    ];
+    const CAPTCHA_URLS: &[&str] = &["/captcha", "challenges.cloudflare.com", "/sorry/index"];
+    const BLOCK_TITLES: &[&str] = &[
+        "access denied",
+        "403 forbidden",
+        "attention required",
+        "request unsuccessful",
+        "you have been blocked",
+    ];
+    
+    if CAPTCHA_TITLES.iter().any(|t| title.contains(t)) || CAPTCHA_URLS.iter().any(|u| url.contains(u)) {
+        return Some(RiskIndicator::CaptchaAppeared);
+    }
+    
+    if BLOCK_TITLES.iter().any(|t| title.contains(t)) {
+        return Some(RiskIndicator::ErrorPage);
+    }
+    
+    None
+}
+
+/// Max Hamming distance at which two screens count as the same learned state
+/// (DBI canvas noise and caret blinks flip a handful of the 64 bits)
+const STATE_MATCH_DISTANCE: u32 = 4;
+
+/// Look up a learned pattern by visual hash, tolerating minor rendering noise
+fn find_similar<'a, T>(patterns: &'a HashMap<String, T>, state_hash: &str) -> Option<&'a T> {
+    patterns.get(state_hash).or_else(|| {
+        patterns
+            .iter()
+            .filter_map(|(hash, pattern)| {
+                visual_hash_distance(hash, state_hash)
+                    .filter(|d| *d <= STATE_MATCH_DISTANCE)
+                    .map(|d| (d, pattern))
+            })
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, pattern)| pattern)
+    })
+}
+
+/// Tuning for the World Model's honeypot heuristics
+#[derive(Debug, Clone)]
+pub struct HoneypotConfig {
+    /// Elements narrower or shorter than this (px) aren't meant for human eyes
+    pub min_size: f64,
+    
+    /// How far (px) an element may hang past the left, top or right edge of
+    /// the viewport before it counts as parked off-screen (below the fold is fine)
+    pub offscreen_margin: f64,
+    
+    /// AX states that hide an element from users
+    pub hidden_states: Vec<String>,
+}
+
+impl Default for HoneypotConfig {
+    fn default() -> Self {
+        Self {
+            min_size: 2.0,
+            offscreen_margin: 8.0,
+            hidden_states: vec!["hidden".to_string(), "invisible".to_string(), "offscreen".to_string()],
+        }
+    }
+}
+
+/// Why an element looks like a trap rather than something a user could click
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoneypotSignal {
+    /// Zero or sub-pixel width/height
+    TooSmall,
+    
+    /// Positioned outside the viewport (`left: -9999px`)
+    OffScreen,
+    
+    /// AX state says it's hidden or invisible
+    Hidden,
+    
+    /// Unnamed link laid over a visible, named element
+    UnlabeledOverlay,
+}
+
 /// World Model - Predicts outcomes before actions
 pub struct WorldModel {
     /// History of state transitions (for learning)
@@ -73,6 +195,9 @@ pub struct WorldModel {
     
     /// Known dangerous patterns
     dangerous_patterns: HashMap<String, DangerousPattern>,
+    
+    /// Honeypot heuristic tuning
+    honeypot: HoneypotConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -84,7 +209,7 @@ struct StateTransition {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-enum Outcome {
+pub enum Outcome {
     Success,
     Failure { reason: String },
     Honeypot,
@@ -106,22 +231,84 @@ struct DangerousPattern {
     description: String,
 }
 
+/// On-disk form of the learned patterns (history is not persisted)
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PersistedPatterns {
+    safe_patterns: HashMap<String, SafePattern>,
+    dangerous_patterns: HashMap<String, DangerousPattern>,
+}
+
 impl WorldModel {
     pub fn new() -> Self {
         Self {
             state_history: Vec::new(),
             safe_patterns: HashMap::new(),
             dangerous_patterns: HashMap::new(),
+            honeypot: HoneypotConfig::default(),
+        }
+    }
+    
+    /// Use custom honeypot heuristics
+    pub fn with_honeypot_config(mut self, honeypot: HoneypotConfig) -> Self {
+        self.honeypot = honeypot;
+        self
+    }
+    
+    /// Load learned patterns saved by `save`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to read world model from {}: {}", path.display(), e)))?;
+        let patterns: PersistedPatterns = serde_json::from_str(&content)
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to parse world model: {}", e)))?;
+        
+        info!(
+            "World Model: Loaded {} safe and {} dangerous patterns from {}",
+            patterns.safe_patterns.len(), patterns.dangerous_patterns.len(), path.display()
+        );
+        Ok(Self {
+            state_history: Vec::new(),
+            safe_patterns: patterns.safe_patterns,
+            dangerous_patterns: patterns.dangerous_patterns,
+            honeypot: HoneypotConfig::default(),
+        })
+    }
+    
+    /// Write the learned safe/dangerous patterns to `path` as JSON
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let patterns = PersistedPatterns {
+            safe_patterns: self.safe_patterns.clone(),
+            dangerous_patterns: self.dangerous_patterns.clone(),
+        };
+        let content = serde_json::to_string_pretty(&patterns)
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to serialize world model: {}", e)))?;
+        
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| ChimeraError::WorldModel(format!("Failed to create {}: {}", parent.display(), e)))?;
         }
+        // Write-then-rename so a crash mid-write can't truncate what we learned
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, content)
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to write {}: {}", tmp.display(), e)))?;
+        std::fs::rename(&tmp, path)
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to replace {}: {}", path.display(), e)))?;
+        
+        debug!("World Model: Saved patterns to {}", path.display());
+        Ok(())
     }
     
     /// Predict the outcome of an action
     /// 
     /// This is the "God Mode" step - we imagine what happens before we act.
+    /// `fusion` gives the honeypot heuristics the AX tree to inspect; without
+    /// it only learned patterns are consulted for clicks.
     pub async fn predict(
         &self,
         current_state: &CurrentState,
         action: &ActionCandidate,
+        fusion: Option<&FusionState>,
     ) -> Result<PredictedState> {
         debug!("World Model: Predicting outcome of action {:?}", action);
         
@@ -129,7 +316,7 @@ impl WorldModel {
         let state_hash = &current_state.visual_hash;
         
         // Check dangerous patterns first
-        if let Some(danger) = self.dangerous_patterns.get(state_hash) {
+        if let Some(danger) = find_similar(&self.dangerous_patterns, state_hash) {
             warn!("World Model: Detected dangerous pattern: {:?}", danger.risk_type);
             return Ok(PredictedState {
                 visual_hash: state_hash.clone(),
@@ -141,7 +328,7 @@ impl WorldModel {
         }
         
         // Check safe patterns
-        if let Some(safe) = self.safe_patterns.get(state_hash) {
+        if let Some(safe) = find_similar(&self.safe_patterns, state_hash) {
             if safe.action.target_coordinates == action.target_coordinates {
                 debug!("World Model: Known safe pattern, low risk");
                 return Ok(PredictedState {
@@ -158,7 +345,7 @@ impl WorldModel {
         // In production, this would use a trained JEPA model
         // For now, we use heuristics based on action type and context
         
-        let predicted = self.simulate_action(current_state, action).await?;
+        let predicted = self.simulate_action(current_state, action, fusion).await?;
         
         Ok(predicted)
     }
@@ -168,6 +355,7 @@ impl WorldModel {
         &self,
         current_state: &CurrentState,
         action: &ActionCandidate,
+        fusion: Option<&FusionState>,
     ) -> Result<PredictedState> {
         let mut risk_indicators = Vec::new();
         let mut risk_score = 0.0;
@@ -181,9 +369,14 @@ impl WorldModel {
                 // - Overlapping with real buttons
                 // - Have suspicious AX tree properties
                 
-                if let Some(ref element_id) = action.target_element {
+                let target = fusion.and_then(|f| {
+                    target_node(&f.ax_tree, action).map(|node| (node, &f.ax_tree))
+                });
+                if let Some((node, tree)) = target {
                     // Check AX tree for suspicious properties
-                    if self.is_suspicious_element(current_state, element_id) {
+                    let signals = self.honeypot_signals(node, tree, current_state.viewport);
+                    if !signals.is_empty() {
+                        warn!("World Model: {} '{}' looks like a honeypot: {:?}", node.role, node.node_id, signals);
                         risk_indicators.push(RiskIndicator::HoneypotDetected);
                         risk_score += 0.5;
                     }
@@ -237,17 +430,49 @@ impl WorldModel {
         })
     }
     
-    /// Check if element is suspicious (potential honeypot)
-    fn is_suspicious_element(&self, state: &CurrentState, element_id: &str) -> bool {
-        // Heuristic: Check if element has suspicious properties
-        // In production, this would analyze the AX tree
+    /// Every reason `node` looks like a honeypot rather than a real control
+    /// 
+    /// `viewport` (width, height) enables the off-screen check.
+    pub fn honeypot_signals(&self, node: &AxNode, tree: &AxTree, viewport: Option<(f64, f64)>) -> Vec<HoneypotSignal> {
+        let config = &self.honeypot;
+        let mut signals = Vec::new();
+        
+        if node.state.iter().any(|s| config.hidden_states.iter().any(|h| h.eq_ignore_ascii_case(s))) {
+            signals.push(HoneypotSignal::Hidden);
+        }
+        
+        let Some(bounds) = &node.bounds else {
+            return signals;
+        };
+        if bounds.width < config.min_size || bounds.height < config.min_size {
+            signals.push(HoneypotSignal::TooSmall);
+        }
+        if let Some((width, _)) = viewport {
+            let margin = config.offscreen_margin;
+            if bounds.x + bounds.width < -margin || bounds.y + bounds.height < -margin || bounds.x > width + margin {
+                signals.push(HoneypotSignal::OffScreen);
+            }
+        }
         
-        // For now, simple heuristics:
-        // - Elements with very small size might be honeypots
-        // - Elements with "invisible" in name/role
-        element_id.contains("invisible") || 
-        element_id.contains("hidden") ||
-        element_id.contains("honeypot")
+        // An unnamed link stacked on a labelled element catches clicks meant for it
+        let named = |n: &AxNode| n.name.as_deref().is_some_and(|name| !name.trim().is_empty());
+        if node.role == "link" && !named(node) {
+            let by_id: HashMap<&str, &AxNode> = tree.nodes.iter().map(|n| (n.node_id.as_str(), n)).collect();
+            let covers_visible = tree.nodes.iter().any(|other| {
+                other.node_id != node.node_id
+                    && named(other)
+                    && other.bounds.as_ref().is_some_and(|b| {
+                        b.width >= config.min_size && b.height >= config.min_size && overlaps(bounds, b)
+                    })
+                    && !is_ancestor(&by_id, &other.node_id, node)
+                    && !is_ancestor(&by_id, &node.node_id, other)
+            });
+            if covers_visible {
+                signals.push(HoneypotSignal::UnlabeledOverlay);
+            }
+        }
+        
+        signals
     }
     
     /// Check if action might trigger navigation
@@ -326,6 +551,48 @@ impl WorldModel {
     }
 }
 
+impl Default for WorldModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The AX node an action is aimed at: `target_element` if given, otherwise
+/// the smallest node whose bounds contain the target coordinates
+/// 
+/// Live snapshots have no bounds; callers resolve them for the candidates
+/// first (`Cortex::resolve_bounds`).
+fn target_node<'a>(tree: &'a AxTree, action: &ActionCandidate) -> Option<&'a AxNode> {
+    if let Some(id) = &action.target_element {
+        return tree.nodes.iter().find(|n| &n.node_id == id);
+    }
+    let (x, y) = action.target_coordinates;
+    tree.nodes
+        .iter()
+        .filter_map(|n| n.bounds.as_ref().map(|b| (n, b)))
+        .filter(|(_, b)| x >= b.x && x <= b.x + b.width && y >= b.y && y <= b.y + b.height)
+        .min_by(|(_, a), (_, b)| (a.width * a.height).total_cmp(&(b.width * b.height)))
+        .map(|(n, _)| n)
+}
+
+fn overlaps(a: &AxBounds, b: &AxBounds) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+/// Whether `ancestor_id` is on `node`'s parent chain
+fn is_ancestor(by_id: &HashMap<&str, &AxNode>, ancestor_id: &str, node: &AxNode) -> bool {
+    let mut parent = node.parent_id.as_deref();
+    // Bounded walk: a malformed tree must not loop forever
+    for _ in 0..by_id.len() {
+        match parent {
+            Some(id) if id == ancestor_id => return true,
+            Some(id) => parent = by_id.get(id).and_then(|n| n.parent_id.as_deref()),
+            None => return false,
+        }
+    }
+    false
+}
+
 /// Current state of the browser
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CurrentState {
@@ -333,11 +600,17 @@ pub struct CurrentState {
     pub url: Option<String>,
     pub title: Option<String>,
     pub ax_tree: Option<String>, // Serialized AX tree
+    
+    /// Viewport (width, height) in CSS px, for off-screen checks
+    #[serde(default)]
+    pub viewport: Option<(f64, f64)>,
 }
 
 impl CurrentState {
     pub fn from_session(session: &BrowserSession) -> Result<Self> {
-        let visual_hash = session.get_visual_hash()?;
+        let visual_hash = session
+            .get_visual_hash()
+            .map_err(|e| ChimeraError::WorldModel(format!("Failed to hash current state: {}", e)))?;
         let url = session.get_url().ok();
         let title = session.get_title().ok();
         
@@ -346,6 +619,7 @@ impl CurrentState {
             url,
             title,
             ax_tree: None, // Would extract from session
+            viewport: Some((session.viewport().width as f64, session.viewport().height as f64)),
         })
     }
 }
@@ -379,3 +653,99 @@ impl SafetyClassifier {
         self.assess(predicted) < threshold
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: &str, role: &str, name: Option<&str>, bounds: (f64, f64, f64, f64)) -> AxNode {
+        let (x, y, width, height) = bounds;
+        AxNode {
+            node_id: id.to_string(),
+            role: role.to_string(),
+            name: name.map(str::to_string),
+            value: None,
+            parent_id: Some("root".to_string()),
+            bounds: Some(AxBounds { x, y, width, height }),
+            state: Vec::new(),
+            frame_id: None,
+            backend_node_id: None,
+        }
+    }
+
+    fn signals(target: &AxNode, others: Vec<AxNode>) -> Vec<HoneypotSignal> {
+        let mut nodes = vec![target.clone()];
+        nodes.extend(others);
+        WorldModel::new().honeypot_signals(target, &AxTree { nodes }, Some((1280.0, 720.0)))
+    }
+
+    #[test]
+    fn test_visible_labelled_button_is_clean() {
+        let button = node("1", "button", Some("Sign in"), (100.0, 100.0, 120.0, 40.0));
+        assert!(signals(&button, vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_sub_pixel_bounds() {
+        let trap = node("1", "button", Some("Submit"), (100.0, 100.0, 1.0, 1.0));
+        assert_eq!(signals(&trap, vec![]), vec![HoneypotSignal::TooSmall]);
+    }
+
+    #[test]
+    fn test_bounds_outside_viewport() {
+        let left = node("1", "link", Some("Admin"), (-9999.0, 10.0, 80.0, 20.0));
+        assert_eq!(signals(&left, vec![]), vec![HoneypotSignal::OffScreen]);
+        
+        let right = node("1", "link", Some("Admin"), (5000.0, 10.0, 80.0, 20.0));
+        assert_eq!(signals(&right, vec![]), vec![HoneypotSignal::OffScreen]);
+        
+        // Below the fold is just a long page
+        let below = node("1", "link", Some("Footer"), (100.0, 3000.0, 80.0, 20.0));
+        assert!(signals(&below, vec![]).is_empty());
+    }
+
+    #[test]
+    fn test_hidden_state() {
+        let mut hidden = node("1", "button", Some("Continue"), (100.0, 100.0, 120.0, 40.0));
+        hidden.state = vec!["focusable".to_string(), "hidden".to_string()];
+        assert_eq!(signals(&hidden, vec![]), vec![HoneypotSignal::Hidden]);
+    }
+
+    #[test]
+    fn test_unlabeled_link_over_visible_element() {
+        let overlay = node("1", "link", None, (90.0, 90.0, 200.0, 60.0));
+        let real = node("2", "button", Some("Buy now"), (100.0, 100.0, 120.0, 40.0));
+        assert_eq!(signals(&overlay, vec![real]), vec![HoneypotSignal::UnlabeledOverlay]);
+        
+        // An icon link whose only overlap is its own labelled child is fine
+        let mut icon = node("3", "image", Some("Cart"), (100.0, 100.0, 24.0, 24.0));
+        icon.parent_id = Some("1".to_string());
+        assert!(signals(&overlay, vec![icon]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_predict_flags_honeypot_under_cursor() {
+        let state = CurrentState {
+            visual_hash: "0".repeat(16),
+            url: None,
+            title: None,
+            ax_tree: None,
+            viewport: Some((1280.0, 720.0)),
+        };
+        let fusion = FusionState {
+            screenshot: Vec::new(),
+            ax_tree: AxTree { nodes: vec![node("1", "button", Some("Next"), (100.0, 100.0, 1.0, 1.0))] },
+            node_to_region: HashMap::new(),
+        };
+        let click = ActionCandidate {
+            action_type: ActionType::Click,
+            target_coordinates: (100.5, 100.5),
+            target_element: None,
+            confidence: 0.9,
+        };
+        
+        let predicted = WorldModel::new().predict(&state, &click, Some(&fusion)).await.unwrap();
+        assert!(matches!(predicted.risk_indicators.as_slice(), [RiskIndicator::HoneypotDetected]));
+        assert!(SafetyClassifier.assess(&predicted) >= 0.7);
+    }
+}